@@ -63,6 +63,11 @@ fn bench_b64_decoding(c: &mut Criterion) {
         b.iter(|| b64_to_bytes(black_box(encoded_binary)))
     });
 
+    c.bench_function("b64_decode_long_bytes", |b| {
+        let bytes = encoded_long.as_bytes().to_vec();
+        b.iter(|| b64_to_bytes(black_box(&bytes)))
+    });
+
     c.bench_function("b64_decode_iter_short", |b| {
         b.iter(|| {
             let mut v = Vec::with_capacity(encoded_short.len() * 3 / 4); // Rough estimate for base64 size
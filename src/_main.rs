@@ -1,3 +1,10 @@
+// stepping code mirrors the doc examples as written, so these lints are not useful here.
+#![allow(
+    clippy::almost_complete_range,
+    clippy::bool_assert_comparison,
+    clippy::unnecessary_cast,
+    clippy::while_let_on_iterator
+)]
 use cj_common::prelude::*;
 use std::time::Instant;
 
@@ -0,0 +1,1710 @@
+//! structs, methods and traits for working with b32 encoding/decoding (RFC 4648.6 and 4648.7)
+//!
+//! # Quick Start
+//!
+//!```
+//! use cj_common::prelude::*;
+//!
+//! let s = "Many hands".to_b32_string();
+//! assert_eq!(s.as_str(), "JVQW46JANBQW4ZDT");
+//!
+//! // decoding is case-insensitive
+//! if let Some(v) = b32_to_bytes("jvqw46janbqw4zdt") {
+//!     assert_eq!(v.as_slice(), b"Many hands");
+//! }
+//!
+//! // extended hex alphabet, which keeps the sort order of the bytes
+//! let s = bytes_to_b32_hex(b"Many hands");
+//! assert_eq!(s.as_str(), "9LGMSU90D1GMSP3J");
+//!
+//! // iter example
+//! let s = "Ma".iter_to_b32().collect::<String>();
+//! assert_eq!(s.as_str(), "JVQQ====");
+//! let v = "JVQQ====".iter_b32_to_byte().collect::<Vec<u8>>();
+//! assert_eq!(v.as_slice(), b"Ma");
+//! ```
+
+use crate::cj_binary::b64::CharToOrdResult;
+use std::slice::Iter;
+
+// general b32 table (RFC 4648.6).
+const B32_TABLE: [char; 32] = [
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '2', '3', '4', '5', '6', '7',
+];
+
+// extended hex b32 table (RFC 4648.7).
+const B32_HEX_TABLE: [char; 32] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I',
+    'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V',
+];
+
+// Crockford's Base32 table, which leaves out I, L, O and U.
+const B32_CROCKFORD_TABLE: [char; 32] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'J',
+    'K', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'V', 'W', 'X', 'Y', 'Z',
+];
+
+// z-base-32 table, ordered so the easier to read and type symbols are the most used.
+const B32_Z_TABLE: [char; 32] = [
+    'y', 'b', 'n', 'd', 'r', 'f', 'g', '8', 'e', 'j', 'k', 'm', 'c', 'p', 'q', 'x', 'o', 't', '1',
+    'u', 'w', 'i', 's', 'z', 'a', '3', '4', '5', 'h', '7', '6', '9',
+];
+
+// Crockford check symbols for the values 32 to 36. the values below 32 use the alphabet.
+const B32_CHECK_EXTRA: [u8; 5] = *b"*~$=U";
+
+// marks a byte that is not part of an alphabet in B32Alphabet's decode table
+const B32_NO_ORD: u8 = 0xFF;
+
+///
+/// a 32 symbol Base32 alphabet.
+///
+/// [`B32Alphabet::STANDARD`] (RFC 4648.6), [`B32Alphabet::HEX`] (RFC 4648.7),
+/// [`B32Alphabet::CROCKFORD`] and [`B32Alphabet::Z_BASE_32`] are provided, and custom alphabets can be built from any 32 printable ASCII chars other than `=` that are
+/// unique ignoring case. letters decode in either case.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// let a = B32Alphabet::STANDARD;
+/// assert_eq!(a.byte_to_ord(b'B'), CharToOrdResult::Ok(1));
+/// assert_eq!(a.byte_to_ord(b'b'), CharToOrdResult::Ok(1));
+///
+/// // too short, and symbols that only differ by case
+/// assert!(B32Alphabet::new("ABC").is_none());
+/// assert!(B32Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ23456a").is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B32Alphabet {
+    pub(crate) encode: [u8; 32],
+    pub(crate) decode: [u8; 256],
+}
+
+impl B32Alphabet {
+    /// general alphabet (RFC 4648.6), `A`-`Z` then `2`-`7`
+    pub const STANDARD: B32Alphabet = match B32Alphabet::from_table(&B32_TABLE) {
+        Some(a) => a,
+        None => panic!("invalid b32 table"),
+    };
+    /// extended hex alphabet (RFC 4648.7), `0`-`9` then `A`-`V`
+    pub const HEX: B32Alphabet = match B32Alphabet::from_table(&B32_HEX_TABLE) {
+        Some(a) => a,
+        None => panic!("invalid b32 table"),
+    };
+    /// Crockford's alphabet, `0`-`9` then `A`-`Z` without `I`, `L`, `O` and `U`.
+    /// `I` and `L` decode as `1`, and `O` decodes as `0`.
+    pub const CROCKFORD: B32Alphabet = match B32Alphabet::from_table(&B32_CROCKFORD_TABLE) {
+        Some(a) => a.alias(b'I', 1).alias(b'L', 1).alias(b'O', 0),
+        None => panic!("invalid b32 table"),
+    };
+    /// z-base-32 alphabet, all lowercase
+    pub const Z_BASE_32: B32Alphabet = match B32Alphabet::from_table(&B32_Z_TABLE) {
+        Some(a) => a,
+        None => panic!("invalid b32 table"),
+    };
+
+    /// builds an alphabet from a table of 32 symbols, where the index of each symbol is its value.
+    ///
+    /// returns None if any symbol is repeated (ignoring case), is not printable ASCII, or is `=`.
+    pub const fn from_table(table: &[char; 32]) -> Option<Self> {
+        let mut encode = [0u8; 32];
+        let mut decode = [B32_NO_ORD; 256];
+        let mut i = 0;
+        while i < 32 {
+            let c = table[i];
+            if !c.is_ascii_graphic() || c == '=' {
+                return None;
+            }
+            let b = c as u8;
+            let lower = b.to_ascii_lowercase();
+            let upper = b.to_ascii_uppercase();
+            if decode[lower as usize] != B32_NO_ORD || decode[upper as usize] != B32_NO_ORD {
+                return None;
+            }
+            encode[i] = b;
+            decode[lower as usize] = i as u8;
+            decode[upper as usize] = i as u8;
+            i += 1;
+        }
+        Some(Self { encode, decode })
+    }
+
+    /// builds an alphabet from a str of exactly 32 symbols. see [`B32Alphabet::from_table`]
+    pub fn new(symbols: &str) -> Option<Self> {
+        let mut table = ['\0'; 32];
+        let mut ct = 0;
+        for c in symbols.chars() {
+            if ct == 32 {
+                return None;
+            }
+            table[ct] = c;
+            ct += 1;
+        }
+        if ct != 32 {
+            return None;
+        }
+        Self::from_table(&table)
+    }
+
+    /// adds a char that decodes the same as one of the alphabet's symbols, in either case.
+    ///
+    /// returns None if `symbol` is not part of the alphabet, or `alias` is not printable ASCII,
+    /// is `=` or already decodes to something.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let a = B32Alphabet::STANDARD.with_alias('0', 'O').unwrap();
+    /// assert_eq!(a.byte_to_ord(b'0'), CharToOrdResult::Ok(14));
+    ///
+    /// assert!(B32Alphabet::STANDARD.with_alias('A', 'B').is_none());
+    /// assert!(B32Alphabet::STANDARD.with_alias('0', '1').is_none());
+    /// ```
+    pub const fn with_alias(self, alias: char, symbol: char) -> Option<Self> {
+        if !alias.is_ascii_graphic() || alias == '=' || !symbol.is_ascii() {
+            return None;
+        }
+        let a = alias as u8;
+        if self.decode[a.to_ascii_lowercase() as usize] != B32_NO_ORD
+            || self.decode[a.to_ascii_uppercase() as usize] != B32_NO_ORD
+        {
+            return None;
+        }
+        let o = self.decode[symbol as usize];
+        if o == B32_NO_ORD {
+            return None;
+        }
+        Some(self.alias(a, o))
+    }
+
+    // maps both cases of alias to ord, without checking either
+    const fn alias(mut self, alias: u8, ord: u8) -> Self {
+        self.decode[alias.to_ascii_lowercase() as usize] = ord;
+        self.decode[alias.to_ascii_uppercase() as usize] = ord;
+        self
+    }
+
+    /// the symbol for a 5 bit value
+    #[inline(always)]
+    pub const fn symbol(&self, ord: u8) -> char {
+        self.encode[(ord & 0x1F) as usize] as char
+    }
+
+    /// looks up a byte of ASCII encoded Base32. `=` is padding, space, tab, `\r` and `\n` are
+    /// whitespace, and anything else outside of the alphabet is invalid.
+    #[inline]
+    pub const fn byte_to_ord(&self, b: u8) -> CharToOrdResult {
+        let o = self.decode[b as usize];
+        if o != B32_NO_ORD {
+            return CharToOrdResult::Ok(o);
+        }
+        match b {
+            b'=' => CharToOrdResult::Pad,
+            // bytes to ignore
+            b' ' | b'\r' | b'\n' | b'\t' => CharToOrdResult::WhiteSpace,
+            // all else are failures
+            _ => CharToOrdResult::Invalid,
+        }
+    }
+}
+
+/// how `=` padding is treated when decoding Base32
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum B32PadMode {
+    /// padding is accepted, but not required
+    Indifferent,
+    /// the final quantum must be padded out to 8 chars
+    Require,
+    /// `=` is treated as an invalid char
+    Forbid,
+}
+
+///
+/// Base32 engine bundling the alphabet, padding, case and strictness options used for encoding
+/// and decoding.
+///
+/// the `bytes_to_b32` and `b32_to_bytes` style functions are shorthand for the
+/// [`B32Config::STANDARD`] and [`B32Config::HEX`] configs.
+///
+/// when decoding, a lenient config (the default) stops at the first problem in the input and returns
+/// what was decoded up to that point. a strict config returns None instead. whitespace is always
+/// skipped, so grouped secrets like `JBSW Y3DP` decode as is.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// // unpadded, lowercase output, as used for TOTP secrets and DNS labels
+/// let cfg = B32Config::STANDARD.with_padding(false).with_lowercase(true);
+/// let s = cfg.encode(b"Ma");
+/// assert_eq!(s.as_str(), "jvqq");
+/// assert_eq!(cfg.decode("JVQQ"), Some(b"Ma".to_vec()));
+///
+/// // strict decoding with required padding
+/// let cfg = B32Config::STANDARD
+///     .with_decode_padding(B32PadMode::Require)
+///     .with_strict(true);
+/// assert_eq!(cfg.decode("JVQQ===="), Some(b"Ma".to_vec()));
+/// assert_eq!(cfg.decode("JVQQ"), None);
+///
+/// // Crockford's Base32 for codes typed by people, with hyphen groups and a check symbol
+/// let cfg = B32Config::CROCKFORD_CHECK.with_hyphen_group(4);
+/// let s = cfg.encode(b"Hello");
+/// assert_eq!(s.as_str(), "91JP-RV3FG");
+/// assert_eq!(cfg.decode("91jp-rv3fs"), None);
+/// assert_eq!(cfg.decode("9ljp rv3fg"), Some(b"Hello".to_vec()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B32Config {
+    alphabet: B32Alphabet,
+    pad: bool,
+    pad_mode: B32PadMode,
+    lower: bool,
+    strict: bool,
+    hyphen_group: usize,
+    skip_hyphens: bool,
+    check: bool,
+}
+
+impl B32Config {
+    /// general alphabet, padded output, lenient decoding
+    pub const STANDARD: B32Config = B32Config::new(B32Alphabet::STANDARD);
+    /// general alphabet, unpadded output, lenient decoding
+    pub const STANDARD_NO_PAD: B32Config = B32Config::STANDARD.with_padding(false);
+    /// extended hex alphabet, padded output, lenient decoding
+    pub const HEX: B32Config = B32Config::new(B32Alphabet::HEX);
+    /// extended hex alphabet, unpadded output, lenient decoding
+    pub const HEX_NO_PAD: B32Config = B32Config::HEX.with_padding(false);
+    /// Crockford's alphabet, unpadded output, hyphens skipped on decode, lenient decoding
+    pub const CROCKFORD: B32Config = B32Config::new(B32Alphabet::CROCKFORD)
+        .with_padding(false)
+        .with_decode_padding(B32PadMode::Forbid)
+        .with_hyphens(true);
+    /// same as [`B32Config::CROCKFORD`], with a mod 37 check symbol and strict decoding
+    pub const CROCKFORD_CHECK: B32Config = B32Config::CROCKFORD.with_check(true).with_strict(true);
+    /// z-base-32 alphabet, unpadded output, lenient decoding
+    pub const Z_BASE_32: B32Config = B32Config::new(B32Alphabet::Z_BASE_32)
+        .with_padding(false)
+        .with_decode_padding(B32PadMode::Forbid);
+
+    /// new config for an alphabet, with padded uppercase output, optional padding on decode,
+    /// and lenient decoding
+    pub const fn new(alphabet: B32Alphabet) -> Self {
+        Self {
+            alphabet,
+            pad: true,
+            pad_mode: B32PadMode::Indifferent,
+            lower: false,
+            strict: false,
+            hyphen_group: 0,
+            skip_hyphens: false,
+            check: false,
+        }
+    }
+
+    /// sets the alphabet
+    pub const fn with_alphabet(mut self, alphabet: B32Alphabet) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+
+    /// sets whether `=` padding is added when encoding
+    pub const fn with_padding(mut self, pad: bool) -> Self {
+        self.pad = pad;
+        self
+    }
+
+    /// sets how `=` padding is treated when decoding
+    pub const fn with_decode_padding(mut self, pad_mode: B32PadMode) -> Self {
+        self.pad_mode = pad_mode;
+        self
+    }
+
+    /// sets whether letters are written in lowercase when encoding. decoding accepts either case.
+    pub const fn with_lowercase(mut self, lower: bool) -> Self {
+        self.lower = lower;
+        self
+    }
+
+    /// sets whether decoding fails outright on bad input (strict), or returns what was decoded
+    /// before the bad input (lenient)
+    pub const fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// sets whether `-` is skipped when decoding, as people often split long codes with it
+    pub const fn with_hyphens(mut self, skip: bool) -> Self {
+        self.skip_hyphens = skip;
+        self
+    }
+
+    /// sets the number of symbols between the `-` written when encoding. a `group` of 0 turns
+    /// grouping off. anything other than 0 also skips hyphens when decoding.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let cfg = B32Config::STANDARD_NO_PAD.with_hyphen_group(4);
+    /// let s = cfg.encode(b"Many hands");
+    /// assert_eq!(s.as_str(), "JVQW-46JA-NBQW-4ZDT");
+    /// assert_eq!(cfg.decode(s.as_str()), Some(b"Many hands".to_vec()));
+    /// ```
+    pub const fn with_hyphen_group(mut self, group: usize) -> Self {
+        self.hyphen_group = group;
+        if group > 0 {
+            self.skip_hyphens = true;
+        }
+        self
+    }
+
+    /// sets whether a Crockford check symbol follows the encoded data. the check symbol is the
+    /// value of the bytes, read as a big-endian number, mod 37. values over 31 use `*`, `~`, `$`, `=`
+    /// and `U`.
+    ///
+    /// the check symbol is always the last symbol of the input when decoding, and a missing or
+    /// wrong one fails with [`B32DecodeErrorKind::InvalidCheck`]. `=` padding can not be used
+    /// with a check symbol.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let cfg = B32Config::CROCKFORD.with_check(true);
+    /// assert_eq!(cfg.encode(&[0x01, 0x00]).as_str(), "0400$");
+    /// assert_eq!(cfg.try_decode("0400$"), Ok(vec![0x01, 0x00]));
+    ///
+    /// let e = cfg.try_decode("0400*").unwrap_err();
+    /// assert_eq!(e, B32DecodeError::new(B32DecodeErrorKind::InvalidCheck, 4));
+    /// ```
+    pub const fn with_check(mut self, check: bool) -> Self {
+        self.check = check;
+        self
+    }
+
+    #[inline]
+    pub const fn alphabet(&self) -> &B32Alphabet {
+        &self.alphabet
+    }
+
+    #[inline]
+    pub const fn padding(&self) -> bool {
+        self.pad
+    }
+
+    #[inline]
+    pub const fn decode_padding(&self) -> B32PadMode {
+        self.pad_mode
+    }
+
+    #[inline]
+    pub const fn is_lowercase(&self) -> bool {
+        self.lower
+    }
+
+    #[inline]
+    pub const fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// symbols per `-` separated group of encoded output, or None if output is not grouped
+    #[inline]
+    pub const fn hyphen_group(&self) -> Option<usize> {
+        if self.hyphen_group > 0 {
+            Some(self.hyphen_group)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub const fn skips_hyphens(&self) -> bool {
+        self.skip_hyphens
+    }
+
+    #[inline]
+    pub const fn has_check(&self) -> bool {
+        self.check
+    }
+
+    /// exact length of the Base32 encoding of `byte_len` bytes with this config
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(B32Config::STANDARD.encoded_len(11), 24);
+    /// assert_eq!(B32Config::STANDARD_NO_PAD.encoded_len(11), 18);
+    /// assert_eq!(B32Config::CROCKFORD_CHECK.with_hyphen_group(4).encoded_len(5), 10);
+    /// ```
+    pub const fn encoded_len(&self, byte_len: usize) -> usize {
+        let rem = byte_len % 5;
+        let mut len = byte_len / 5 * 8;
+        if rem > 0 {
+            len += if self.pad { 8 } else { (rem * 8).div_ceil(5) };
+        }
+        if self.hyphen_group > 0 && len > 0 {
+            len += (len - 1) / self.hyphen_group;
+        }
+        if self.check {
+            len += 1;
+        }
+        len
+    }
+
+    /// upper bound of the number of bytes decoded from `char_len` chars of Base32.
+    /// padding and whitespace make the actual length shorter.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(B32Config::STANDARD.decoded_len_estimate(24), 15);
+    /// ```
+    pub const fn decoded_len_estimate(&self, char_len: usize) -> usize {
+        char_len.div_ceil(8) * 5
+    }
+
+    /// the symbol written for a 5 bit value
+    #[inline(always)]
+    fn symbol(&self, ord: u8) -> u8 {
+        let c = self.alphabet.encode[(ord & 0x1F) as usize];
+        if self.lower {
+            c.to_ascii_lowercase()
+        } else {
+            c
+        }
+    }
+
+    // the check symbol written for the value of the bytes mod 37
+    #[inline]
+    fn check_symbol(&self, rem: u8) -> u8 {
+        if rem < 32 {
+            return self.symbol(rem);
+        }
+        let c = B32_CHECK_EXTRA[(rem - 32) as usize];
+        if self.lower {
+            c.to_ascii_lowercase()
+        } else {
+            c
+        }
+    }
+
+    // the value of a check symbol, in either case
+    #[inline]
+    fn check_ord(&self, b: u8) -> Option<u8> {
+        if let CharToOrdResult::Ok(o) = self.alphabet.byte_to_ord(b) {
+            return Some(o);
+        }
+        let b = b.to_ascii_uppercase();
+        B32_CHECK_EXTRA
+            .iter()
+            .position(|c| *c == b)
+            .map(|i| i as u8 + 32)
+    }
+
+    // compares the check symbol held at index (if any) against the value of the decoded bytes
+    fn verify_check(
+        &self,
+        held: Option<(u8, usize)>,
+        rem: u8,
+        end_index: usize,
+    ) -> Result<(), B32DecodeError> {
+        match held {
+            Some((b, i)) if self.check_ord(b) != Some(rem) => {
+                Err(B32DecodeError::new(B32DecodeErrorKind::InvalidCheck, i))
+            }
+            Some(_) => Ok(()),
+            None => Err(B32DecodeError::new(
+                B32DecodeErrorKind::InvalidCheck,
+                end_index,
+            )),
+        }
+    }
+
+    // looks up a byte of input, with hyphens treated as whitespace when they are skipped
+    #[inline]
+    fn byte_to_ord(&self, b: u8) -> CharToOrdResult {
+        if b == b'-' && self.skip_hyphens {
+            return CharToOrdResult::WhiteSpace;
+        }
+        self.alphabet.byte_to_ord(b)
+    }
+
+    /// converts a slice of bytes into a Base32 encoded String
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let mut s = String::with_capacity(self.encoded_len(bytes.len()));
+        self.encode_each(bytes, |c| s.push(c as char));
+        s
+    }
+
+    // feeds each ASCII char of the encoding, padding, hyphens and check symbol included, to put
+    #[inline]
+    fn encode_each(&self, bytes: &[u8], mut put: impl FnMut(u8)) {
+        let mut col = 0;
+        for chunk in bytes.chunks(5) {
+            for c in B32Chars::from_chunk(self, chunk).as_slice() {
+                if self.hyphen_group > 0 {
+                    if col == self.hyphen_group {
+                        put(b'-');
+                        col = 0;
+                    }
+                    col += 1;
+                }
+                put(*c);
+            }
+        }
+        if self.check {
+            put(self.check_symbol(b32_check_rem(0, bytes)));
+        }
+    }
+
+    /// converts Base32 encoded data, either a str or ASCII bytes, into a vec of bytes.
+    ///
+    /// see [`B32Config::try_decode`] to find out why decoding failed.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(B32Config::STANDARD.decode("JVQW4==="), Some(b"Man".to_vec()));
+    /// assert_eq!(B32Config::STANDARD.decode(b"JVQW4"), Some(b"Man".to_vec()));
+    /// ```
+    pub fn decode(&self, data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+        let data = data.as_ref();
+        let mut v = Vec::<u8>::with_capacity(self.decoded_len_estimate(data.len()));
+        let mut state = B32DecodeState::new();
+        let r = self.decode_each(data, &mut state, |q| v.extend_from_slice(q.as_slice()));
+        match r {
+            Ok(()) if self.strict => Some(v),
+            Err(_) if self.strict => None,
+            r => {
+                if r.is_err() {
+                    // we were able to decode something, even if there was an error. keep what we have.
+                    v.extend_from_slice(state.flush().as_slice());
+                }
+                if v.is_empty() { None } else { Some(v) }
+            }
+        }
+    }
+
+    /// converts Base32 encoded data, either a str or ASCII bytes, into a vec of bytes, failing on the
+    /// first problem in the input regardless of [`B32Config::is_strict`].
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let cfg = B32Config::STANDARD;
+    /// assert_eq!(cfg.try_decode("JVQW4==="), Ok(b"Man".to_vec()));
+    ///
+    /// let e = cfg.try_decode("JVQW1===").unwrap_err();
+    /// assert_eq!(e, B32DecodeError::new(B32DecodeErrorKind::InvalidSymbol, 4));
+    /// ```
+    pub fn try_decode(&self, data: impl AsRef<[u8]>) -> Result<Vec<u8>, B32DecodeError> {
+        let data = data.as_ref();
+        let mut v = Vec::<u8>::with_capacity(self.decoded_len_estimate(data.len()));
+        let mut state = B32DecodeState::new();
+        self.decode_each(data, &mut state, |q| v.extend_from_slice(q.as_slice()))?;
+        Ok(v)
+    }
+
+    // decodes data, feeding each complete quantum to put. stops at the first problem, in which
+    // case state holds the partial quantum the problem was found in.
+    #[inline]
+    fn decode_each(
+        &self,
+        data: &[u8],
+        state: &mut B32DecodeState,
+        mut put: impl FnMut(B32Quantum),
+    ) -> Result<(), B32DecodeError> {
+        let end = data.len();
+        // the check symbol is the last thing in the input that is not skipped
+        let (data, held) = if self.check {
+            match data
+                .iter()
+                .rposition(|b| !self.byte_to_ord(*b).is_whitespace())
+            {
+                Some(i) => (&data[..i], Some((data[i], i))),
+                None => (data, None),
+            }
+        } else {
+            (data, None)
+        };
+        let mut rem = 0;
+        for (i, b) in data.iter().enumerate() {
+            if let Some(q) = state.push(self, self.byte_to_ord(*b), i)? {
+                rem = b32_check_rem(rem, q.as_slice());
+                put(q);
+            }
+        }
+        let q = state.finish(self, data.len())?.unwrap_or_default();
+        if self.check {
+            self.verify_check(held, b32_check_rem(rem, q.as_slice()), end)?;
+        }
+        put(q);
+        Ok(())
+    }
+
+    /// Iterator over a slice of bytes that produces Base32 encoded chars
+    pub fn iter_encode<'a>(&'a self, bytes: &'a [u8]) -> ToBase32Iter<'a> {
+        ToBase32Iter::new_with(bytes.iter(), self)
+    }
+
+    /// Iterator over Base32 encoded data, either a str or ASCII bytes, that produces decoded bytes
+    pub fn iter_decode<'a, T: AsRef<[u8]> + ?Sized>(&'a self, data: &'a T) -> FromBase32Iter<'a> {
+        FromBase32Iter::new_with(data.as_ref().iter(), self)
+    }
+}
+
+impl Default for B32Config {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// the kind of problem found while decoding Base32
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum B32DecodeErrorKind {
+    /// a char that is not part of the alphabet
+    InvalidSymbol,
+    /// misplaced, missing, extra or forbidden `=` padding
+    InvalidPadding,
+    /// a symbol following `=` padding
+    TrailingData,
+    /// the unused trailing bits of the final symbol are not zero
+    NonCanonical,
+    /// the final quantum has 1, 3 or 6 symbols, which can not make whole bytes
+    TruncatedQuantum,
+    /// the Crockford check symbol is missing, or does not match the decoded bytes
+    InvalidCheck,
+}
+
+/// error returned by strict Base32 decoding, with the kind of problem and the byte offset
+/// in the input where it was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B32DecodeError {
+    pub kind: B32DecodeErrorKind,
+    pub index: usize,
+}
+
+impl B32DecodeError {
+    #[inline]
+    pub const fn new(kind: B32DecodeErrorKind, index: usize) -> Self {
+        Self { kind, index }
+    }
+}
+
+impl std::fmt::Display for B32DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self.kind {
+            B32DecodeErrorKind::InvalidSymbol => "invalid Base32 symbol",
+            B32DecodeErrorKind::InvalidPadding => "invalid Base32 padding",
+            B32DecodeErrorKind::TrailingData => "Base32 data after padding",
+            B32DecodeErrorKind::NonCanonical => "non-canonical Base32 trailing bits",
+            B32DecodeErrorKind::TruncatedQuantum => "truncated Base32 quantum",
+            B32DecodeErrorKind::InvalidCheck => "invalid Base32 check symbol",
+        };
+        write!(f, "{} at byte {}", msg, self.index)
+    }
+}
+
+impl std::error::Error for B32DecodeError {}
+
+// folds bytes into the running value mod 37 of everything before them, read as a big-endian number
+#[inline]
+fn b32_check_rem(rem: u8, bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(rem as u32, |r, b| (r * 256 + *b as u32) % 37) as u8
+}
+
+// up to 8 encoded chars of a quantum
+#[derive(Debug, Clone, Copy, Default)]
+struct B32Chars {
+    chars: [u8; 8],
+    len: usize,
+}
+
+impl B32Chars {
+    // encodes up to 5 bytes, padding them out if the config asks for it
+    #[inline]
+    fn from_chunk(config: &B32Config, chunk: &[u8]) -> Self {
+        let mut buf = [0u8; 5];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        // 40 bits, high byte first
+        let bits = buf.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+        let len = (chunk.len() * 8).div_ceil(5);
+        let mut chars = [b'='; 8];
+        for (i, c) in chars.iter_mut().enumerate().take(len) {
+            *c = config.symbol((bits >> (35 - i * 5)) as u8);
+        }
+        Self {
+            chars,
+            len: if config.pad { 8 } else { len },
+        }
+    }
+
+    #[inline(always)]
+    fn as_slice(&self) -> &[u8] {
+        &self.chars[..self.len]
+    }
+}
+
+// up to 5 decoded bytes of a quantum
+#[derive(Debug, Clone, Copy, Default)]
+struct B32Quantum {
+    bytes: [u8; 5],
+    len: usize,
+}
+
+impl B32Quantum {
+    #[inline(always)]
+    fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+// decoding state shared by B32Config::decode and FromBase32Iter. symbols are collected into
+// quantums of 8, and a quantum's bytes are only released once it is known to be complete.
+#[derive(Debug, Clone, Copy)]
+struct B32DecodeState {
+    ords: [u8; 8],
+    ord_ct: usize,
+    pad_ct: usize,
+    // offsets of the first and last symbols of the current quantum
+    first_index: usize,
+    last_index: usize,
+}
+
+impl B32DecodeState {
+    const fn new() -> Self {
+        Self {
+            ords: [0; 8],
+            ord_ct: 0,
+            pad_ct: 0,
+            first_index: 0,
+            last_index: 0,
+        }
+    }
+
+    // ord is the lookup of the byte at offset index in the input
+    #[inline]
+    fn push(
+        &mut self,
+        config: &B32Config,
+        ord: CharToOrdResult,
+        index: usize,
+    ) -> Result<Option<B32Quantum>, B32DecodeError> {
+        let err = |kind| Err(B32DecodeError::new(kind, index));
+        match ord {
+            CharToOrdResult::Ok(b) => {
+                if self.pad_ct > 0 {
+                    return err(B32DecodeErrorKind::TrailingData);
+                }
+                if self.ord_ct == 0 {
+                    self.first_index = index;
+                }
+                self.last_index = index;
+                self.ords[self.ord_ct] = b;
+                self.ord_ct += 1;
+                if self.ord_ct == 8 {
+                    let q = self.flush();
+                    self.ord_ct = 0;
+                    return Ok(Some(q));
+                }
+                Ok(None)
+            }
+            CharToOrdResult::Pad => {
+                if config.pad_mode == B32PadMode::Forbid
+                    || self.ord_ct < 2
+                    || self.ord_ct + self.pad_ct == 8
+                {
+                    return err(B32DecodeErrorKind::InvalidPadding);
+                }
+                self.pad_ct += 1;
+                Ok(None)
+            }
+            CharToOrdResult::WhiteSpace => Ok(None),
+            CharToOrdResult::Invalid => err(B32DecodeErrorKind::InvalidSymbol),
+        }
+    }
+
+    // end of input, end_index being the length of the input.
+    // returns the bytes of the final partial quantum, if any.
+    fn finish(
+        &mut self,
+        config: &B32Config,
+        end_index: usize,
+    ) -> Result<Option<B32Quantum>, B32DecodeError> {
+        // unused low bits of the last symbol, by the number of symbols in the quantum
+        let unused = match self.ord_ct {
+            0 => return Ok(None),
+            2 => 0x03,
+            4 => 0x0F,
+            5 => 0x01,
+            7 => 0x07,
+            _ => {
+                return Err(B32DecodeError::new(
+                    B32DecodeErrorKind::TruncatedQuantum,
+                    self.first_index,
+                ));
+            }
+        };
+        if (self.pad_ct > 0 && self.ord_ct + self.pad_ct != 8)
+            || (self.pad_ct == 0 && config.pad_mode == B32PadMode::Require)
+        {
+            return Err(B32DecodeError::new(
+                B32DecodeErrorKind::InvalidPadding,
+                end_index,
+            ));
+        }
+        if self.ords[self.ord_ct - 1] & unused != 0 {
+            return Err(B32DecodeError::new(
+                B32DecodeErrorKind::NonCanonical,
+                self.last_index,
+            ));
+        }
+        let q = self.flush();
+        self.ord_ct = 0;
+        Ok(Some(q))
+    }
+
+    // the complete bytes held by the current (possibly partial) quantum.
+    #[inline(always)]
+    fn flush(&self) -> B32Quantum {
+        // 8 symbols of 5 bits make 40 bits, high symbol first
+        let bits = self.ords[..self.ord_ct]
+            .iter()
+            .enumerate()
+            .fold(0u64, |acc, (i, o)| acc | (*o as u64) << (35 - i * 5));
+        let mut bytes = [0u8; 5];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (bits >> (32 - i * 8)) as u8;
+        }
+        B32Quantum {
+            bytes,
+            len: self.ord_ct * 5 / 8,
+        }
+    }
+}
+
+///
+/// converts a slice of bytes into a base32 encoded string (RFC 4648.6)
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_b32;
+///
+/// let s2 = bytes_to_b32(b"Many hands");
+/// assert_eq!(s2.as_str(), "JVQW46JANBQW4ZDT");
+/// assert_eq!(bytes_to_b32(b"Ma").as_str(), "JVQQ====");
+/// ```
+pub fn bytes_to_b32(bytes: &[u8]) -> String {
+    B32Config::STANDARD.encode(bytes)
+}
+
+///
+/// converts a slice of bytes into a base32 encoded string using the extended hex alphabet
+/// (RFC 4648.7). the encoding sorts in the same order as the bytes.
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_b32_hex;
+///
+/// let s2 = bytes_to_b32_hex(b"Many hands");
+/// assert_eq!(s2.as_str(), "9LGMSU90D1GMSP3J");
+/// ```
+pub fn bytes_to_b32_hex(bytes: &[u8]) -> String {
+    B32Config::HEX.encode(bytes)
+}
+
+///
+/// converts base32 encoded data, either a str or ASCII bytes, into a vec of bytes.
+/// decoding is case-insensitive, and padding and whitespace are optional.
+///
+/// decoding stops at the first invalid char, returning what was decoded up to that point,
+/// or None if nothing could be decoded. see [`b32_to_bytes_strict`] to reject bad input.
+///
+/// ```
+///  # use crate::cj_common::prelude::b32_to_bytes;
+///
+/// let v = b32_to_bytes("JBSW Y3DP").unwrap();
+/// assert_eq!(v.as_slice(), b"Hello");
+/// ```
+pub fn b32_to_bytes(data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    B32Config::STANDARD.decode(data)
+}
+
+/// extended hex (RFC 4648.7) variant of [`b32_to_bytes`]
+/// ```
+/// # use cj_common::prelude::*;
+/// assert_eq!(b32_hex_to_bytes("9lgmsu90"), Some(b"Many ".to_vec()));
+/// ```
+pub fn b32_hex_to_bytes(data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    B32Config::HEX.decode(data)
+}
+
+/// converts base32 encoded data into a vec of bytes, failing with the kind and location of the
+/// first problem in the input
+/// ```
+/// # use cj_common::prelude::*;
+/// assert_eq!(b32_to_bytes_strict("JVQQ===="), Ok(b"Ma".to_vec()));
+///
+/// let e = b32_to_bytes_strict("JVQR").unwrap_err();
+/// assert_eq!(e, B32DecodeError::new(B32DecodeErrorKind::NonCanonical, 3));
+/// ```
+pub fn b32_to_bytes_strict(data: impl AsRef<[u8]>) -> Result<Vec<u8>, B32DecodeError> {
+    B32Config::STANDARD.try_decode(data)
+}
+
+/// extended hex (RFC 4648.7) variant of [`b32_to_bytes_strict`]
+/// ```
+/// # use cj_common::prelude::*;
+/// assert_eq!(b32_hex_to_bytes_strict("9LGG===="), Ok(b"Ma".to_vec()));
+///
+/// let e = b32_hex_to_bytes_strict("9LGW====").unwrap_err();
+/// assert_eq!(e.kind, B32DecodeErrorKind::InvalidSymbol);
+/// ```
+pub fn b32_hex_to_bytes_strict(data: impl AsRef<[u8]>) -> Result<Vec<u8>, B32DecodeError> {
+    B32Config::HEX.try_decode(data)
+}
+
+///
+/// converts a slice of bytes into Crockford's Base32, without a check symbol. see
+/// [`B32Config::CROCKFORD_CHECK`] to add one.
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_b32_crockford;
+///
+/// let s2 = bytes_to_b32_crockford(b"Many hands");
+/// assert_eq!(s2.as_str(), "9NGPWY90D1GPWS3K");
+/// ```
+pub fn bytes_to_b32_crockford(bytes: &[u8]) -> String {
+    B32Config::CROCKFORD.encode(bytes)
+}
+
+///
+/// converts Crockford's Base32, either a str or ASCII bytes, into a vec of bytes. decoding is
+/// case-insensitive, `I` and `L` are read as `1`, `O` is read as `0`, and hyphens and whitespace
+/// are skipped.
+///
+/// decoding stops at the first invalid char, returning what was decoded up to that point,
+/// or None if nothing could be decoded. see [`b32_crockford_to_bytes_strict`] to reject bad input.
+///
+/// ```
+///  # use crate::cj_common::prelude::b32_crockford_to_bytes;
+///
+/// let v = b32_crockford_to_bytes("9ngp-wy9o-dlgp-ws3k").unwrap();
+/// assert_eq!(v.as_slice(), b"Many hands");
+/// ```
+pub fn b32_crockford_to_bytes(data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    B32Config::CROCKFORD.decode(data)
+}
+
+/// strict variant of [`b32_crockford_to_bytes`]
+/// ```
+/// # use cj_common::prelude::*;
+/// assert_eq!(b32_crockford_to_bytes_strict("9NGG"), Ok(b"Ma".to_vec()));
+///
+/// let e = b32_crockford_to_bytes_strict("9NGU").unwrap_err();
+/// assert_eq!(e, B32DecodeError::new(B32DecodeErrorKind::InvalidSymbol, 3));
+/// ```
+pub fn b32_crockford_to_bytes_strict(data: impl AsRef<[u8]>) -> Result<Vec<u8>, B32DecodeError> {
+    B32Config::CROCKFORD.try_decode(data)
+}
+
+///
+/// converts a slice of bytes into z-base-32
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_zb32;
+///
+/// assert_eq!(bytes_to_zb32(&[0xF0, 0xBF, 0xC7]).as_str(), "6n9hq");
+/// assert_eq!(bytes_to_zb32(&[0xD4, 0x7A, 0x04]).as_str(), "4t7ye");
+/// ```
+pub fn bytes_to_zb32(bytes: &[u8]) -> String {
+    B32Config::Z_BASE_32.encode(bytes)
+}
+
+///
+/// converts z-base-32, either a str or ASCII bytes, into a vec of bytes. decoding is
+/// case-insensitive.
+///
+/// decoding stops at the first invalid char, returning what was decoded up to that point,
+/// or None if nothing could be decoded. see [`zb32_to_bytes_strict`] to reject bad input.
+///
+/// ```
+///  # use crate::cj_common::prelude::zb32_to_bytes;
+///
+/// assert_eq!(zb32_to_bytes("6n9hq"), Some(vec![0xF0, 0xBF, 0xC7]));
+/// ```
+pub fn zb32_to_bytes(data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    B32Config::Z_BASE_32.decode(data)
+}
+
+/// strict variant of [`zb32_to_bytes`]
+/// ```
+/// # use cj_common::prelude::*;
+/// assert_eq!(zb32_to_bytes_strict("4t7ye"), Ok(vec![0xD4, 0x7A, 0x04]));
+///
+/// let e = zb32_to_bytes_strict("4t7yv").unwrap_err();
+/// assert_eq!(e, B32DecodeError::new(B32DecodeErrorKind::InvalidSymbol, 4));
+/// ```
+pub fn zb32_to_bytes_strict(data: impl AsRef<[u8]>) -> Result<Vec<u8>, B32DecodeError> {
+    B32Config::Z_BASE_32.try_decode(data)
+}
+
+///
+/// Iterator for a slice of bytes that returns Base32
+///
+/// ```
+///  # use cj_common::prelude::*;
+/// let s = b"Many hands".as_slice();
+/// let mut s2 = String::new();
+/// for c in s.iter_to_b32() {
+///     s2.push(c);
+/// }
+/// assert_eq!(s2.as_str(), "JVQW46JANBQW4ZDT");
+/// ```
+pub struct ToBase32Iter<'a> {
+    pend: B32Chars,
+    pend_pos: usize,
+    col: usize,
+    held: Option<char>,
+    rem: u8,
+    checked: bool,
+    config: &'a B32Config,
+    inner: Iter<'a, u8>,
+}
+
+impl<'a> ToBase32Iter<'a> {
+    pub fn new(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B32Config::STANDARD)
+    }
+
+    /// extended hex (RFC 4648.7) variant of [`ToBase32Iter::new`]
+    pub fn new_hex(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B32Config::HEX)
+    }
+
+    /// Crockford's Base32 variant of [`ToBase32Iter::new`], without a check symbol
+    pub fn new_crockford(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B32Config::CROCKFORD)
+    }
+
+    /// z-base-32 variant of [`ToBase32Iter::new`]
+    pub fn new_zbase32(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B32Config::Z_BASE_32)
+    }
+
+    /// same as [`ToBase32Iter::new`], but encodes using the given config
+    pub fn new_with(i: Iter<'a, u8>, config: &'a B32Config) -> Self {
+        Self {
+            pend: B32Chars::default(),
+            pend_pos: 0,
+            col: 0,
+            held: None,
+            rem: 0,
+            checked: false,
+            config,
+            inner: i,
+        }
+    }
+
+    // next symbol or padding char, before hyphens and the check symbol are added
+    #[inline]
+    fn next_symbol(&mut self) -> Option<char> {
+        if self.pend_pos == self.pend.len {
+            let mut chunk = [0u8; 5];
+            let mut n = 0;
+            while n < 5 {
+                match self.inner.next() {
+                    Some(b) => chunk[n] = *b,
+                    None => break,
+                }
+                n += 1;
+            }
+            if n == 0 {
+                return None;
+            }
+            self.rem = b32_check_rem(self.rem, &chunk[..n]);
+            self.pend = B32Chars::from_chunk(self.config, &chunk[..n]);
+            self.pend_pos = 0;
+        }
+        let c = self.pend.chars[self.pend_pos];
+        self.pend_pos += 1;
+        Some(c as char)
+    }
+}
+
+impl Iterator for ToBase32Iter<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(c) = self.held.take() {
+            return Some(c);
+        }
+        let Some(c) = self.next_symbol() else {
+            if self.config.check && !self.checked {
+                self.checked = true;
+                return Some(self.config.check_symbol(self.rem) as char);
+            }
+            return None;
+        };
+        let group = self.config.hyphen_group;
+        if group > 0 {
+            if self.col == group {
+                self.col = 1;
+                self.held = Some(c);
+                return Some('-');
+            }
+            self.col += 1;
+        }
+        Some(c)
+    }
+}
+
+pub trait CjToBase32Iter {
+    fn iter_to_b32(&self) -> ToBase32Iter<'_>;
+    fn iter_to_b32_hex(&self) -> ToBase32Iter<'_>;
+    fn iter_to_b32_with<'a>(&'a self, config: &'a B32Config) -> ToBase32Iter<'a>;
+}
+
+impl<T: AsRef<[u8]> + ?Sized> CjToBase32Iter for T {
+    /// Iterator for anything holding bytes that produces Base32 encoded chars
+    /// ```
+    /// # use cj_common::prelude::CjToBase32Iter;
+    /// let s2 = "Many hands".iter_to_b32().collect::<String>();
+    /// assert_eq!(s2.as_str(), "JVQW46JANBQW4ZDT");
+    /// ```
+    fn iter_to_b32(&self) -> ToBase32Iter<'_> {
+        ToBase32Iter::new(self.as_ref().iter())
+    }
+    /// Iterator for anything holding bytes that produces extended hex Base32 encoded chars
+    /// ```
+    /// # use cj_common::prelude::CjToBase32Iter;
+    /// let s2 = [0x00u8, 0xFF].iter_to_b32_hex().collect::<String>();
+    /// assert_eq!(s2.as_str(), "03VG====");
+    /// ```
+    fn iter_to_b32_hex(&self) -> ToBase32Iter<'_> {
+        ToBase32Iter::new_hex(self.as_ref().iter())
+    }
+    /// Iterator for anything holding bytes that produces Base32 encoded chars using the given config
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let s2 = b"Ma".iter_to_b32_with(&B32Config::STANDARD_NO_PAD).collect::<String>();
+    /// assert_eq!(s2.as_str(), "JVQQ");
+    /// ```
+    fn iter_to_b32_with<'a>(&'a self, config: &'a B32Config) -> ToBase32Iter<'a> {
+        ToBase32Iter::new_with(self.as_ref().iter(), config)
+    }
+}
+
+///
+/// Iterator for Base32 encoded data, either a str or ASCII bytes, that returns decoded bytes
+///
+/// ```
+///  # use cj_common::prelude::*;
+/// let mut v = Vec::new();
+/// for b in "JVQW46JANBQW4ZDT".iter_b32_to_byte() {
+///     v.push(b);
+/// }
+/// assert_eq!(v.as_slice(), b"Many hands");
+/// ```
+pub struct FromBase32Iter<'a> {
+    state: B32DecodeState,
+    pend: B32Quantum,
+    pend_pos: usize,
+    done: bool,
+    index: usize,
+    error: Option<B32DecodeError>,
+    held: Option<(u8, usize)>,
+    rem: u8,
+    config: &'a B32Config,
+    inner: Iter<'a, u8>,
+}
+
+impl<'a> FromBase32Iter<'a> {
+    pub fn new(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B32Config::STANDARD)
+    }
+
+    /// extended hex (RFC 4648.7) variant of [`FromBase32Iter::new`]
+    pub fn new_hex(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B32Config::HEX)
+    }
+
+    /// Crockford's Base32 variant of [`FromBase32Iter::new`], without a check symbol
+    pub fn new_crockford(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B32Config::CROCKFORD)
+    }
+
+    /// z-base-32 variant of [`FromBase32Iter::new`]
+    pub fn new_zbase32(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B32Config::Z_BASE_32)
+    }
+
+    /// same as [`FromBase32Iter::new`], but decodes using the given config.
+    ///
+    /// a strict config ends the iterator at the first problem in the input, without returning
+    /// the bytes of the quantum it was found in.
+    ///
+    /// with a check symbol, each symbol is held back until the next one is read, and the check is
+    /// only made at the end of the input. a mismatch is reported by [`FromBase32Iter::error`], and a
+    /// strict config leaves out the final quantum, but the bytes before it have already been returned.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut it = "91JP-RV3FG".iter_b32_to_byte_with(&B32Config::CROCKFORD_CHECK);
+    /// assert_eq!(it.by_ref().collect::<Vec<u8>>().as_slice(), b"Hello");
+    /// assert_eq!(it.error(), None);
+    /// ```
+    pub fn new_with(i: Iter<'a, u8>, config: &'a B32Config) -> Self {
+        Self {
+            state: B32DecodeState::new(),
+            pend: B32Quantum::default(),
+            pend_pos: 0,
+            done: false,
+            index: 0,
+            error: None,
+            held: None,
+            rem: 0,
+            config,
+            inner: i,
+        }
+    }
+
+    /// the problem that ended decoding, if any. byte offsets are relative to the start of the
+    /// str or bytes the iterator was created from.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut it = "JVQW4===JVQW4===".iter_b32_to_byte();
+    /// let v = it.by_ref().collect::<Vec<u8>>();
+    /// assert_eq!(v.as_slice(), b"Man");
+    /// assert_eq!(
+    ///     it.error(),
+    ///     Some(B32DecodeError::new(B32DecodeErrorKind::TrailingData, 8))
+    /// );
+    /// ```
+    pub fn error(&self) -> Option<B32DecodeError> {
+        self.error
+    }
+
+    #[inline]
+    fn next_pending(&mut self) -> Option<u8> {
+        if self.pend_pos < self.pend.len {
+            let b = self.pend.bytes[self.pend_pos];
+            self.pend_pos += 1;
+            return Some(b);
+        }
+        None
+    }
+
+    #[inline]
+    fn start_quantum(&mut self, q: B32Quantum) -> Option<u8> {
+        self.pend = q;
+        self.pend_pos = 0;
+        self.next_pending()
+    }
+
+    // decoding stopped at a problem in the input. a lenient config keeps what it can.
+    fn fail(&mut self, e: B32DecodeError) -> Option<u8> {
+        self.done = true;
+        self.error = Some(e);
+        if self.config.strict {
+            None
+        } else {
+            self.start_quantum(self.state.flush())
+        }
+    }
+}
+
+impl Iterator for FromBase32Iter<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(b) = self.next_pending() {
+            return Some(b);
+        }
+        if self.done {
+            return None;
+        }
+        while let Some(b) = self.inner.next() {
+            let mut i = self.index;
+            self.index += 1;
+            let mut ord = self.config.byte_to_ord(*b);
+            if self.config.check && !ord.is_whitespace() {
+                // the check symbol is the last symbol, so a symbol is only decoded once another follows
+                match self.held.replace((*b, i)) {
+                    Some((hb, hi)) => {
+                        ord = self.config.byte_to_ord(hb);
+                        i = hi;
+                    }
+                    None => continue,
+                }
+            }
+            match self.state.push(self.config, ord, i) {
+                Ok(Some(q)) => {
+                    self.rem = b32_check_rem(self.rem, q.as_slice());
+                    return self.start_quantum(q);
+                }
+                Ok(None) => {}
+                Err(e) => return self.fail(e),
+            }
+        }
+
+        self.done = true;
+        let end = self.held.map_or(self.index, |(_, i)| i);
+        match self.state.finish(self.config, end) {
+            Ok(q) => {
+                let q = q.unwrap_or_default();
+                if self.config.check {
+                    let rem = b32_check_rem(self.rem, q.as_slice());
+                    if let Err(e) = self.config.verify_check(self.held, rem, self.index) {
+                        self.error = Some(e);
+                        if self.config.strict {
+                            return None;
+                        }
+                    }
+                }
+                self.start_quantum(q)
+            }
+            Err(e) => self.fail(e),
+        }
+    }
+}
+
+pub trait CjFromBase32Iter {
+    fn iter_b32_to_byte(&self) -> FromBase32Iter<'_>;
+    fn iter_b32_hex_to_byte(&self) -> FromBase32Iter<'_>;
+    fn iter_b32_to_byte_with<'a>(&'a self, config: &'a B32Config) -> FromBase32Iter<'a>;
+}
+
+impl<T: AsRef<[u8]> + ?Sized> CjFromBase32Iter for T {
+    /// Iterator for decoding Base32 held in a str or as ASCII bytes to bytes
+    /// ```
+    /// # use cj_common::prelude::CjFromBase32Iter;
+    /// let v = "jbsw y3dp".iter_b32_to_byte().collect::<Vec<u8>>();
+    /// assert_eq!(v.as_slice(), b"Hello");
+    /// ```
+    fn iter_b32_to_byte(&self) -> FromBase32Iter<'_> {
+        FromBase32Iter::new(self.as_ref().iter())
+    }
+    /// Iterator for decoding extended hex Base32 held in a str or as ASCII bytes to bytes
+    /// ```
+    /// # use cj_common::prelude::CjFromBase32Iter;
+    /// let v = "03VG====".iter_b32_hex_to_byte().collect::<Vec<u8>>();
+    /// assert_eq!(v, vec![0x00u8, 0xFF]);
+    /// ```
+    fn iter_b32_hex_to_byte(&self) -> FromBase32Iter<'_> {
+        FromBase32Iter::new_hex(self.as_ref().iter())
+    }
+    /// Iterator for decoding Base32 held in a str or as ASCII bytes to bytes using the given config
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let cfg = B32Config::STANDARD.with_strict(true);
+    /// let v = "JVQQ====".iter_b32_to_byte_with(&cfg).collect::<Vec<u8>>();
+    /// assert_eq!(v.as_slice(), b"Ma");
+    /// ```
+    fn iter_b32_to_byte_with<'a>(&'a self, config: &'a B32Config) -> FromBase32Iter<'a> {
+        FromBase32Iter::new_with(self.as_ref().iter(), config)
+    }
+}
+
+pub trait CjToBase32 {
+    fn to_b32_string(&self) -> String;
+    fn to_b32_hex_string(&self) -> String;
+    fn to_b32_string_with(&self, config: &B32Config) -> String;
+}
+
+impl<T: AsRef<[u8]> + ?Sized> CjToBase32 for T {
+    /// converts anything holding bytes (`&str`, `String`, `Vec<u8>`, `[u8; N]`, ...) to a Base32
+    /// encoded String
+    /// ```
+    /// # use cj_common::prelude::CjToBase32;
+    /// let s2 = "Many hands".to_b32_string();
+    /// assert_eq!(s2.as_str(), "JVQW46JANBQW4ZDT");
+    /// ```
+    fn to_b32_string(&self) -> String {
+        bytes_to_b32(self.as_ref())
+    }
+    /// converts anything holding bytes to an extended hex Base32 encoded String
+    /// ```
+    /// # use cj_common::prelude::CjToBase32;
+    /// let s2 = vec![0x00u8, 0xFF].to_b32_hex_string();
+    /// assert_eq!(s2.as_str(), "03VG====");
+    /// ```
+    fn to_b32_hex_string(&self) -> String {
+        bytes_to_b32_hex(self.as_ref())
+    }
+    /// converts anything holding bytes to a Base32 encoded String using the given config
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let cfg = B32Config::HEX_NO_PAD.with_lowercase(true);
+    /// let s2 = "Ma".to_b32_string_with(&cfg);
+    /// assert_eq!(s2.as_str(), "9lgg");
+    /// ```
+    fn to_b32_string_with(&self, config: &B32Config) -> String {
+        config.encode(self.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4648.10 test vectors
+    const VECTORS: [(&str, &str, &str); 7] = [
+        ("", "", ""),
+        ("f", "MY======", "CO======"),
+        ("fo", "MZXQ====", "CPNG===="),
+        ("foo", "MZXW6===", "CPNMU==="),
+        ("foob", "MZXW6YQ=", "CPNMUOG="),
+        ("fooba", "MZXW6YTB", "CPNMUOJ1"),
+        ("foobar", "MZXW6YTBOI======", "CPNMUOJ1E8======"),
+    ];
+
+    #[test]
+    fn test_rfc_vectors() {
+        for (plain, b32, b32_hex) in VECTORS {
+            assert_eq!(bytes_to_b32(plain.as_bytes()), b32);
+            assert_eq!(bytes_to_b32_hex(plain.as_bytes()), b32_hex);
+            assert_eq!(plain.iter_to_b32().collect::<String>(), b32);
+            assert_eq!(plain.iter_to_b32_hex().collect::<String>(), b32_hex);
+            assert_eq!(b32_to_bytes_strict(b32).unwrap(), plain.as_bytes());
+            assert_eq!(b32_hex_to_bytes_strict(b32_hex).unwrap(), plain.as_bytes());
+            assert_eq!(
+                b32.iter_b32_to_byte().collect::<Vec<u8>>(),
+                plain.as_bytes()
+            );
+            assert_eq!(
+                b32_hex.iter_b32_hex_to_byte().collect::<Vec<u8>>(),
+                plain.as_bytes()
+            );
+            // unpadded and lowercase input decodes the same
+            let bare = b32.trim_end_matches('=').to_ascii_lowercase();
+            assert_eq!(b32_to_bytes_strict(&bare).unwrap(), plain.as_bytes());
+            assert_eq!(
+                B32Config::STANDARD_NO_PAD.encode(plain.as_bytes()),
+                b32.trim_end_matches('=')
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data = (0..=255u8).collect::<Vec<u8>>();
+        for cfg in [
+            B32Config::STANDARD,
+            B32Config::STANDARD_NO_PAD,
+            B32Config::HEX.with_lowercase(true),
+            B32Config::HEX_NO_PAD,
+        ] {
+            for n in 0..20 {
+                let s = cfg.encode(&data[..n]);
+                assert_eq!(s.len(), cfg.encoded_len(n));
+                assert_eq!(s, cfg.iter_encode(&data[..n]).collect::<String>());
+                assert_eq!(cfg.try_decode(&s).unwrap(), &data[..n]);
+                assert_eq!(cfg.iter_decode(&s).collect::<Vec<u8>>(), &data[..n]);
+            }
+            let s = cfg.encode(&data);
+            assert_eq!(cfg.decode(&s).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let e = |kind, index| Err(B32DecodeError::new(kind, index));
+        assert_eq!(
+            b32_to_bytes_strict("MZXW6YQ1"),
+            e(B32DecodeErrorKind::InvalidSymbol, 7)
+        );
+        assert_eq!(
+            b32_to_bytes_strict("M"),
+            e(B32DecodeErrorKind::TruncatedQuantum, 0)
+        );
+        assert_eq!(
+            b32_to_bytes_strict("MZXW6YTBMZX"),
+            e(B32DecodeErrorKind::TruncatedQuantum, 8)
+        );
+        assert_eq!(
+            b32_to_bytes_strict("MZX====="),
+            e(B32DecodeErrorKind::TruncatedQuantum, 0)
+        );
+        assert_eq!(
+            b32_to_bytes_strict("MZ==="),
+            e(B32DecodeErrorKind::InvalidPadding, 5)
+        );
+        assert_eq!(
+            b32_to_bytes_strict("M======="),
+            e(B32DecodeErrorKind::InvalidPadding, 1)
+        );
+        assert_eq!(
+            b32_to_bytes_strict("MY======MY"),
+            e(B32DecodeErrorKind::TrailingData, 8)
+        );
+        assert_eq!(
+            b32_to_bytes_strict("MZ======"),
+            e(B32DecodeErrorKind::NonCanonical, 1)
+        );
+
+        let cfg = B32Config::STANDARD.with_decode_padding(B32PadMode::Require);
+        assert_eq!(
+            cfg.try_decode("MY"),
+            e(B32DecodeErrorKind::InvalidPadding, 2)
+        );
+        let cfg = B32Config::STANDARD.with_decode_padding(B32PadMode::Forbid);
+        assert_eq!(
+            cfg.try_decode("MY=="),
+            e(B32DecodeErrorKind::InvalidPadding, 2)
+        );
+
+        // lenient decoding keeps what came before the problem
+        assert_eq!(b32_to_bytes("MZXW6YTB!"), Some(b"fooba".to_vec()));
+        assert_eq!(b32_to_bytes("MZXW6!"), Some(b"foo".to_vec()));
+        assert_eq!(b32_to_bytes("!"), None);
+        let cfg = B32Config::STANDARD.with_strict(true);
+        assert_eq!(cfg.decode("MZXW6YTB!"), None);
+        assert_eq!(cfg.iter_decode("MZXW6YTB!").collect::<Vec<u8>>(), b"fooba");
+        assert_eq!(cfg.iter_decode("MZXW6!").count(), 0);
+
+        let s = format!(
+            "{}",
+            B32DecodeError::new(B32DecodeErrorKind::InvalidSymbol, 3)
+        );
+        assert_eq!(s.as_str(), "invalid Base32 symbol at byte 3");
+    }
+
+    #[test]
+    fn test_crockford() {
+        assert_eq!(bytes_to_b32_crockford(b"Hello"), "91JPRV3F");
+        // folded letters, either case
+        assert_eq!(
+            b32_crockford_to_bytes_strict("9ljprv3f"),
+            Ok(b"Hello".to_vec())
+        );
+        assert_eq!(
+            b32_crockford_to_bytes_strict("9IJPRV3F"),
+            Ok(b"Hello".to_vec())
+        );
+        assert_eq!(
+            b32_crockford_to_bytes_strict("00"),
+            b32_crockford_to_bytes_strict("oO")
+        );
+        assert_eq!(
+            b32_crockford_to_bytes_strict("91JPRV3F=="),
+            Err(B32DecodeError::new(B32DecodeErrorKind::InvalidPadding, 8))
+        );
+        assert_eq!(
+            b32_crockford_to_bytes_strict("91JPURV3F"),
+            Err(B32DecodeError::new(B32DecodeErrorKind::InvalidSymbol, 4))
+        );
+
+        let data = (0..40u8).map(|x| x.wrapping_mul(97)).collect::<Vec<u8>>();
+        for cfg in [
+            B32Config::CROCKFORD,
+            B32Config::CROCKFORD_CHECK,
+            B32Config::CROCKFORD_CHECK.with_hyphen_group(4),
+            B32Config::CROCKFORD
+                .with_hyphen_group(5)
+                .with_lowercase(true),
+            B32Config::CROCKFORD_CHECK
+                .with_hyphen_group(3)
+                .with_lowercase(true),
+        ] {
+            for n in 0..data.len() {
+                let s = cfg.encode(&data[..n]);
+                assert_eq!(s.len(), cfg.encoded_len(n));
+                assert_eq!(s, cfg.iter_encode(&data[..n]).collect::<String>());
+                assert!(!s.starts_with('-') && !s.ends_with('-'));
+                assert_eq!(cfg.try_decode(&s).unwrap(), &data[..n]);
+                let mut it = cfg.iter_decode(&s);
+                assert_eq!(it.by_ref().collect::<Vec<u8>>(), &data[..n]);
+                assert_eq!(it.error(), None);
+                // whitespace around the check symbol is skipped
+                let spaced = format!(" {} \n", s);
+                assert_eq!(cfg.try_decode(&spaced).unwrap(), &data[..n]);
+                assert_eq!(cfg.iter_decode(&spaced).collect::<Vec<u8>>(), &data[..n]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_crockford_check() {
+        let cfg = B32Config::CROCKFORD_CHECK;
+        let e = |kind, index| Err(B32DecodeError::new(kind, index));
+        // values 32 to 36 use the extra check symbols
+        let mut seen = [false; 37];
+        for x in 0..=255u8 {
+            let s = cfg.encode(&[x]);
+            let c = s.as_bytes()[2];
+            seen[(x % 37) as usize] = true;
+            if x % 37 >= 32 {
+                assert_eq!(c, b"*~$=U"[(x % 37 - 32) as usize]);
+            }
+            assert_eq!(cfg.try_decode(&s), Ok(vec![x]));
+            assert_eq!(cfg.try_decode(s.to_ascii_lowercase()), Ok(vec![x]));
+        }
+        assert!(seen.iter().all(|s| *s));
+
+        assert_eq!(cfg.encode(b""), "0");
+        assert_eq!(cfg.try_decode("0"), Ok(vec![]));
+        assert_eq!(cfg.try_decode(""), e(B32DecodeErrorKind::InvalidCheck, 0));
+        assert_eq!(
+            cfg.try_decode(" - "),
+            e(B32DecodeErrorKind::InvalidCheck, 3)
+        );
+        assert_eq!(
+            cfg.try_decode("91JPRV3FH"),
+            e(B32DecodeErrorKind::InvalidCheck, 8)
+        );
+        assert_eq!(
+            cfg.try_decode("91JPRV3F!"),
+            e(B32DecodeErrorKind::InvalidCheck, 8)
+        );
+        // a dropped symbol is caught by the trailing bits or the check
+        assert_eq!(
+            cfg.try_decode("91JPRV3G"),
+            e(B32DecodeErrorKind::NonCanonical, 6)
+        );
+        assert!(cfg.try_decode("91JPV3FG").is_err());
+        assert_eq!(cfg.decode("91JPRV3FH"), None);
+
+        // the iterator reports a mismatch at the end, leaving out the final quantum when strict
+        let mut it = cfg.iter_decode("91JPRV3F00H");
+        assert_eq!(it.by_ref().collect::<Vec<u8>>(), b"Hello");
+        assert_eq!(
+            it.error(),
+            Some(B32DecodeError::new(B32DecodeErrorKind::InvalidCheck, 10))
+        );
+        let lenient = cfg.with_strict(false);
+        let mut it = lenient.iter_decode("91JPRV3F00H");
+        assert_eq!(it.by_ref().collect::<Vec<u8>>(), b"Hello\0");
+        assert_eq!(
+            it.error(),
+            Some(B32DecodeError::new(B32DecodeErrorKind::InvalidCheck, 10))
+        );
+        let mut it = cfg.iter_decode("");
+        assert_eq!(it.next(), None);
+        assert_eq!(
+            it.error(),
+            Some(B32DecodeError::new(B32DecodeErrorKind::InvalidCheck, 0))
+        );
+    }
+
+    #[test]
+    fn test_zbase32() {
+        assert_eq!(bytes_to_zb32(&[0x00]), "yy");
+        assert_eq!(bytes_to_zb32(b"Hello"), "jb1sa5dx");
+        let data = (0..=255u8).collect::<Vec<u8>>();
+        for n in 0..20 {
+            let s = bytes_to_zb32(&data[..n]);
+            assert!(!s.contains('='));
+            assert_eq!(
+                s,
+                ToBase32Iter::new_zbase32(data[..n].iter()).collect::<String>()
+            );
+            assert_eq!(zb32_to_bytes_strict(&s).unwrap(), &data[..n]);
+            assert_eq!(
+                zb32_to_bytes_strict(s.to_ascii_uppercase()).unwrap(),
+                &data[..n]
+            );
+            assert_eq!(
+                FromBase32Iter::new_zbase32(s.as_bytes().iter()).collect::<Vec<u8>>(),
+                &data[..n]
+            );
+        }
+        assert_eq!(
+            zb32_to_bytes_strict("yy=="),
+            Err(B32DecodeError::new(B32DecodeErrorKind::InvalidPadding, 2))
+        );
+        assert_eq!(zb32_to_bytes("jb1sa5dx2"), Some(b"Hello".to_vec()));
+    }
+
+    #[test]
+    fn test_b32_docs() {
+        let secret = "JBSW Y3DP EHPK 3PXP";
+        let v = b32_to_bytes(secret).unwrap();
+        assert_eq!(v.as_slice(), b"Hello!\xDE\xAD\xBE\xEF");
+        let cfg = B32Config::STANDARD_NO_PAD;
+        assert_eq!(v.to_b32_string_with(&cfg), "JBSWY3DPEHPK3PXP");
+        assert!(B32Alphabet::new("0123456789abcdefghjkmnpqrstvwxyz").is_some());
+    }
+}
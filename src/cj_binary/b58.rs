@@ -0,0 +1,520 @@
+//! structs, methods and traits for working with b58 and Base58Check encoding/decoding
+//!
+//! # Quick Start
+//!
+//!```
+//! use cj_common::prelude::*;
+//!
+//! let s = "Hello World!".to_b58_string();
+//! assert_eq!(s.as_str(), "2NEpo7TZRRrLZSi2U");
+//! assert_eq!(b58_to_bytes("2NEpo7TZRRrLZSi2U"), Some(b"Hello World!".to_vec()));
+//!
+//! // Base58Check appends a 4 byte double SHA-256 checksum, verified on decode
+//! let s = bytes_to_b58check(&[0x00, 0x01, 0x02]);
+//! assert_eq!(b58check_to_bytes(&s), Ok(vec![0x00, 0x01, 0x02]));
+//!
+//! // leading zero bytes become leading '1's
+//! assert_eq!(bytes_to_b58(&[0, 0, 1]).as_str(), "112");
+//! ```
+
+use crate::cj_binary::sha256::sha256d;
+
+// Bitcoin b58 table, which leaves out 0, O, I and l.
+const B58_BITCOIN_TABLE: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// Ripple b58 table.
+const B58_RIPPLE_TABLE: &[u8; 58] = b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz";
+
+// Flickr b58 table, the Bitcoin table with the cases swapped.
+const B58_FLICKR_TABLE: &[u8; 58] = b"123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ";
+
+// marks a byte that is not part of an alphabet in B58Alphabet's decode table
+const B58_NO_ORD: u8 = 0xFF;
+
+// 58^5, the largest power of 58 that fits in a u32. encoding works on digits of this size.
+const B58_POW5: u64 = 58 * 58 * 58 * 58 * 58;
+
+// bytes of the Base58Check checksum
+const B58_CHECK_LEN: usize = 4;
+
+///
+/// a 58 symbol Base58 alphabet.
+///
+/// [`B58Alphabet::BITCOIN`], [`B58Alphabet::RIPPLE`] and [`B58Alphabet::FLICKR`] are provided, and
+/// custom alphabets can be built from any 58 unique, printable ASCII chars. the alphabet also
+/// does the encoding and decoding, as Base58 has no other options.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// let a = B58Alphabet::RIPPLE;
+/// let s = a.encode(b"Hello World!");
+/// assert_eq!(s.as_str(), "p4NFofTZRRiLZS5p7");
+/// assert_eq!(a.decode(&s), Some(b"Hello World!".to_vec()));
+///
+/// // too short, and duplicate symbols
+/// assert!(B58Alphabet::new("ABC").is_none());
+/// assert!(B58Alphabet::new(&"A".repeat(58)).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B58Alphabet {
+    encode: [u8; 58],
+    decode: [u8; 256],
+}
+
+impl B58Alphabet {
+    /// Bitcoin alphabet, also used by IPFS and most other Base58 users
+    pub const BITCOIN: B58Alphabet = match B58Alphabet::from_bytes(B58_BITCOIN_TABLE) {
+        Some(a) => a,
+        None => panic!("invalid b58 table"),
+    };
+    /// Ripple alphabet
+    pub const RIPPLE: B58Alphabet = match B58Alphabet::from_bytes(B58_RIPPLE_TABLE) {
+        Some(a) => a,
+        None => panic!("invalid b58 table"),
+    };
+    /// Flickr alphabet
+    pub const FLICKR: B58Alphabet = match B58Alphabet::from_bytes(B58_FLICKR_TABLE) {
+        Some(a) => a,
+        None => panic!("invalid b58 table"),
+    };
+
+    // builds an alphabet from 58 ASCII bytes, where the index of each symbol is its value
+    const fn from_bytes(table: &[u8; 58]) -> Option<Self> {
+        let mut encode = [0u8; 58];
+        let mut decode = [B58_NO_ORD; 256];
+        let mut i = 0;
+        while i < 58 {
+            let b = table[i];
+            if !b.is_ascii_graphic() || decode[b as usize] != B58_NO_ORD {
+                return None;
+            }
+            encode[i] = b;
+            decode[b as usize] = i as u8;
+            i += 1;
+        }
+        Some(Self { encode, decode })
+    }
+
+    /// builds an alphabet from a str of exactly 58 symbols, where the index of each symbol is its value.
+    ///
+    /// returns None if any symbol is repeated or is not printable ASCII.
+    pub fn new(symbols: &str) -> Option<Self> {
+        let table: &[u8; 58] = symbols.as_bytes().try_into().ok()?;
+        Self::from_bytes(table)
+    }
+
+    /// the symbol for a value below 58
+    #[inline(always)]
+    pub const fn symbol(&self, ord: u8) -> char {
+        self.encode[ord as usize % 58] as char
+    }
+
+    /// the value of a symbol, or None if it is not part of the alphabet
+    #[inline]
+    pub const fn byte_to_ord(&self, b: u8) -> Option<u8> {
+        match self.decode[b as usize] {
+            B58_NO_ORD => None,
+            o => Some(o),
+        }
+    }
+
+    /// converts a slice of bytes into a Base58 encoded String
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let zeros = bytes.iter().take_while(|b| **b == 0).count();
+        // the rest of the bytes as a big number, in base 58^5 digits, least significant first
+        let mut digits: Vec<u32> = Vec::with_capacity((bytes.len() - zeros) * 138 / 100 / 5 + 1);
+        for b in &bytes[zeros..] {
+            let mut carry = *b as u64;
+            for d in digits.iter_mut() {
+                let x = ((*d as u64) << 8) | carry;
+                *d = (x % B58_POW5) as u32;
+                carry = x / B58_POW5;
+            }
+            while carry > 0 {
+                digits.push((carry % B58_POW5) as u32);
+                carry /= B58_POW5;
+            }
+        }
+
+        let mut out = Vec::with_capacity(zeros + digits.len() * 5);
+        for (i, d) in digits.iter().enumerate() {
+            let mut d = *d;
+            let last = i + 1 == digits.len();
+            for _ in 0..5 {
+                // the most significant digit has no leading zeros
+                if last && d == 0 {
+                    break;
+                }
+                out.push(self.encode[(d % 58) as usize]);
+                d /= 58;
+            }
+        }
+        out.extend(std::iter::repeat_n(self.encode[0], zeros));
+        out.reverse();
+        // SAFETY: only symbols of the alphabet, which are ASCII, are pushed
+        unsafe { String::from_utf8_unchecked(out) }
+    }
+
+    /// converts Base58 encoded data, either a str or ASCII bytes, into a vec of bytes, or None if
+    /// any char is not part of the alphabet. see [`B58Alphabet::try_decode`] to find out which.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(B58Alphabet::BITCOIN.decode("1112"), Some(vec![0, 0, 0, 1]));
+    /// assert_eq!(B58Alphabet::BITCOIN.decode("0"), None);
+    /// ```
+    pub fn decode(&self, data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+        self.try_decode(data).ok()
+    }
+
+    /// converts Base58 encoded data, either a str or ASCII bytes, into a vec of bytes, failing with
+    /// the location of the first char that is not part of the alphabet
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let e = B58Alphabet::BITCOIN.try_decode("2NEpo7TZ0").unwrap_err();
+    /// assert_eq!(e, B58DecodeError::new(B58DecodeErrorKind::InvalidSymbol, 8));
+    /// ```
+    pub fn try_decode(&self, data: impl AsRef<[u8]>) -> Result<Vec<u8>, B58DecodeError> {
+        let data = data.as_ref();
+        let zero = self.encode[0];
+        let zeros = data.iter().take_while(|b| **b == zero).count();
+        // the rest of the symbols as a big number, in u32 digits, least significant first
+        let mut digits: Vec<u32> = Vec::with_capacity((data.len() - zeros) * 733 / 1000 / 4 + 1);
+        for (i, b) in data.iter().enumerate().skip(zeros) {
+            let Some(o) = self.byte_to_ord(*b) else {
+                return Err(B58DecodeError::new(B58DecodeErrorKind::InvalidSymbol, i));
+            };
+            let mut carry = o as u64;
+            for d in digits.iter_mut() {
+                let x = *d as u64 * 58 + carry;
+                *d = x as u32;
+                carry = x >> 32;
+            }
+            if carry > 0 {
+                digits.push(carry as u32);
+            }
+        }
+
+        let mut out = Vec::with_capacity(zeros + digits.len() * 4);
+        out.resize(zeros, 0);
+        if let Some(top) = digits.last() {
+            // the most significant digit has no leading zero bytes
+            let top = top.to_be_bytes();
+            let skip = top.iter().take_while(|b| **b == 0).count();
+            out.extend_from_slice(&top[skip..]);
+        }
+        for d in digits.iter().rev().skip(1) {
+            out.extend_from_slice(&d.to_be_bytes());
+        }
+        Ok(out)
+    }
+
+    /// converts a payload into Base58Check, which is the payload followed by the first 4 bytes of
+    /// its double SHA-256, encoded as Base58. any version byte should already lead the payload.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let s = B58Alphabet::BITCOIN.encode_check(b"Ma");
+    /// assert_eq!(B58Alphabet::BITCOIN.decode_check(&s), Ok(b"Ma".to_vec()));
+    /// ```
+    pub fn encode_check(&self, payload: &[u8]) -> String {
+        let mut v = Vec::with_capacity(payload.len() + B58_CHECK_LEN);
+        v.extend_from_slice(payload);
+        v.extend_from_slice(&sha256d(payload)[..B58_CHECK_LEN]);
+        self.encode(&v)
+    }
+
+    /// converts Base58Check encoded data back into its payload, verifying and removing the checksum
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let e = B58Alphabet::BITCOIN.decode_check("2NEpo7TZRRrLZSi2U").unwrap_err();
+    /// assert_eq!(e.kind, B58DecodeErrorKind::ChecksumMismatch);
+    ///
+    /// let e = B58Alphabet::BITCOIN.decode_check("2g").unwrap_err();
+    /// assert_eq!(e.kind, B58DecodeErrorKind::MissingChecksum);
+    /// ```
+    pub fn decode_check(&self, data: impl AsRef<[u8]>) -> Result<Vec<u8>, B58DecodeError> {
+        let data = data.as_ref();
+        let mut v = self.try_decode(data)?;
+        if v.len() < B58_CHECK_LEN {
+            return Err(B58DecodeError::new(
+                B58DecodeErrorKind::MissingChecksum,
+                data.len(),
+            ));
+        }
+        let split = v.len() - B58_CHECK_LEN;
+        if sha256d(&v[..split])[..B58_CHECK_LEN] != v[split..] {
+            return Err(B58DecodeError::new(
+                B58DecodeErrorKind::ChecksumMismatch,
+                data.len(),
+            ));
+        }
+        v.truncate(split);
+        Ok(v)
+    }
+}
+
+impl Default for B58Alphabet {
+    fn default() -> Self {
+        Self::BITCOIN
+    }
+}
+
+/// the kind of problem found while decoding Base58
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum B58DecodeErrorKind {
+    /// a char that is not part of the alphabet
+    InvalidSymbol,
+    /// Base58Check data that decodes to fewer than the 4 checksum bytes
+    MissingChecksum,
+    /// the Base58Check checksum does not match the payload
+    ChecksumMismatch,
+}
+
+/// error returned by Base58 decoding, with the kind of problem and the byte offset in the input
+/// where it was found. checksum problems are reported at the end of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B58DecodeError {
+    pub kind: B58DecodeErrorKind,
+    pub index: usize,
+}
+
+impl B58DecodeError {
+    #[inline]
+    pub const fn new(kind: B58DecodeErrorKind, index: usize) -> Self {
+        Self { kind, index }
+    }
+}
+
+impl std::fmt::Display for B58DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self.kind {
+            B58DecodeErrorKind::InvalidSymbol => "invalid Base58 symbol",
+            B58DecodeErrorKind::MissingChecksum => "Base58Check data too short for a checksum",
+            B58DecodeErrorKind::ChecksumMismatch => "Base58Check checksum mismatch",
+        };
+        write!(f, "{} at byte {}", msg, self.index)
+    }
+}
+
+impl std::error::Error for B58DecodeError {}
+
+///
+/// converts a slice of bytes into a base58 encoded string, using the Bitcoin alphabet
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_b58;
+///
+/// let s2 = bytes_to_b58(b"Hello World!");
+/// assert_eq!(s2.as_str(), "2NEpo7TZRRrLZSi2U");
+/// ```
+pub fn bytes_to_b58(bytes: &[u8]) -> String {
+    B58Alphabet::BITCOIN.encode(bytes)
+}
+
+///
+/// converts base58 encoded data, either a str or ASCII bytes, into a vec of bytes, using the
+/// Bitcoin alphabet. returns None if any char is not part of the alphabet.
+///
+/// ```
+///  # use crate::cj_common::prelude::b58_to_bytes;
+///
+/// let v = b58_to_bytes("2NEpo7TZRRrLZSi2U").unwrap();
+/// assert_eq!(v.as_slice(), b"Hello World!");
+/// ```
+pub fn b58_to_bytes(data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    B58Alphabet::BITCOIN.decode(data)
+}
+
+/// same as [`b58_to_bytes`], but fails with the location of the first invalid char
+/// ```
+/// # use cj_common::prelude::*;
+/// let e = b58_to_bytes_strict("2NEpo7TZRRrLZSi2l").unwrap_err();
+/// assert_eq!(e, B58DecodeError::new(B58DecodeErrorKind::InvalidSymbol, 16));
+/// ```
+pub fn b58_to_bytes_strict(data: impl AsRef<[u8]>) -> Result<Vec<u8>, B58DecodeError> {
+    B58Alphabet::BITCOIN.try_decode(data)
+}
+
+///
+/// converts a payload into Base58Check using the Bitcoin alphabet. see [`B58Alphabet::encode_check`]
+///
+/// ```
+///  # use crate::cj_common::prelude::*;
+///
+/// // version 0 followed by a public key hash makes a Bitcoin address
+/// let mut payload = vec![0x00];
+/// payload.extend(hex_str_to_u8_vec("62E907B15CBF27D5425399EBF6F0FB50EBB88F18").unwrap());
+/// let s2 = bytes_to_b58check(&payload);
+/// assert_eq!(s2.as_str(), "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+/// ```
+pub fn bytes_to_b58check(payload: &[u8]) -> String {
+    B58Alphabet::BITCOIN.encode_check(payload)
+}
+
+///
+/// converts Base58Check using the Bitcoin alphabet back into its payload, verifying the checksum.
+/// see [`B58Alphabet::decode_check`]
+///
+/// ```
+///  # use crate::cj_common::prelude::*;
+///
+/// let v = b58check_to_bytes("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa").unwrap();
+/// assert_eq!(v.len(), 21);
+/// assert_eq!(v[0], 0x00);
+///
+/// let e = b58check_to_bytes("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb").unwrap_err();
+/// assert_eq!(e.kind, B58DecodeErrorKind::ChecksumMismatch);
+/// ```
+pub fn b58check_to_bytes(data: impl AsRef<[u8]>) -> Result<Vec<u8>, B58DecodeError> {
+    B58Alphabet::BITCOIN.decode_check(data)
+}
+
+pub trait CjToBase58 {
+    fn to_b58_string(&self) -> String;
+    fn to_b58check_string(&self) -> String;
+    fn to_b58_string_with(&self, alphabet: &B58Alphabet) -> String;
+}
+
+impl<T: AsRef<[u8]> + ?Sized> CjToBase58 for T {
+    /// converts anything holding bytes (`&str`, `String`, `Vec<u8>`, `[u8; N]`, ...) to a Base58
+    /// encoded String, using the Bitcoin alphabet
+    /// ```
+    /// # use cj_common::prelude::CjToBase58;
+    /// let s2 = "Hello World!".to_b58_string();
+    /// assert_eq!(s2.as_str(), "2NEpo7TZRRrLZSi2U");
+    /// ```
+    fn to_b58_string(&self) -> String {
+        bytes_to_b58(self.as_ref())
+    }
+    /// converts anything holding bytes to a Base58Check encoded String, using the Bitcoin alphabet
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let s2 = vec![0x00u8, 0x01].to_b58check_string();
+    /// assert_eq!(b58check_to_bytes(&s2), Ok(vec![0x00, 0x01]));
+    /// ```
+    fn to_b58check_string(&self) -> String {
+        bytes_to_b58check(self.as_ref())
+    }
+    /// converts anything holding bytes to a Base58 encoded String using the given alphabet
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let s2 = "Hello World!".to_b58_string_with(&B58Alphabet::FLICKR);
+    /// assert_eq!(s2.as_str(), "2nePN7syqqRkyrH2t");
+    /// ```
+    fn to_b58_string_with(&self, alphabet: &B58Alphabet) -> String {
+        alphabet.encode(self.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cj_binary::hex::hex_str_to_u8_vec;
+
+    // vectors from Bitcoin Core's base58_encode_decode.json
+    const VECTORS: [(&str, &str); 14] = [
+        ("", ""),
+        ("61", "2g"),
+        ("626262", "a3gV"),
+        ("636363", "aPEr"),
+        (
+            "73696D706C792061206C6F6E6720737472696E67",
+            "2cFupjhnEsSn59qHXstmK2ffpLv2",
+        ),
+        (
+            "00EB15231DFCEB60925886B67D065299925915AEB172C06647",
+            "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L",
+        ),
+        ("516B6FCD0F", "ABnLTmg"),
+        ("BF4F89001E670274DD", "3SEo3LWLoPntC"),
+        ("572E4794", "3EFU7m"),
+        ("ECAC89CAD93923C02321", "EJDM8drfXA6uyA"),
+        ("10C8511E", "Rt5zm"),
+        ("00000000000000000000", "1111111111"),
+        (
+            "000111D38E5FC9071FFCD20B4A763CC9AE4F252BB4E48FD66A835E252ADA93FF480D6DD43DC62A641155A5",
+            "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz",
+        ),
+        (
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            "JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFG",
+        ),
+    ];
+
+    #[test]
+    fn test_vectors() {
+        for (hex, b58) in VECTORS {
+            let bytes = hex_str_to_u8_vec(hex).unwrap_or_default();
+            assert_eq!(bytes_to_b58(&bytes), b58);
+            assert_eq!(b58_to_bytes_strict(b58), Ok(bytes.clone()));
+            assert_eq!(bytes.to_b58_string(), b58);
+        }
+
+        // the genesis block's address, as Base58Check
+        let payload = hex_str_to_u8_vec("0062E907B15CBF27D5425399EBF6F0FB50EBB88F18").unwrap();
+        let s = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa";
+        assert_eq!(bytes_to_b58check(&payload), s);
+        assert_eq!(payload.to_b58check_string(), s);
+        assert_eq!(b58check_to_bytes(s), Ok(payload));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data = (0..100u32)
+            .map(|x| (x * 131 + 7) as u8)
+            .collect::<Vec<u8>>();
+        for a in [
+            B58Alphabet::BITCOIN,
+            B58Alphabet::RIPPLE,
+            B58Alphabet::FLICKR,
+        ] {
+            for n in 0..data.len() {
+                for zeros in [0usize, 1, 3] {
+                    let mut v = vec![0u8; zeros];
+                    v.extend_from_slice(&data[..n]);
+                    let s = a.encode(&v);
+                    assert_eq!(a.try_decode(&s), Ok(v.clone()));
+                    assert_eq!(a.decode_check(a.encode_check(&v)), Ok(v.clone()));
+                }
+            }
+        }
+        // the same number in each alphabet
+        let v = hex_str_to_u8_vec("FFFF").unwrap();
+        assert_eq!(B58Alphabet::BITCOIN.encode(&v), "LUv");
+        assert_eq!(B58Alphabet::RIPPLE.encode(&v), "L7v");
+        assert_eq!(B58Alphabet::FLICKR.encode(&v), "ktV");
+    }
+
+    #[test]
+    fn test_errors() {
+        let e = |kind, index| Err(B58DecodeError::new(kind, index));
+        for (s, i) in [("0", 0), ("1O", 1), ("2NEI", 3), ("abc l", 3), (" 2g", 0)] {
+            assert_eq!(
+                b58_to_bytes_strict(s),
+                e(B58DecodeErrorKind::InvalidSymbol, i)
+            );
+            assert_eq!(b58_to_bytes(s), None);
+        }
+        assert_eq!(
+            b58check_to_bytes(""),
+            e(B58DecodeErrorKind::MissingChecksum, 0)
+        );
+        assert_eq!(
+            b58check_to_bytes("1111"),
+            e(B58DecodeErrorKind::ChecksumMismatch, 4)
+        );
+        let s = bytes_to_b58check(b"payload");
+        let mut bad = s.clone().into_bytes();
+        bad[3] = if bad[3] == b'2' { b'3' } else { b'2' };
+        assert_eq!(
+            b58check_to_bytes(&bad),
+            e(B58DecodeErrorKind::ChecksumMismatch, s.len())
+        );
+        assert_eq!(b58check_to_bytes(bytes_to_b58check(b"")), Ok(vec![]));
+
+        let s = format!(
+            "{}",
+            B58DecodeError::new(B58DecodeErrorKind::InvalidSymbol, 2)
+        );
+        assert_eq!(s.as_str(), "invalid Base58 symbol at byte 2");
+    }
+}
@@ -32,6 +32,9 @@
 //! let iter = [1u8,2,3,4,5,6].as_slice().iter_to_b64();
 //! ```
 
+use crate::cj_binary::ct::{ct_in_range, ct_is_zero};
+use crate::cj_binary::simd;
+use std::io::{self, Read, Write};
 use std::slice::Iter;
 use std::str::Chars;
 
@@ -43,7 +46,15 @@ const B64_TABLE: [char; 64] = [
     '5', '6', '7', '8', '9', '+', '/',
 ];
 
-#[derive(Debug)]
+// url and filename safe b64 table (RFC 4648.5).
+const B64_URL_TABLE: [char; 64] = [
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l',
+    'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '0', '1', '2', '3', '4',
+    '5', '6', '7', '8', '9', '-', '_',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum CharToOrdResult {
     Ok(u8),
@@ -149,6 +160,853 @@ pub const fn b64_char_to_ord(c: &char) -> &'static CharToOrdResult {
     }
 }
 
+/// same as [`b64_char_to_ord`], but for the url and filename safe alphabet (RFC 4648.5),
+/// where `-` and `_` take the place of `+` and `/`
+/// ```
+/// # use cj_common::prelude::*;
+/// assert!(b64_url_char_to_ord(&'-').is_ok());
+/// assert!(b64_url_char_to_ord(&'_').is_ok());
+/// assert!(b64_url_char_to_ord(&'+').is_invalid());
+/// ```
+#[inline]
+pub const fn b64_url_char_to_ord(c: &char) -> &'static CharToOrdResult {
+    match c {
+        '-' => &CharToOrdResult::Ok(62),
+        '_' => &CharToOrdResult::Ok(63),
+        '+' | '/' => &CharToOrdResult::Invalid,
+        _ => b64_char_to_ord(c),
+    }
+}
+
+// marks a byte that is not part of an alphabet in B64Alphabet's decode table
+const B64_NO_ORD: u8 = 0xFF;
+
+///
+/// a 64 symbol Base64 alphabet.
+///
+/// [`B64Alphabet::STANDARD`] (RFC 4648.4) and [`B64Alphabet::URL_SAFE`] (RFC 4648.5) are provided,
+/// and custom alphabets can be built from any 64 unique, printable ASCII chars other than `=`.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// let alpha = B64Alphabet::new("./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz");
+/// assert!(alpha.is_some());
+///
+/// // too short, and duplicate symbols
+/// assert!(B64Alphabet::new("ABC").is_none());
+/// assert!(B64Alphabet::new(&"A".repeat(64)).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B64Alphabet {
+    pub(crate) encode: [u8; 64],
+    pub(crate) decode: [u8; 256],
+}
+
+impl B64Alphabet {
+    /// general alphabet (RFC 4648.4), using `+` and `/`
+    pub const STANDARD: B64Alphabet = match B64Alphabet::from_table(&B64_TABLE) {
+        Some(a) => a,
+        None => panic!("invalid b64 table"),
+    };
+    /// url and filename safe alphabet (RFC 4648.5), using `-` and `_`
+    pub const URL_SAFE: B64Alphabet = match B64Alphabet::from_table(&B64_URL_TABLE) {
+        Some(a) => a,
+        None => panic!("invalid b64 table"),
+    };
+
+    /// builds an alphabet from a table of 64 symbols, where the index of each symbol is its value.
+    ///
+    /// returns None if any symbol is repeated, is not printable ASCII, or is `=`.
+    pub const fn from_table(table: &[char; 64]) -> Option<Self> {
+        let mut encode = [0u8; 64];
+        let mut decode = [B64_NO_ORD; 256];
+        let mut i = 0;
+        while i < 64 {
+            let c = table[i];
+            if !c.is_ascii_graphic() || c == '=' {
+                return None;
+            }
+            let b = c as u8;
+            if decode[b as usize] != B64_NO_ORD {
+                return None;
+            }
+            encode[i] = b;
+            decode[b as usize] = i as u8;
+            i += 1;
+        }
+        Some(Self { encode, decode })
+    }
+
+    /// builds an alphabet from a str of exactly 64 symbols. see [`B64Alphabet::from_table`]
+    pub fn new(symbols: &str) -> Option<Self> {
+        let mut table = ['\0'; 64];
+        let mut ct = 0;
+        for c in symbols.chars() {
+            if ct == 64 {
+                return None;
+            }
+            table[ct] = c;
+            ct += 1;
+        }
+        if ct != 64 {
+            return None;
+        }
+        Self::from_table(&table)
+    }
+
+    /// the symbol for a 6 bit value
+    #[inline(always)]
+    pub const fn symbol(&self, ord: u8) -> char {
+        self.encode[(ord & 0x3F) as usize] as char
+    }
+
+    /// same as [`b64_char_to_ord`], but for this alphabet
+    #[inline]
+    pub const fn char_to_ord(&self, c: &char) -> CharToOrdResult {
+        let n = *c as u32;
+        if n < 256 {
+            let o = self.decode[n as usize];
+            if o != B64_NO_ORD {
+                return CharToOrdResult::Ok(o);
+            }
+        }
+        match c {
+            '=' => CharToOrdResult::Pad,
+            // chars to ignore
+            ' ' | '\r' | '\n' | '\t' => CharToOrdResult::WhiteSpace,
+            // all else are failures
+            _ => CharToOrdResult::Invalid,
+        }
+    }
+
+    /// same as [`B64Alphabet::char_to_ord`], for a byte of ASCII encoded Base64. bytes outside of
+    /// ASCII are invalid.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let a = B64Alphabet::STANDARD;
+    /// assert_eq!(a.byte_to_ord(b'/'), CharToOrdResult::Ok(63));
+    /// assert_eq!(a.byte_to_ord(b'='), CharToOrdResult::Pad);
+    /// assert_eq!(a.byte_to_ord(0xC3), CharToOrdResult::Invalid);
+    /// ```
+    #[inline]
+    pub const fn byte_to_ord(&self, b: u8) -> CharToOrdResult {
+        let o = self.decode[b as usize];
+        if o != B64_NO_ORD {
+            return CharToOrdResult::Ok(o);
+        }
+        match b {
+            b'=' => CharToOrdResult::Pad,
+            // bytes to ignore
+            b' ' | b'\r' | b'\n' | b'\t' => CharToOrdResult::WhiteSpace,
+            // all else are failures
+            _ => CharToOrdResult::Invalid,
+        }
+    }
+}
+
+/// how `=` padding is treated when decoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum B64PadMode {
+    /// padding is accepted, but not required
+    Indifferent,
+    /// the final quantum must be padded out to 4 chars
+    Require,
+    /// `=` is treated as an invalid char
+    Forbid,
+}
+
+/// how whitespace (space, tab, `\r` and `\n`) is treated when decoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum B64Whitespace {
+    /// whitespace is skipped
+    Ignore,
+    /// whitespace is treated as an invalid char
+    Reject,
+}
+
+/// line ending written between lines of wrapped output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum B64LineEnding {
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl B64LineEnding {
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            B64LineEnding::Lf => "\n",
+            B64LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+///
+/// Base64 engine bundling the alphabet, padding, line wrapping and strictness options used for encoding
+/// and decoding.
+///
+/// `B64Config` drives one-shot calls ([`B64Config::encode`], [`B64Config::decode`]) and the
+/// [`ToBase64Iter`] / [`FromBase64Iter`] iterators. The `bytes_to_b64` and `b64_to_bytes` style functions
+/// are shorthand for the [`B64Config::STANDARD`] and [`B64Config::URL_SAFE_NO_PAD`] configs.
+///
+/// When decoding, a lenient config (the default) stops at the first problem in the input and returns
+/// what was decoded up to that point. A strict config returns None instead.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// let cfg = B64Config::URL_SAFE.with_padding(false);
+/// let s = cfg.encode(&[0xFB, 0xFF, 0xBF, 0x01]);
+/// assert_eq!(s.as_str(), "-_-_AQ");
+/// assert_eq!(cfg.decode(s.as_str()), Some(vec![0xFB, 0xFF, 0xBF, 0x01]));
+///
+/// // strict decoding with required padding
+/// let cfg = B64Config::STANDARD
+///     .with_decode_padding(B64PadMode::Require)
+///     .with_strict(true);
+/// assert_eq!(cfg.decode("TWE="), Some(b"Ma".to_vec()));
+/// assert_eq!(cfg.decode("TWE"), None);
+///
+/// // iterators
+/// let s = cfg.iter_encode(b"Ma").collect::<String>();
+/// assert_eq!(s.as_str(), "TWE=");
+/// let v = cfg.iter_decode("TWE=").collect::<Vec<u8>>();
+/// assert_eq!(v.as_slice(), b"Ma");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B64Config {
+    alphabet: B64Alphabet,
+    pad: bool,
+    pad_mode: B64PadMode,
+    whitespace: B64Whitespace,
+    strict: bool,
+    line_len: usize,
+    line_ending: B64LineEnding,
+}
+
+impl B64Config {
+    /// general alphabet, padded output, lenient decoding
+    pub const STANDARD: B64Config = B64Config::new(B64Alphabet::STANDARD);
+    /// url and filename safe alphabet, padded output, lenient decoding
+    pub const URL_SAFE: B64Config = B64Config::new(B64Alphabet::URL_SAFE);
+    /// url and filename safe alphabet, unpadded output, lenient decoding
+    pub const URL_SAFE_NO_PAD: B64Config = B64Config::URL_SAFE.with_padding(false);
+    /// general alphabet, padded output wrapped at 76 columns with `\r\n` (RFC 2045), lenient decoding
+    pub const MIME: B64Config = B64Config::STANDARD.with_line_wrap(76, B64LineEnding::CrLf);
+    /// general alphabet, padded output wrapped at 64 columns with `\n` (RFC 7468), lenient decoding
+    pub const PEM: B64Config = B64Config::STANDARD.with_line_wrap(64, B64LineEnding::Lf);
+
+    /// new config for an alphabet, with padded unwrapped output, optional padding and ignored
+    /// whitespace on decode, and lenient decoding
+    pub const fn new(alphabet: B64Alphabet) -> Self {
+        Self {
+            alphabet,
+            pad: true,
+            pad_mode: B64PadMode::Indifferent,
+            whitespace: B64Whitespace::Ignore,
+            strict: false,
+            line_len: 0,
+            line_ending: B64LineEnding::CrLf,
+        }
+    }
+
+    /// sets the alphabet
+    pub const fn with_alphabet(mut self, alphabet: B64Alphabet) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+
+    /// sets whether `=` padding is added when encoding
+    pub const fn with_padding(mut self, pad: bool) -> Self {
+        self.pad = pad;
+        self
+    }
+
+    /// sets how `=` padding is treated when decoding
+    pub const fn with_decode_padding(mut self, pad_mode: B64PadMode) -> Self {
+        self.pad_mode = pad_mode;
+        self
+    }
+
+    /// sets how whitespace is treated when decoding
+    pub const fn with_whitespace(mut self, whitespace: B64Whitespace) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// sets the number of chars per line of encoded output, and the line ending written between lines.
+    /// a `line_len` of 0 turns wrapping off. no line ending follows the last line.
+    ///
+    /// decoding skips the line endings as whitespace, unless whitespace is rejected.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let cfg = B64Config::STANDARD.with_line_wrap(8, B64LineEnding::Lf);
+    /// let s = cfg.encode(b"Many hands make");
+    /// assert_eq!(s.as_str(), "TWFueSBo\nYW5kcyBt\nYWtl");
+    /// assert_eq!(cfg.decode(s.as_str()), Some(b"Many hands make".to_vec()));
+    /// ```
+    pub const fn with_line_wrap(mut self, line_len: usize, line_ending: B64LineEnding) -> Self {
+        self.line_len = line_len;
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// sets whether decoding fails outright on bad input (strict), or returns what was decoded
+    /// before the bad input (lenient)
+    pub const fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    #[inline]
+    pub const fn alphabet(&self) -> &B64Alphabet {
+        &self.alphabet
+    }
+
+    #[inline]
+    pub const fn padding(&self) -> bool {
+        self.pad
+    }
+
+    #[inline]
+    pub const fn decode_padding(&self) -> B64PadMode {
+        self.pad_mode
+    }
+
+    #[inline]
+    pub const fn whitespace(&self) -> B64Whitespace {
+        self.whitespace
+    }
+
+    #[inline]
+    pub const fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// chars per line and line ending of encoded output, or None if output is not wrapped
+    #[inline]
+    pub const fn line_wrap(&self) -> Option<(usize, B64LineEnding)> {
+        if self.line_len > 0 {
+            Some((self.line_len, self.line_ending))
+        } else {
+            None
+        }
+    }
+
+    /// exact length of the Base64 encoding of `byte_len` bytes with this config
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(B64Config::STANDARD.encoded_len(28), 40);
+    /// assert_eq!(B64Config::URL_SAFE_NO_PAD.encoded_len(28), 38);
+    /// assert_eq!(B64Config::MIME.encoded_len(60), 82);
+    /// ```
+    pub const fn encoded_len(&self, byte_len: usize) -> usize {
+        let rem = byte_len % 3;
+        let mut len = byte_len / 3 * 4;
+        if rem > 0 {
+            len += if self.pad { 4 } else { rem + 1 };
+        }
+        if self.line_len > 0 && len > 0 {
+            len += (len - 1) / self.line_len * self.line_ending.as_str().len();
+        }
+        len
+    }
+
+    /// upper bound of the number of bytes decoded from `char_len` chars of Base64.
+    /// padding and whitespace make the actual length shorter.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(B64Config::STANDARD.decoded_len_estimate(40), 30);
+    /// assert_eq!(B64Config::STANDARD.decoded_len_estimate(38), 30);
+    /// ```
+    pub const fn decoded_len_estimate(&self, char_len: usize) -> usize {
+        char_len.div_ceil(4) * 3
+    }
+
+    /// converts a slice of bytes into a Base64 encoded String
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let mut s = String::new();
+        self.encode_into(bytes, &mut s);
+        s
+    }
+
+    /// appends the Base64 encoding of a slice of bytes to a String
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut s = String::from("data:");
+    /// B64Config::STANDARD.encode_into(b"Ma", &mut s);
+    /// assert_eq!(s.as_str(), "data:TWE=");
+    /// ```
+    pub fn encode_into(&self, bytes: &[u8], out: &mut String) {
+        out.reserve(self.encoded_len(bytes.len()));
+        // whole quanta go through the SIMD path when there are no line endings to place
+        let done = if self.line_len == 0 {
+            // SAFETY: only symbols of the alphabet, which are ASCII, are appended
+            simd::b64_encode_prefix(&self.alphabet.encode, bytes, unsafe { out.as_mut_vec() })
+        } else {
+            0
+        };
+        self.encode_each(&bytes[done..], |c| out.push(c as char));
+    }
+
+    /// writes the Base64 encoding of a slice of bytes to the start of `out`, returning the number
+    /// of bytes written. `out` must hold at least [`B64Config::encoded_len`] bytes.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut buf = [0u8; 8];
+    /// let n = B64Config::STANDARD.encode_to_slice(b"Man", &mut buf).unwrap();
+    /// assert_eq!(&buf[..n], b"TWFu");
+    ///
+    /// let e = B64Config::STANDARD.encode_to_slice(b"Many", &mut buf[..4]).unwrap_err();
+    /// assert_eq!(e.needed, 8);
+    /// ```
+    pub fn encode_to_slice(&self, bytes: &[u8], out: &mut [u8]) -> Result<usize, B64EncodeError> {
+        let needed = self.encoded_len(bytes.len());
+        if out.len() < needed {
+            return Err(B64EncodeError {
+                needed,
+                available: out.len(),
+            });
+        }
+        let mut n = 0;
+        self.encode_each(bytes, |c| {
+            out[n] = c;
+            n += 1;
+        });
+        Ok(n)
+    }
+
+    // feeds each ASCII char of the encoding, padding and line endings included, to put
+    #[inline]
+    fn encode_each(&self, bytes: &[u8], mut put: impl FnMut(u8)) {
+        if self.line_len == 0 {
+            return self.encode_unwrapped(bytes, put);
+        }
+        let mut col = 0;
+        self.encode_unwrapped(bytes, |c| {
+            if col == self.line_len {
+                self.line_ending.as_str().bytes().for_each(&mut put);
+                col = 0;
+            }
+            put(c);
+            col += 1;
+        });
+    }
+
+    #[inline]
+    fn encode_unwrapped(&self, bytes: &[u8], mut put: impl FnMut(u8)) {
+        let enc = &self.alphabet.encode;
+        let mut b_six = 0u8;
+        let mut b_rem = 0u8;
+        let mut pass_no = 0usize;
+        let b_len = bytes.len();
+        let mut s_char_ct = 0usize;
+
+        for b in bytes[..].iter() {
+            match bit_split_6(b, &mut b_six, &mut b_rem, &mut pass_no) {
+                BitSplit6Result::Ready => {
+                    put(enc[b_six as usize]);
+                    s_char_ct += 1;
+                }
+                BitSplit6Result::Resend => {
+                    put(enc[b_six as usize]);
+                    let _ = bit_split_6(b, &mut b_six, &mut b_rem, &mut pass_no);
+                    put(enc[b_six as usize]);
+                    s_char_ct += 2;
+                }
+            }
+        }
+
+        if b_len > 0 {
+            let x = b_rem;
+            b_rem = 0;
+            pass_no = 0;
+            let _ = bit_split_6(&x, &mut b_six, &mut b_rem, &mut pass_no);
+            put(enc[b_six as usize]);
+            s_char_ct += 1;
+        }
+
+        if self.pad {
+            for _ in 0..b64_pad_len(s_char_ct) {
+                put(b'=');
+            }
+        }
+    }
+
+    /// converts Base64 encoded data, either a str or ASCII bytes, into a vec of bytes.
+    ///
+    /// see [`B64Config::try_decode`] to find out why decoding failed.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(B64Config::STANDARD.decode("TWFu"), Some(b"Man".to_vec()));
+    /// assert_eq!(B64Config::STANDARD.decode(b"TWFu"), Some(b"Man".to_vec()));
+    /// ```
+    pub fn decode(&self, data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+        let data = data.as_ref();
+        let mut v = Vec::<u8>::with_capacity(self.decoded_len_estimate(data.len()));
+        let a = &self.alphabet;
+        let done = simd::b64_decode_prefix(&a.encode, &a.decode, data, &mut v);
+        let mut state = B64DecodeState::new();
+        let r = self.decode_each(&data[done..], done, &mut state, |q, _| {
+            v.extend_from_slice(q.as_slice());
+            Ok(())
+        });
+        match r {
+            Ok(()) if self.strict => Some(v),
+            Err(_) if self.strict => None,
+            r => {
+                if r.is_err() {
+                    // we were able to decode something, even if there was an error. keep what we have.
+                    v.extend_from_slice(state.flush().as_slice());
+                }
+                if v.is_empty() { None } else { Some(v) }
+            }
+        }
+    }
+
+    /// converts Base64 encoded data, either a str or ASCII bytes, into a vec of bytes, failing on the
+    /// first problem in the input regardless of [`B64Config::is_strict`].
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let cfg = B64Config::STANDARD;
+    /// assert_eq!(cfg.try_decode("TWFu"), Ok(b"Man".to_vec()));
+    ///
+    /// let e = cfg.try_decode("TWFu&TWFu").unwrap_err();
+    /// assert_eq!(e.kind, B64DecodeErrorKind::InvalidSymbol);
+    /// assert_eq!(e.index, 4);
+    /// ```
+    pub fn try_decode(&self, data: impl AsRef<[u8]>) -> Result<Vec<u8>, B64DecodeError> {
+        let data = data.as_ref();
+        let mut v = Vec::<u8>::with_capacity(self.decoded_len_estimate(data.len()));
+        self.decode_into(data, &mut v)?;
+        Ok(v)
+    }
+
+    /// appends the bytes decoded from Base64 encoded data to a vec, returning the number of bytes
+    /// added. like [`B64Config::try_decode`], the first problem in the input fails decoding, in which
+    /// case `out` is left as it was.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut v = b"Many".to_vec();
+    /// assert_eq!(B64Config::STANDARD.decode_into("IGhhbmRz", &mut v), Ok(6));
+    /// assert_eq!(v.as_slice(), b"Many hands");
+    ///
+    /// assert!(B64Config::STANDARD.decode_into("IGhhb&", &mut v).is_err());
+    /// assert_eq!(v.as_slice(), b"Many hands");
+    /// ```
+    pub fn decode_into(
+        &self,
+        data: impl AsRef<[u8]>,
+        out: &mut Vec<u8>,
+    ) -> Result<usize, B64DecodeError> {
+        let data = data.as_ref();
+        let start = out.len();
+        out.reserve(self.decoded_len_estimate(data.len()));
+        let a = &self.alphabet;
+        let done = simd::b64_decode_prefix(&a.encode, &a.decode, data, out);
+        let mut state = B64DecodeState::new();
+        let r = self.decode_each(&data[done..], done, &mut state, |q, _| {
+            out.extend_from_slice(q.as_slice());
+            Ok(())
+        });
+        match r {
+            Ok(()) => Ok(out.len() - start),
+            Err(e) => {
+                out.truncate(start);
+                Err(e)
+            }
+        }
+    }
+
+    /// writes the bytes decoded from Base64 encoded data to the start of `out`, returning the number
+    /// of bytes written. like [`B64Config::try_decode`], the first problem in the input fails decoding.
+    /// [`B64Config::decoded_len_estimate`] bytes is always enough room, but `out` only has to hold the
+    /// actual decoded length; running out of room fails with [`B64DecodeErrorKind::OutputTooSmall`].
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut buf = [0u8; 8];
+    /// let n = B64Config::STANDARD.decode_to_slice("TWFueQ==", &mut buf).unwrap();
+    /// assert_eq!(&buf[..n], b"Many");
+    ///
+    /// let e = B64Config::STANDARD.decode_to_slice("TWFueQ==", &mut buf[..3]).unwrap_err();
+    /// assert_eq!(e.kind, B64DecodeErrorKind::OutputTooSmall);
+    /// ```
+    pub fn decode_to_slice(
+        &self,
+        data: impl AsRef<[u8]>,
+        out: &mut [u8],
+    ) -> Result<usize, B64DecodeError> {
+        let data = data.as_ref();
+        let mut n = 0;
+        let mut state = B64DecodeState::new();
+        self.decode_each(data, 0, &mut state, |q, i| {
+            let b = q.as_slice();
+            if n + b.len() > out.len() {
+                return Err(B64DecodeError::new(B64DecodeErrorKind::OutputTooSmall, i));
+            }
+            out[n..n + b.len()].copy_from_slice(b);
+            n += b.len();
+            Ok(())
+        })?;
+        Ok(n)
+    }
+
+    // decodes data, feeding each complete quantum and the offset of the byte that completed it to put.
+    // offsets start at base. stops at the first problem, in which case state holds the partial quantum
+    // the problem was found in.
+    #[inline]
+    fn decode_each(
+        &self,
+        data: &[u8],
+        base: usize,
+        state: &mut B64DecodeState,
+        mut put: impl FnMut(B64Quantum, usize) -> Result<(), B64DecodeError>,
+    ) -> Result<(), B64DecodeError> {
+        for (i, b) in data.iter().enumerate() {
+            let i = base + i;
+            if let Some(q) = state.push(self, self.alphabet.byte_to_ord(*b), i)? {
+                put(q, i)?;
+            }
+        }
+        let end = base + data.len();
+        if let Some(q) = state.finish(self, end)? {
+            put(q, end)?;
+        }
+        Ok(())
+    }
+
+    /// Iterator over a slice of bytes that produces Base64 encoded chars
+    pub fn iter_encode<'a>(&'a self, bytes: &'a [u8]) -> ToBase64Iter<'a> {
+        ToBase64Iter::new_with(bytes.iter(), self)
+    }
+
+    /// Iterator over Base64 encoded data, either a str or ASCII bytes, that produces decoded bytes
+    pub fn iter_decode<'a, T: AsRef<[u8]> + ?Sized>(&'a self, data: &'a T) -> FromBase64Iter<'a> {
+        FromBase64Iter::new_bytes_with(data.as_ref().iter(), self)
+    }
+}
+
+impl Default for B64Config {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+// number of '=' needed to pad out the final quantum
+#[inline(always)]
+const fn b64_pad_len(char_ct: usize) -> usize {
+    (4 - char_ct % 4) % 4
+}
+
+/// the kind of problem found while decoding Base64
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum B64DecodeErrorKind {
+    /// a char that is not part of the alphabet (or whitespace, when it is rejected)
+    InvalidSymbol,
+    /// misplaced, missing, extra or forbidden `=` padding
+    InvalidPadding,
+    /// a symbol following `=` padding
+    TrailingData,
+    /// the unused trailing bits of the final symbol are not zero
+    NonCanonical,
+    /// the input ends with a single symbol, which can not make a byte
+    TruncatedQuantum,
+    /// the output buffer is too small for the decoded bytes
+    OutputTooSmall,
+    /// the decoded length does not match the fixed size of the target
+    LengthMismatch,
+}
+
+/// error returned by strict Base64 decoding, with the kind of problem and the byte offset
+/// in the input where it was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B64DecodeError {
+    pub kind: B64DecodeErrorKind,
+    pub index: usize,
+}
+
+impl B64DecodeError {
+    #[inline]
+    pub const fn new(kind: B64DecodeErrorKind, index: usize) -> Self {
+        Self { kind, index }
+    }
+}
+
+impl std::fmt::Display for B64DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self.kind {
+            B64DecodeErrorKind::InvalidSymbol => "invalid Base64 symbol",
+            B64DecodeErrorKind::InvalidPadding => "invalid Base64 padding",
+            B64DecodeErrorKind::TrailingData => "Base64 data after padding",
+            B64DecodeErrorKind::NonCanonical => "non-canonical Base64 trailing bits",
+            B64DecodeErrorKind::TruncatedQuantum => "truncated Base64 quantum",
+            B64DecodeErrorKind::OutputTooSmall => "output buffer too small for Base64 data",
+            B64DecodeErrorKind::LengthMismatch => "decoded Base64 length does not match the target",
+        };
+        write!(f, "{} at byte {}", msg, self.index)
+    }
+}
+
+impl std::error::Error for B64DecodeError {}
+
+/// error returned when an output buffer is too small to hold the Base64 encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B64EncodeError {
+    /// bytes needed for the encoding
+    pub needed: usize,
+    /// bytes available in the output buffer
+    pub available: usize,
+}
+
+impl std::fmt::Display for B64EncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "output buffer too small for Base64 encoding: {} bytes needed, {} available",
+            self.needed, self.available
+        )
+    }
+}
+
+impl std::error::Error for B64EncodeError {}
+
+// up to 3 decoded bytes of a quantum
+#[derive(Debug, Clone, Copy, Default)]
+struct B64Quantum {
+    bytes: [u8; 3],
+    len: usize,
+}
+
+impl B64Quantum {
+    #[inline(always)]
+    fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+// decoding state shared by B64Config::decode and FromBase64Iter. symbols are collected into
+// quantums of 4, and a quantum's bytes are only released once it is known to be complete.
+#[derive(Debug, Clone, Copy)]
+struct B64DecodeState {
+    ords: [u8; 4],
+    ord_ct: usize,
+    pad_ct: usize,
+    // offsets of the first and last symbols of the current quantum
+    first_index: usize,
+    last_index: usize,
+}
+
+impl B64DecodeState {
+    const fn new() -> Self {
+        Self {
+            ords: [0; 4],
+            ord_ct: 0,
+            pad_ct: 0,
+            first_index: 0,
+            last_index: 0,
+        }
+    }
+
+    // ord is the lookup of the byte at offset index in the input
+    #[inline]
+    fn push(
+        &mut self,
+        config: &B64Config,
+        ord: CharToOrdResult,
+        index: usize,
+    ) -> Result<Option<B64Quantum>, B64DecodeError> {
+        let err = |kind| Err(B64DecodeError::new(kind, index));
+        match ord {
+            CharToOrdResult::Ok(b) => {
+                if self.pad_ct > 0 {
+                    return err(B64DecodeErrorKind::TrailingData);
+                }
+                if self.ord_ct == 0 {
+                    self.first_index = index;
+                }
+                self.last_index = index;
+                self.ords[self.ord_ct] = b;
+                self.ord_ct += 1;
+                if self.ord_ct == 4 {
+                    let q = self.flush();
+                    self.ord_ct = 0;
+                    return Ok(Some(q));
+                }
+                Ok(None)
+            }
+            CharToOrdResult::Pad => {
+                if config.pad_mode == B64PadMode::Forbid
+                    || self.ord_ct < 2
+                    || self.ord_ct + self.pad_ct == 4
+                {
+                    return err(B64DecodeErrorKind::InvalidPadding);
+                }
+                self.pad_ct += 1;
+                Ok(None)
+            }
+            CharToOrdResult::WhiteSpace => match config.whitespace {
+                B64Whitespace::Ignore => Ok(None),
+                B64Whitespace::Reject => err(B64DecodeErrorKind::InvalidSymbol),
+            },
+            CharToOrdResult::Invalid => err(B64DecodeErrorKind::InvalidSymbol),
+        }
+    }
+
+    // end of input, end_index being the length of the input.
+    // returns the bytes of the final partial quantum, if any.
+    fn finish(
+        &mut self,
+        config: &B64Config,
+        end_index: usize,
+    ) -> Result<Option<B64Quantum>, B64DecodeError> {
+        match self.ord_ct {
+            0 => Ok(None),
+            1 => Err(B64DecodeError::new(
+                B64DecodeErrorKind::TruncatedQuantum,
+                self.first_index,
+            )),
+            _ => {
+                if (self.pad_ct > 0 && self.ord_ct + self.pad_ct != 4)
+                    || (self.pad_ct == 0 && config.pad_mode == B64PadMode::Require)
+                {
+                    return Err(B64DecodeError::new(
+                        B64DecodeErrorKind::InvalidPadding,
+                        end_index,
+                    ));
+                }
+                // the unused low bits of the last symbol must be zero
+                let last = self.ords[self.ord_ct - 1];
+                let unused = if self.ord_ct == 2 { 0x0F } else { 0x03 };
+                if last & unused != 0 {
+                    return Err(B64DecodeError::new(
+                        B64DecodeErrorKind::NonCanonical,
+                        self.last_index,
+                    ));
+                }
+                let q = self.flush();
+                self.ord_ct = 0;
+                Ok(Some(q))
+            }
+        }
+    }
+
+    // the complete bytes held by the current (possibly partial) quantum.
+    #[inline(always)]
+    fn flush(&self) -> B64Quantum {
+        // ords  00111111 00112222 00222233 00333333
+        let o = &self.ords;
+        B64Quantum {
+            bytes: [
+                (o[0] << 2) | (o[1] >> 4),
+                (o[1] << 4) | (o[2] >> 2),
+                (o[2] << 6) | o[3],
+            ],
+            len: self.ord_ct.saturating_sub(1),
+        }
+    }
+}
+
 #[derive(Debug, PartialOrd, PartialEq, Ord, Eq)]
 #[repr(u8)]
 enum BitSplit6Result {
@@ -222,43 +1080,54 @@ fn bit_split_6(src: &u8, b6: &mut u8, br: &mut u8, pass_of: &mut usize) -> BitSp
 /// assert_eq!(s2.as_str(), "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsu");
 /// ```
 pub fn bytes_to_b64(bytes: &[u8]) -> String {
-    let mut s = String::with_capacity((bytes.len() as f64 * 1.25) as usize + 2);
-
-    let mut b_six = 0u8;
-    let mut b_rem = 0u8;
-    let mut pass_no = 0usize;
-    let b_len = bytes.len();
-    let mut s_char_ct = 0;
-
-    for b in bytes[..].iter() {
-        match bit_split_6(b, &mut b_six, &mut b_rem, &mut pass_no) {
-            BitSplit6Result::Ready => {
-                s.push(B64_TABLE[b_six as usize]);
-                s_char_ct += 1;
-            }
-            BitSplit6Result::Resend => {
-                s.push(B64_TABLE[b_six as usize]);
-                let _ = bit_split_6(b, &mut b_six, &mut b_rem, &mut pass_no);
-                s.push(B64_TABLE[b_six as usize]);
-                s_char_ct += 2;
-            }
-        }
-    }
+    B64Config::STANDARD.encode(bytes)
+}
 
-    if b_len > 0 {
-        let x = b_rem;
-        b_rem = 0;
-        pass_no = 0;
-        let _ = bit_split_6(&x, &mut b_six, &mut b_rem, &mut pass_no);
-        s.push(B64_TABLE[b_six as usize]);
-        s_char_ct += 1;
-    }
+///
+/// converts a slice of bytes into a url and filename safe base64 encoded string (RFC 4648.5).
+///
+/// `-` and `_` are used in place of `+` and `/`, and no `=` padding is added, so the result
+/// can be used as is in urls, cookies and JWT segments.
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_b64_url;
+///
+/// let s = [0xFBu8, 0xFF, 0xBF, 0x01];
+/// let s2 = bytes_to_b64_url(&s);
+/// assert_eq!(s2.as_str(), "-_-_AQ");
+/// ```
+pub fn bytes_to_b64_url(bytes: &[u8]) -> String {
+    B64Config::URL_SAFE_NO_PAD.encode(bytes)
+}
 
-    while ((s_char_ct * 6) % 8) != 0 {
-        s.push('=');
-        s_char_ct += 1;
-    }
-    s
+///
+/// converts a slice of bytes into a base64 encoded string wrapped at 76 columns with `\r\n`,
+/// as used in MIME email bodies (RFC 2045).
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_b64_mime;
+///
+/// let s = [0u8; 60];
+/// let s2 = bytes_to_b64_mime(&s);
+/// assert_eq!(&s2[74..], "AA\r\nAAAA");
+/// ```
+pub fn bytes_to_b64_mime(bytes: &[u8]) -> String {
+    B64Config::MIME.encode(bytes)
+}
+
+///
+/// converts a slice of bytes into a base64 encoded string wrapped at 64 columns with `\n`,
+/// as used in PEM blocks (RFC 7468).
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_b64_pem;
+///
+/// let s = [0u8; 60];
+/// let s2 = bytes_to_b64_pem(&s);
+/// assert_eq!(&s2[62..], "AA\nAAAAAAAAAAAAAAAA");
+/// ```
+pub fn bytes_to_b64_pem(bytes: &[u8]) -> String {
+    B64Config::PEM.encode(bytes)
 }
 
 ///
@@ -291,11 +1160,25 @@ pub struct ToBase64Iter<'a> {
     char_count: usize,
     push_last: bool,
     pend_char: Option<char>,
+    col: usize,
+    eol: &'static [u8],
+    held: Option<char>,
+    config: &'a B64Config,
     inner: Iter<'a, u8>,
 }
 
 impl<'a> ToBase64Iter<'a> {
     pub fn new(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B64Config::STANDARD)
+    }
+
+    /// url and filename safe (RFC 4648.5) variant of [`ToBase64Iter::new`]. no `=` padding is produced.
+    pub fn new_url(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B64Config::URL_SAFE_NO_PAD)
+    }
+
+    /// same as [`ToBase64Iter::new`], but encodes using the given config
+    pub fn new_with(i: Iter<'a, u8>, config: &'a B64Config) -> Self {
         Self {
             six_bits: 0,
             rem_bits: 0,
@@ -303,6 +1186,10 @@ impl<'a> ToBase64Iter<'a> {
             pend_char: None,
             char_count: 0,
             push_last: true,
+            col: 0,
+            eol: &[],
+            held: None,
+            config,
             inner: i,
         }
     }
@@ -316,18 +1203,18 @@ impl<'a> ToBase64Iter<'a> {
                 let c: char;
                 match bit_split_6(b, &mut self.six_bits, &mut self.rem_bits, &mut self.pass_no) {
                     BitSplit6Result::Ready => {
-                        c = B64_TABLE[self.six_bits as usize];
+                        c = self.config.alphabet.symbol(self.six_bits);
                         self.char_count += 1;
                     }
                     BitSplit6Result::Resend => {
-                        c = B64_TABLE[self.six_bits as usize];
+                        c = self.config.alphabet.symbol(self.six_bits);
                         let _ = bit_split_6(
                             b,
                             &mut self.six_bits,
                             &mut self.rem_bits,
                             &mut self.pass_no,
                         );
-                        self.pend_char = Some(B64_TABLE[self.six_bits as usize]);
+                        self.pend_char = Some(self.config.alphabet.symbol(self.six_bits));
                         self.char_count += 2;
                     }
                 }
@@ -345,8 +1232,8 @@ impl<'a> ToBase64Iter<'a> {
                     );
                     self.char_count += 1;
                     self.push_last = false;
-                    return Some(B64_TABLE[self.six_bits as usize]);
-                } else if ((self.char_count * 6) % 8) != 0 {
+                    return Some(self.config.alphabet.symbol(self.six_bits));
+                } else if self.config.pad && b64_pad_len(self.char_count) > 0 {
                     self.char_count += 1;
                     return Some('=');
                 }
@@ -360,63 +1247,77 @@ impl Iterator for ToBase64Iter<'_> {
     type Item = char;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.next_char()
+        if self.config.line_len == 0 {
+            return self.next_char();
+        }
+        // a line ending is only started once there is a char to follow it
+        if let Some((&b, rest)) = self.eol.split_first() {
+            self.eol = rest;
+            return Some(b as char);
+        }
+        if let Some(c) = self.held.take() {
+            self.col = 1;
+            return Some(c);
+        }
+        let c = self.next_char()?;
+        if self.col == self.config.line_len {
+            let eol = self.config.line_ending.as_str().as_bytes();
+            self.eol = &eol[1..];
+            self.held = Some(c);
+            return Some(eol[0] as char);
+        }
+        self.col += 1;
+        Some(c)
     }
 }
 
 pub trait CjToBase64Iter {
     fn iter_to_b64(&self) -> ToBase64Iter<'_>;
+    fn iter_to_b64_url(&self) -> ToBase64Iter<'_>;
+    fn iter_to_b64_with<'a>(&'a self, config: &'a B64Config) -> ToBase64Iter<'a>;
 }
 
-impl CjToBase64Iter for &[u8] {
-    /// Iterator for a slice of bytes that produces Base64 encoded chars
+impl<T: AsRef<[u8]> + ?Sized> CjToBase64Iter for T {
+    /// Iterator for anything holding bytes (`&[u8]`, `&str`, `Vec<u8>`, `[u8; N]`, `Box<[u8]>`,
+    /// `Cow<[u8]>`, ...) that produces Base64 encoded chars
     /// ```
     /// # use cj_common::prelude::CjToBase64Iter;
-    /// let s = "Many hands make light work.".as_bytes();
     /// let mut s2 = String::new();
-    /// for c in s.iter_to_b64() {
+    /// for c in "Many hands make light work.".iter_to_b64() {
     ///     s2.push(c);
     /// }
     /// assert_eq!(s2.as_str(), "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsu");
+    ///
+    /// let s = [0x4Du8, 0x61, 0x6E];
+    /// assert_eq!(s.iter_to_b64().collect::<String>(), "TWFu");
     /// ```
     fn iter_to_b64(&self) -> ToBase64Iter<'_> {
-        ToBase64Iter::new(self[..].iter())
+        ToBase64Iter::new(self.as_ref().iter())
     }
-}
-
-impl CjToBase64Iter for &str {
-    /// Iterator for str that produces Base64 encoded chars
+    /// Iterator for anything holding bytes that produces url and filename safe Base64 encoded chars
     /// ```
     /// # use cj_common::prelude::CjToBase64Iter;
-    /// let mut s2 = String::new();
-    /// for c in "Many hands make light work.".iter_to_b64() {
-    ///     s2.push(c);
-    /// }
-    /// assert_eq!(s2.as_str(), "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsu");
+    /// let s = [0xFBu8, 0xFF, 0xBF, 0x01];
+    /// let s2 = s.iter_to_b64_url().collect::<String>();
+    /// assert_eq!(s2.as_str(), "-_-_AQ");
     /// ```
-    fn iter_to_b64(&self) -> ToBase64Iter<'_> {
-        ToBase64Iter::new(self.as_bytes()[..].iter())
+    fn iter_to_b64_url(&self) -> ToBase64Iter<'_> {
+        ToBase64Iter::new_url(self.as_ref().iter())
     }
-}
-
-impl CjToBase64Iter for Vec<u8> {
-    /// Iterator for a `Vec<u8>` that produces Base64 encoded chars
+    /// Iterator for anything holding bytes that produces Base64 encoded chars using the given config
     /// ```
-    /// # use cj_common::prelude::CjToBase64Iter;
-    /// let s = Vec::<u8>::from("Many hands make light work.");
-    /// let mut s2 = String::new();
-    /// for c in s.iter_to_b64() {
-    ///     s2.push(c);
-    /// }
-    /// assert_eq!(s2.as_str(), "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsu");
+    /// # use cj_common::prelude::*;
+    /// let s = vec![0xFBu8, 0xFF, 0xBF, 0x01];
+    /// let s2 = s.iter_to_b64_with(&B64Config::URL_SAFE).collect::<String>();
+    /// assert_eq!(s2.as_str(), "-_-_AQ==");
     /// ```
-    fn iter_to_b64(&self) -> ToBase64Iter<'_> {
-        ToBase64Iter::new(self[..].iter())
+    fn iter_to_b64_with<'a>(&'a self, config: &'a B64Config) -> ToBase64Iter<'a> {
+        ToBase64Iter::new_with(self.as_ref().iter(), config)
     }
 }
 
 ///
-/// Iterator for a Base64 encoded str that returns decoded byte
+/// Iterator for a Base64 encoded str, or ASCII bytes, that returns decoded byte
 ///
 /// ```
 ///  # use cj_common::prelude::{CjFromBase64Iter,FromBase64Iter};
@@ -431,104 +1332,130 @@ impl CjToBase64Iter for Vec<u8> {
 /// assert_eq!(r.to_string().as_str(), s);
 /// ```
 pub struct FromBase64Iter<'a> {
-    buf_left: u8,
-    buf_ct: u8,
-    had_pad: bool,
-    total_added: usize,
-    inner: Chars<'a>,
+    state: B64DecodeState,
+    pend: B64Quantum,
+    pend_pos: usize,
+    done: bool,
+    index: usize,
+    error: Option<B64DecodeError>,
+    config: &'a B64Config,
+    inner: Iter<'a, u8>,
 }
 
 impl<'a> FromBase64Iter<'a> {
     pub fn new(i: Chars<'a>) -> Self {
+        Self::new_with(i, &B64Config::STANDARD)
+    }
+
+    /// url and filename safe (RFC 4648.5) variant of [`FromBase64Iter::new`]. `=` padding is optional.
+    pub fn new_url(i: Chars<'a>) -> Self {
+        Self::new_with(i, &B64Config::URL_SAFE_NO_PAD)
+    }
+
+    /// same as [`FromBase64Iter::new`], but decodes using the given config.
+    ///
+    /// a strict config ends the iterator at the first problem in the input, without returning
+    /// the bytes of the quantum it was found in.
+    pub fn new_with(i: Chars<'a>, config: &'a B64Config) -> Self {
+        Self::new_bytes_with(i.as_str().as_bytes().iter(), config)
+    }
+
+    /// same as [`FromBase64Iter::new`], for Base64 held as ASCII bytes
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let data = b"TWFu".to_vec();
+    /// let v = FromBase64Iter::new_bytes(data.iter()).collect::<Vec<u8>>();
+    /// assert_eq!(v.as_slice(), b"Man");
+    /// ```
+    pub fn new_bytes(i: Iter<'a, u8>) -> Self {
+        Self::new_bytes_with(i, &B64Config::STANDARD)
+    }
+
+    /// same as [`FromBase64Iter::new_with`], for Base64 held as ASCII bytes
+    pub fn new_bytes_with(i: Iter<'a, u8>, config: &'a B64Config) -> Self {
         Self {
-            buf_left: 0,
-            buf_ct: 0,
-            had_pad: false,
-            total_added: 0,
+            state: B64DecodeState::new(),
+            pend: B64Quantum::default(),
+            pend_pos: 0,
+            done: false,
+            index: 0,
+            error: None,
+            config,
             inner: i,
         }
     }
 
-    // fn new_adapter(i: Chars) -> Self {
-    //     Self {
-    //         buf_left: 0,
-    //         buf_ct: 0,
-    //         had_pad: false,
-    //         total_added: 0,
-    //         inner: i,
-    //     }
-    // }
+    /// the problem that ended decoding, if any. byte offsets are relative to the start of the
+    /// str or bytes the iterator was created from.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut it = "TWFu&TWFu".iter_b64_to_byte();
+    /// let v = it.by_ref().collect::<Vec<u8>>();
+    /// assert_eq!(v.as_slice(), b"Man");
+    /// assert_eq!(
+    ///     it.error(),
+    ///     Some(B64DecodeError::new(B64DecodeErrorKind::InvalidSymbol, 4))
+    /// );
+    /// ```
+    pub fn error(&self) -> Option<B64DecodeError> {
+        self.error
+    }
+
+    #[inline]
+    fn next_pending(&mut self) -> Option<u8> {
+        if self.pend_pos < self.pend.len {
+            let b = self.pend.bytes[self.pend_pos];
+            self.pend_pos += 1;
+            return Some(b);
+        }
+        None
+    }
+
+    #[inline]
+    fn start_quantum(&mut self, q: B64Quantum) -> Option<u8> {
+        self.pend = q;
+        self.pend_pos = 0;
+        self.next_pending()
+    }
+
+    // decoding stopped at a problem in the input. a lenient config keeps what it can.
+    fn fail(&mut self, e: B64DecodeError) -> Option<u8> {
+        self.done = true;
+        self.error = Some(e);
+        if self.config.strict {
+            None
+        } else {
+            self.start_quantum(self.state.flush())
+        }
+    }
+
     #[inline]
     fn next_byte(&mut self) -> Option<u8> {
-        let return_b: u8;
-        while let Some(c) = &self.inner.next() {
-            let r = b64_char_to_ord(c);
-            match r {
-                CharToOrdResult::Ok(b) => {
-                    if self.had_pad {
-                        break;
-                    }
-                    match self.buf_ct {
-                        0 => {
-                            // buf_left is empty
-                            // drop two bits from 'b' and take 6
-                            // 00XXXXXX
-                            self.buf_left = b << 2;
-                            self.buf_ct += 1;
-                        }
-                        1 => {
-                            // buf_left has 6 bits XXXXXX00
-                            // drop two bits from 'b' and take 2
-                            self.buf_left += (b << 2) >> 6;
-                            // push the complete byte
-                            return_b = self.buf_left;
-                            // now take the remaining 4
-                            self.buf_left = b << 4;
-                            self.buf_ct += 1;
-                            self.total_added += 1;
-                            return Some(return_b);
-                        }
-                        2 => {
-                            // buf_left has 4 bits XXXX0000
-                            // drop two bits from 'b' and take 4
-                            self.buf_left += (b << 2) >> 4;
-                            // push the complete byte
-                            return_b = self.buf_left;
-                            self.buf_left = b << 6;
-                            self.buf_ct += 1;
-                            self.total_added += 1;
-                            return Some(return_b);
-                        }
-                        3 => {
-                            // buf_left has 2 bits XX000000
-                            // 'b' only has 6 bits. take them all
-                            self.buf_left += b;
-                            // push the complete byte
-                            return_b = self.buf_left;
-                            self.buf_ct = 0;
-                            self.total_added += 1;
-                            return Some(return_b);
-                        }
-                        _ => unreachable!(),
-                    }
-                }
-                CharToOrdResult::Pad => self.had_pad = true,
-                CharToOrdResult::WhiteSpace => {
-                    if self.had_pad {
-                        break;
-                    }
-                }
-                CharToOrdResult::Invalid => {
-                    return None;
-                }
+        if let Some(b) = self.next_pending() {
+            return Some(b);
+        }
+        if self.done {
+            return None;
+        }
+        while let Some(b) = self.inner.next() {
+            let i = self.index;
+            self.index += 1;
+            match self
+                .state
+                .push(self.config, self.config.alphabet.byte_to_ord(*b), i)
+            {
+                Ok(Some(q)) => return self.start_quantum(q),
+                Ok(None) => {}
+                Err(e) => return self.fail(e),
             }
         }
 
-        if self.total_added > 0 && (self.buf_ct != 0) && (!self.had_pad) {
-            return Some(self.buf_left);
+        self.done = true;
+        match self.state.finish(self.config, self.index) {
+            Ok(Some(q)) => self.start_quantum(q),
+            Ok(None) => None,
+            Err(e) => self.fail(e),
         }
-
-        None
     }
 }
 
@@ -542,10 +1469,12 @@ impl Iterator for FromBase64Iter<'_> {
 
 pub trait CjFromBase64Iter {
     fn iter_b64_to_byte(&self) -> FromBase64Iter<'_>;
+    fn iter_b64_url_to_byte(&self) -> FromBase64Iter<'_>;
+    fn iter_b64_to_byte_with<'a>(&'a self, config: &'a B64Config) -> FromBase64Iter<'a>;
 }
 
-impl CjFromBase64Iter for &str {
-    /// Iterator for decoding a Base64 endoced str to bytes
+impl<T: AsRef<[u8]> + ?Sized> CjFromBase64Iter for T {
+    /// Iterator for decoding Base64 held in a str or as ASCII bytes to bytes
     /// ```
     /// # use cj_common::prelude::CjFromBase64Iter;
     /// let mut v = Vec::new();
@@ -556,13 +1485,332 @@ impl CjFromBase64Iter for &str {
     /// let r = String::from_utf8_lossy(v.as_slice());
     /// let s = "Many hands make light work.";
     /// assert_eq!(r.to_string().as_str(), s);
+    ///
+    /// let v = b"TWFueSBoYW5kcw==".iter_b64_to_byte().collect::<Vec<u8>>();
+    /// assert_eq!(v.as_slice(), b"Many hands");
     /// ```
     fn iter_b64_to_byte(&self) -> FromBase64Iter<'_> {
-        FromBase64Iter::new(self.chars())
+        FromBase64Iter::new_bytes(self.as_ref().iter())
+    }
+    /// Iterator for decoding url and filename safe Base64 held in a str or as ASCII bytes to bytes
+    /// ```
+    /// # use cj_common::prelude::CjFromBase64Iter;
+    /// let v = "-_-_AQ".iter_b64_url_to_byte().collect::<Vec<u8>>();
+    /// assert_eq!(v, vec![0xFBu8, 0xFF, 0xBF, 0x01]);
+    /// ```
+    fn iter_b64_url_to_byte(&self) -> FromBase64Iter<'_> {
+        FromBase64Iter::new_bytes_with(self.as_ref().iter(), &B64Config::URL_SAFE_NO_PAD)
+    }
+    /// Iterator for decoding Base64 held in a str or as ASCII bytes to bytes using the given config
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let cfg = B64Config::STANDARD.with_strict(true);
+    /// let v = "TWE=".iter_b64_to_byte_with(&cfg).collect::<Vec<u8>>();
+    /// assert_eq!(v.as_slice(), b"Ma");
+    /// ```
+    fn iter_b64_to_byte_with<'a>(&'a self, config: &'a B64Config) -> FromBase64Iter<'a> {
+        FromBase64Iter::new_bytes_with(self.as_ref().iter(), config)
+    }
+}
+
+// size of the internal buffers used by B64Writer and B64Reader
+const B64_IO_BUF_LEN: usize = 1024;
+
+/// Base64 encoder that writes the encoded chars to any [`Write`] sink as bytes are written to it.
+///
+/// bytes that do not make a complete 3 byte quantum are held until more are written, so the
+/// writer must be ended with [`B64Writer::finish`] to write the final quantum and its padding.
+/// dropping an unfinished writer also writes it, but any error is lost.
+/// ```
+/// # use cj_common::prelude::*;
+/// use std::io::Write;
+/// let mut w = B64Writer::new(Vec::new());
+/// w.write_all(b"Many hands ").unwrap();
+/// w.write_all(b"make light work.").unwrap();
+/// let v = w.finish().unwrap();
+/// assert_eq!(v.as_slice(), b"TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsu");
+/// ```
+pub struct B64Writer<W: Write> {
+    inner: Option<W>,
+    config: B64Config,
+    pend: [u8; 3],
+    pend_len: usize,
+    col: usize,
+    out: [u8; B64_IO_BUF_LEN],
+}
+
+impl<W: Write> B64Writer<W> {
+    pub fn new(inner: W) -> Self {
+        Self::new_with(inner, &B64Config::STANDARD)
+    }
+
+    /// url and filename safe (RFC 4648.5) variant of [`B64Writer::new`]. no `=` padding is written.
+    pub fn new_url(inner: W) -> Self {
+        Self::new_with(inner, &B64Config::URL_SAFE_NO_PAD)
+    }
+
+    /// same as [`B64Writer::new`], but encodes using (a copy of) the given config.
+    pub fn new_with(inner: W, config: &B64Config) -> Self {
+        Self {
+            inner: Some(inner),
+            config: *config,
+            pend: [0; 3],
+            pend_len: 0,
+            col: 0,
+            out: [0; B64_IO_BUF_LEN],
+        }
+    }
+
+    /// writes the final quantum and padding, flushes and returns the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        let r = self.write_tail();
+        // taken even on error, so dropping self does not write the tail again
+        let inner = self.inner.take().expect("B64Writer inner writer missing");
+        r.map(|_| inner)
+    }
+
+    /// the inner writer
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().expect("B64Writer inner writer missing")
+    }
+
+    /// the inner writer. writing to it directly may corrupt the Base64 output.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.as_mut().expect("B64Writer inner writer missing")
+    }
+
+    fn write_tail(&mut self) -> io::Result<()> {
+        if self.pend_len > 0 {
+            let pend = self.pend;
+            self.write_encoded(&pend[..self.pend_len])?;
+            self.pend_len = 0;
+        }
+        self.get_mut().flush()
+    }
+
+    // encodes bytes into out, then writes out to inner, adding line endings where needed
+    fn write_encoded(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let mut n = 0;
+        let out = &mut self.out;
+        self.config.encode_unwrapped(bytes, |c| {
+            out[n] = c;
+            n += 1;
+        });
+
+        let inner = self.inner.as_mut().expect("B64Writer inner writer missing");
+        let line_len = self.config.line_len;
+        if line_len == 0 {
+            return inner.write_all(&self.out[..n]);
+        }
+        let mut chars = &self.out[..n];
+        while !chars.is_empty() {
+            if self.col == line_len {
+                inner.write_all(self.config.line_ending.as_str().as_bytes())?;
+                self.col = 0;
+            }
+            let take = (line_len - self.col).min(chars.len());
+            inner.write_all(&chars[..take])?;
+            self.col += take;
+            chars = &chars[take..];
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for B64Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // top up a held partial quantum first
+        if self.pend_len > 0 {
+            let used = (3 - self.pend_len).min(buf.len());
+            self.pend[self.pend_len..self.pend_len + used].copy_from_slice(&buf[..used]);
+            self.pend_len += used;
+            if self.pend_len == 3 {
+                let pend = self.pend;
+                self.write_encoded(&pend)?;
+                self.pend_len = 0;
+            }
+            return Ok(used);
+        }
+
+        // then as many whole quanta as fit the out buffer, holding back anything less than a quantum
+        let whole = (buf.len() / 3 * 3).min(B64_IO_BUF_LEN / 4 * 3);
+        if whole > 0 {
+            self.write_encoded(&buf[..whole])?;
+            Ok(whole)
+        } else {
+            self.pend[..buf.len()].copy_from_slice(buf);
+            self.pend_len = buf.len();
+            Ok(buf.len())
+        }
+    }
+
+    /// flushes the inner writer. a held partial quantum is not written until [`B64Writer::finish`].
+    fn flush(&mut self) -> io::Result<()> {
+        self.get_mut().flush()
+    }
+}
+
+impl<W: Write> Drop for B64Writer<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.write_tail();
+        }
+    }
+}
+
+/// Base64 decoder that reads encoded chars from any [`Read`] source and returns the decoded bytes.
+///
+/// whitespace is skipped according to the config. decoding problems are handled like
+/// [`FromBase64Iter`] does: a lenient config ends the stream at the first problem, keeping the
+/// bytes decoded so far, while a strict config fails the read with [`io::ErrorKind::InvalidData`].
+/// either way [`B64Reader::error`] returns the problem.
+/// ```
+/// # use cj_common::prelude::*;
+/// use std::io::Read;
+/// let src = "TWFueSBoYW5kcyBt\r\nYWtlIGxpZ2h0IHdvcmsu".as_bytes();
+/// let mut r = B64Reader::new(src);
+/// let mut s = String::new();
+/// r.read_to_string(&mut s).unwrap();
+/// assert_eq!(s.as_str(), "Many hands make light work.");
+/// ```
+pub struct B64Reader<R: Read> {
+    inner: R,
+    config: B64Config,
+    state: B64DecodeState,
+    dec: [u8; B64_IO_BUF_LEN],
+    dec_pos: usize,
+    dec_len: usize,
+    index: usize,
+    done: bool,
+    error: Option<B64DecodeError>,
+}
+
+impl<R: Read> B64Reader<R> {
+    pub fn new(inner: R) -> Self {
+        Self::new_with(inner, &B64Config::STANDARD)
+    }
+
+    /// url and filename safe (RFC 4648.5) variant of [`B64Reader::new`]. `=` padding is optional.
+    pub fn new_url(inner: R) -> Self {
+        Self::new_with(inner, &B64Config::URL_SAFE_NO_PAD)
+    }
+
+    /// same as [`B64Reader::new`], but decodes using (a copy of) the given config.
+    pub fn new_with(inner: R, config: &B64Config) -> Self {
+        Self {
+            inner,
+            config: *config,
+            state: B64DecodeState::new(),
+            dec: [0; B64_IO_BUF_LEN],
+            dec_pos: 0,
+            dec_len: 0,
+            index: 0,
+            done: false,
+            error: None,
+        }
+    }
+
+    /// the problem that ended decoding, if any. the index is the byte offset in the source.
+    pub fn error(&self) -> Option<B64DecodeError> {
+        self.error
+    }
+
+    /// the inner reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// the inner reader. reading from it directly skips that data.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// returns the inner reader, dropping any decoded bytes not yet read
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    // reads a block of encoded chars and decodes what it can into dec
+    fn fill(&mut self) -> io::Result<()> {
+        let mut enc = [0u8; B64_IO_BUF_LEN];
+        let n = loop {
+            match self.inner.read(&mut enc) {
+                Ok(n) => break n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        };
+        self.dec_pos = 0;
+        self.dec_len = 0;
+
+        if n == 0 {
+            self.done = true;
+            match self.state.finish(&self.config, self.index) {
+                Ok(Some(q)) => self.push_quantum(q),
+                Ok(None) => {}
+                Err(e) => self.fail(e),
+            }
+            return Ok(());
+        }
+
+        for &b in enc[..n].iter() {
+            let i = self.index;
+            self.index += 1;
+            match self
+                .state
+                .push(&self.config, self.config.alphabet.byte_to_ord(b), i)
+            {
+                Ok(Some(q)) => self.push_quantum(q),
+                Ok(None) => {}
+                Err(e) => {
+                    self.fail(e);
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn push_quantum(&mut self, q: B64Quantum) {
+        let b = q.as_slice();
+        self.dec[self.dec_len..self.dec_len + b.len()].copy_from_slice(b);
+        self.dec_len += b.len();
+    }
+
+    fn fail(&mut self, e: B64DecodeError) {
+        self.done = true;
+        self.error = Some(e);
+        if !self.config.strict {
+            self.push_quantum(self.state.flush());
+        }
+    }
+}
+
+impl<R: Read> Read for B64Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.dec_pos == self.dec_len {
+            if self.done {
+                return match self.error {
+                    Some(e) if self.config.strict => {
+                        Err(io::Error::new(io::ErrorKind::InvalidData, e))
+                    }
+                    _ => Ok(0),
+                };
+            }
+            self.fill()?;
+        }
+        let n = (self.dec_len - self.dec_pos).min(buf.len());
+        buf[..n].copy_from_slice(&self.dec[self.dec_pos..self.dec_pos + n]);
+        self.dec_pos += n;
+        Ok(n)
     }
 }
 
-/// converts a Base64 encoded str into a vec of bytes
+/// converts Base64 encoded data, either a str or ASCII bytes, into a vec of bytes
 /// ```
 /// # use cj_common::prelude::b64_to_bytes;
 /// let v = b64_to_bytes("TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsu");
@@ -571,129 +1819,255 @@ impl CjFromBase64Iter for &str {
 ///    let s = "Many hands make light work.".as_bytes().to_vec();
 ///    assert_eq!(x, s);
 /// }
+///
+/// // bytes read from a file or socket need no UTF-8 check first
+/// let data = b"TWFueSBoYW5kcw==".to_vec();
+/// assert_eq!(b64_to_bytes(&data), Some(b"Many hands".to_vec()));
+/// ```
+pub fn b64_to_bytes(data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    B64Config::STANDARD.decode(data)
+}
+
+/// converts url and filename safe Base64 encoded data (RFC 4648.5), either a str or ASCII bytes,
+/// into a vec of bytes.
+/// `=` padding is optional.
+/// ```
+/// # use cj_common::prelude::b64_url_to_bytes;
+/// let v = b64_url_to_bytes("-_-_AQ");
+/// assert_eq!(v, Some(vec![0xFBu8, 0xFF, 0xBF, 0x01]));
+///
+/// let v = b64_url_to_bytes("-_-_AQ==");
+/// assert_eq!(v, Some(vec![0xFBu8, 0xFF, 0xBF, 0x01]));
+/// ```
+pub fn b64_url_to_bytes(data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    B64Config::URL_SAFE_NO_PAD.decode(data)
+}
+
+/// converts Base64 encoded data, either a str or ASCII bytes, into a vec of bytes, rejecting the
+/// input on the first problem
+/// instead of returning what was decoded up to it. `=` padding is optional and whitespace is skipped.
+/// ```
+/// # use cj_common::prelude::*;
+/// let v = b64_to_bytes_strict("TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLi4=");
+/// assert_eq!(v, Ok("Many hands make light work...".as_bytes().to_vec()));
+///
+/// let e = b64_to_bytes_strict("TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLi4=nope").unwrap_err();
+/// assert_eq!(e.kind, B64DecodeErrorKind::TrailingData);
+/// assert_eq!(e.index, 40);
+/// ```
+pub fn b64_to_bytes_strict(data: impl AsRef<[u8]>) -> Result<Vec<u8>, B64DecodeError> {
+    B64Config::STANDARD.try_decode(data)
+}
+
+/// url and filename safe (RFC 4648.5) variant of [`b64_to_bytes_strict`]
+/// ```
+/// # use cj_common::prelude::*;
+/// assert_eq!(b64_url_to_bytes_strict("-_-_AQ"), Ok(vec![0xFBu8, 0xFF, 0xBF, 0x01]));
+///
+/// let e = b64_url_to_bytes_strict("-_-_AR").unwrap_err();
+/// assert_eq!(e, B64DecodeError::new(B64DecodeErrorKind::NonCanonical, 5));
 /// ```
-pub fn b64_to_bytes(data: &str) -> Option<Vec<u8>> {
-    let mut v = Vec::<u8>::with_capacity((data.len() as f64 * 0.8) as usize);
-    let mut buf_left = 0u8;
-    let mut buf_ct = 0usize;
-    let mut had_pad = false;
-    //let mut had_error = false;
-    let mut total_added = 0usize;
-    for c in data.chars() {
-        let r = b64_char_to_ord(&c);
-        match r {
-            CharToOrdResult::Ok(b) => {
-                if had_pad {
-                    break;
-                }
-                match buf_ct {
-                    0 => {
-                        // buf_left is empty
-                        // drop two bits from 'b' and take 6
-                        // 00XXXXXX
-                        buf_left = b << 2;
-                        buf_ct += 1;
-                    }
-                    1 => {
-                        // buf_left has 6 bits XXXXXX00
-                        // drop two bits from 'b' and take 2
-                        buf_left += (b << 2) >> 6;
-                        // push the complete byte
-                        v.push(buf_left);
-                        // now take the remaining 4
-                        buf_left = b << 4;
-                        buf_ct += 1;
-                        total_added += 1;
-                    }
-                    2 => {
-                        // buf_left has 4 bits XXXX0000
-                        // drop two bits from 'b' and take 4
-                        buf_left += (b << 2) >> 4;
-                        // push the complete byte
-                        v.push(buf_left);
-                        buf_left = b << 6;
-                        buf_ct += 1;
-                        total_added += 1;
-                    }
-                    3 => {
-                        // buf_left has 2 bits XX000000
-                        // 'b' only has 6 bits. take them all
-                        buf_left += b;
-                        // push the complete byte
-                        v.push(buf_left);
-                        buf_ct = 0;
-                        total_added += 1;
-                    }
-                    _ => unreachable!(),
-                }
-            }
-            CharToOrdResult::Pad => had_pad = true,
-            CharToOrdResult::WhiteSpace => {
-                if had_pad {
-                    break;
-                }
-            }
-            CharToOrdResult::Invalid => {
-                //had_error = true;
-                break;
-            }
-        }
+pub fn b64_url_to_bytes_strict(data: impl AsRef<[u8]>) -> Result<Vec<u8>, B64DecodeError> {
+    B64Config::URL_SAFE_NO_PAD.try_decode(data)
+}
+
+// value of a Base64 char and 0xFF, or 0 and 0 if it is not one, without branching on c
+#[inline(always)]
+const fn ct_b64_sextet(c: u8, url: bool) -> (u8, u8) {
+    let (c62, c63) = if url { (b'-', b'_') } else { (b'+', b'/') };
+    let upper = ct_in_range(c, b'A', b'Z');
+    let lower = ct_in_range(c, b'a', b'z');
+    let digit = ct_in_range(c, b'0', b'9');
+    let s62 = ct_in_range(c, c62, c62);
+    let s63 = ct_in_range(c, c63, c63);
+    let v = (upper & c.wrapping_sub(b'A'))
+        | (lower & c.wrapping_sub(b'a' - 26))
+        | (digit & c.wrapping_add(52 - b'0'))
+        | (s62 & 62)
+        | (s63 & 63);
+    (v, upper | lower | digit | s62 | s63)
+}
+
+// constant-time decoding shared by b64_to_bytes_ct and b64_url_to_bytes_ct
+fn b64_decode_ct(data: &[u8], url: bool) -> Option<Vec<u8>> {
+    // padding only says how long the data is, which is not secret
+    let mut data = data;
+    if data.len().is_multiple_of(4) {
+        data = data
+            .strip_suffix(b"==")
+            .or_else(|| data.strip_suffix(b"="))
+            .unwrap_or(data);
+    }
+    if data.len() % 4 == 1 {
+        return None;
     }
 
-    if total_added > 0 {
-        if (buf_ct != 0) && (!had_pad) {
-            v.push(buf_left);
+    let mut v = Vec::with_capacity(data.len() / 4 * 3 + 2);
+    let mut valid = 0xFFu8;
+    for q in data.chunks(4) {
+        let mut n = 0u32;
+        for (i, c) in q.iter().enumerate() {
+            let (x, ok) = ct_b64_sextet(*c, url);
+            valid &= ok;
+            n |= (x as u32) << (18 - 6 * i);
         }
-        // we were able to decode something, even if there was an error. return what we have.
-        return Some(v);
+        // the bits of a final partial quantum that do not make a byte must be zero
+        let unused = (1u32 << (8 * (4 - q.len()))) - 1;
+        valid &= ct_is_zero(n & unused);
+        v.extend_from_slice(&n.to_be_bytes()[1..q.len()]);
+    }
+    if std::hint::black_box(valid) == 0xFF {
+        Some(v)
+    } else {
+        None
     }
+}
+
+/// constant-time variant of [`b64_to_bytes`] for secret material. each char is decoded with
+/// arithmetic rather than a table, and the whole input is read before any problem is reported.
+///
+/// only the timing of the input's length leaks. `=` padding is optional, and whitespace is not
+/// skipped, as that would depend on the chars; remove line breaks first. returns None if any char
+/// is not Base64 or the final quantum has bits set that do not make a byte.
+/// ```
+/// # use cj_common::prelude::*;
+/// let v = b64_to_bytes_ct("TWFueSBoYW5kcw==");
+/// assert_eq!(v, Some(b"Many hands".to_vec()));
+/// assert_eq!(b64_to_bytes_ct("TWFueSBoYW5kcw"), Some(b"Many hands".to_vec()));
+///
+/// assert_eq!(b64_to_bytes_ct("TWFueSBoYW5kcx=="), None);
+/// assert_eq!(b64_to_bytes_ct("TWFue SBoYW5kcw=="), None);
+/// ```
+pub fn b64_to_bytes_ct(data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    b64_decode_ct(data.as_ref(), false)
+}
 
-    None
+/// url and filename safe (RFC 4648.5) variant of [`b64_to_bytes_ct`]
+/// ```
+/// # use cj_common::prelude::*;
+/// assert_eq!(b64_url_to_bytes_ct("-_-_AQ"), Some(vec![0xFBu8, 0xFF, 0xBF, 0x01]));
+/// assert_eq!(b64_url_to_bytes_ct("+/+/AQ"), None);
+/// ```
+pub fn b64_url_to_bytes_ct(data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    b64_decode_ct(data.as_ref(), true)
 }
 
 pub trait CjToBase64 {
     fn to_b64_string(&self) -> String;
+    fn to_b64_url_string(&self) -> String;
+    fn to_b64_string_with(&self, config: &B64Config) -> String;
 }
 
-impl CjToBase64 for &str {
-    /// converts str to Base64 encoded String
+impl<T: AsRef<[u8]> + ?Sized> CjToBase64 for T {
+    /// converts anything holding bytes (`&str`, `String`, `Vec<u8>`, `[u8; N]`, `Box<[u8]>`,
+    /// `Cow<[u8]>`, ...) to a Base64 encoded String
     /// ```
     /// # use cj_common::prelude::CjToBase64;
     /// let s = "Many hands make light work...8675";
     /// let s2 = s.to_b64_string();
-    /// println!("{}", s2);
     /// assert_eq!(s2.as_str(), "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLi44Njc1");
+    ///
+    /// let key = [0xDEu8, 0xAD, 0xBE, 0xEF];
+    /// assert_eq!(key.to_b64_string(), "3q2+7w==");
     /// ```
     fn to_b64_string(&self) -> String {
-        bytes_to_b64(self.as_bytes())
+        bytes_to_b64(self.as_ref())
     }
-}
-
-impl CjToBase64 for String {
-    /// converts String to Base64 encoded String
+    /// converts anything holding bytes to a url and filename safe Base64 encoded String
     /// ```
     /// # use cj_common::prelude::CjToBase64;
-    /// let s = String::from("Many hands make light work...8675");
-    /// let s2 = s.to_b64_string();
-    /// println!("{}", s2);
-    /// assert_eq!(s2.as_str(), "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLi44Njc1");
+    /// let s = String::from("Many hands make light work..");
+    /// let s2 = s.to_b64_url_string();
+    /// assert_eq!(s2.as_str(), "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLg");
     /// ```
-    fn to_b64_string(&self) -> String {
-        bytes_to_b64(self.as_bytes())
+    fn to_b64_url_string(&self) -> String {
+        bytes_to_b64_url(self.as_ref())
     }
-}
-
-impl CjToBase64 for Vec<u8> {
-    /// converts `Vec<u8>` to Base64 encoded String
+    /// converts anything holding bytes to a Base64 encoded String using the given config
     /// ```
-    /// # use cj_common::prelude::CjToBase64;
-    /// let s = Vec::<u8>::from("Many hands make light work...8675");
-    /// let s2 = s.to_b64_string();
-    /// println!("{}", s2);
-    /// assert_eq!(s2.as_str(), "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLi44Njc1");
+    /// # use cj_common::prelude::*;
+    /// let s = Vec::<u8>::from("Many hands make light work..");
+    /// let s2 = s.to_b64_string_with(&B64Config::URL_SAFE);
+    /// assert_eq!(s2.as_str(), "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLg==");
     /// ```
-    fn to_b64_string(&self) -> String {
-        bytes_to_b64(self.as_slice())
+    fn to_b64_string_with(&self, config: &B64Config) -> String {
+        config.encode(self.as_ref())
+    }
+}
+
+/// types that Base64 can be decoded straight into.
+///
+/// fixed-size arrays require the decoded length to match exactly, so keys and hashes round-trip
+/// without manual slicing.
+/// ```
+/// # use cj_common::prelude::*;
+/// let key = <[u8; 4]>::from_b64("3q2+7w==").unwrap();
+/// assert_eq!(key, [0xDE, 0xAD, 0xBE, 0xEF]);
+///
+/// let e = <[u8; 5]>::from_b64("3q2+7w==").unwrap_err();
+/// assert_eq!(e.kind, B64DecodeErrorKind::LengthMismatch);
+///
+/// let v: Box<[u8]> = FromBase64::from_b64_url("-_-_AQ").unwrap();
+/// assert_eq!(&v[..], &[0xFB, 0xFF, 0xBF, 0x01]);
+/// ```
+pub trait FromBase64: Sized {
+    /// decodes data using the given config
+    fn from_b64_with(data: impl AsRef<[u8]>, config: &B64Config) -> Result<Self, B64DecodeError>;
+
+    /// decodes standard (RFC 4648.4) Base64
+    #[inline]
+    fn from_b64(data: impl AsRef<[u8]>) -> Result<Self, B64DecodeError> {
+        Self::from_b64_with(data, &B64Config::STANDARD)
+    }
+
+    /// decodes url and filename safe (RFC 4648.5) Base64
+    #[inline]
+    fn from_b64_url(data: impl AsRef<[u8]>) -> Result<Self, B64DecodeError> {
+        Self::from_b64_with(data, &B64Config::URL_SAFE_NO_PAD)
+    }
+}
+
+impl FromBase64 for Vec<u8> {
+    #[inline]
+    fn from_b64_with(data: impl AsRef<[u8]>, config: &B64Config) -> Result<Self, B64DecodeError> {
+        config.try_decode(data)
+    }
+}
+
+impl FromBase64 for Box<[u8]> {
+    #[inline]
+    fn from_b64_with(data: impl AsRef<[u8]>, config: &B64Config) -> Result<Self, B64DecodeError> {
+        config.try_decode(data).map(Vec::into_boxed_slice)
+    }
+}
+
+impl<const N: usize> FromBase64 for [u8; N] {
+    fn from_b64_with(data: impl AsRef<[u8]>, config: &B64Config) -> Result<Self, B64DecodeError> {
+        let data = data.as_ref();
+        let mut out = [0u8; N];
+        let n = config
+            .decode_to_slice(data, &mut out)
+            .map_err(|e| match e.kind {
+                B64DecodeErrorKind::OutputTooSmall => {
+                    B64DecodeError::new(B64DecodeErrorKind::LengthMismatch, e.index)
+                }
+                _ => e,
+            })?;
+        if n != N {
+            return Err(B64DecodeError::new(
+                B64DecodeErrorKind::LengthMismatch,
+                data.len(),
+            ));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl FromBase64 for bytes::Bytes {
+    #[inline]
+    fn from_b64_with(data: impl AsRef<[u8]>, config: &B64Config) -> Result<Self, B64DecodeError> {
+        config.try_decode(data).map(bytes::Bytes::from)
     }
 }
 
@@ -871,6 +2245,791 @@ mod test {
         assert_eq!(r.to_string().as_str(), s);
     }
 
+    #[test]
+    fn test_from_b64_unpadded() {
+        let v = b64_to_bytes("TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLg");
+        assert_eq!(v, Some("Many hands make light work..".as_bytes().to_vec()));
+
+        let v = "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLi4".iter_b64_to_byte();
+        assert_eq!(
+            v.collect::<Vec<u8>>(),
+            "Many hands make light work...".as_bytes().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_url_1() {
+        let s = [0xFBu8, 0xFF, 0xBF, 0x01];
+        assert_eq!(bytes_to_b64(&s).as_str(), "+/+/AQ==");
+        assert_eq!(bytes_to_b64_url(&s).as_str(), "-_-_AQ");
+        assert_eq!(s.to_vec().to_b64_url_string().as_str(), "-_-_AQ");
+    }
+
+    #[test]
+    fn test_url_2() {
+        let s = "Many hands make light work...8675";
+        assert_eq!(
+            s.to_b64_url_string().as_str(),
+            "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLi44Njc1"
+        );
+        assert_eq!(
+            String::from(s).to_b64_url_string().as_str(),
+            "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLi44Njc1"
+        );
+    }
+
+    #[test]
+    fn test_url_iter_1() {
+        let s = [0xFBu8, 0xFF, 0xBF, 0x01];
+        let s2 = s.as_slice().iter_to_b64_url().collect::<String>();
+        assert_eq!(s2.as_str(), "-_-_AQ");
+
+        let s2 = "Many hands make light work.."
+            .iter_to_b64_url()
+            .collect::<String>();
+        assert_eq!(s2.as_str(), "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLg");
+    }
+
+    #[test]
+    fn test_from_b64_url_1() {
+        assert_eq!(
+            b64_url_to_bytes("-_-_AQ"),
+            Some(vec![0xFBu8, 0xFF, 0xBF, 0x01])
+        );
+        assert_eq!(
+            b64_url_to_bytes("-_-_AQ=="),
+            Some(vec![0xFBu8, 0xFF, 0xBF, 0x01])
+        );
+        // standard alphabet chars are not part of the url safe alphabet
+        assert_eq!(b64_url_to_bytes("+/+/AQ=="), None);
+        assert_eq!(b64_to_bytes("-_-_AQ=="), None);
+    }
+
+    #[test]
+    fn test_from_b64_url_iter_1() {
+        let v = "-_-_AQ".iter_b64_url_to_byte().collect::<Vec<u8>>();
+        assert_eq!(v, vec![0xFBu8, 0xFF, 0xBF, 0x01]);
+
+        let v = "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLg"
+            .iter_b64_url_to_byte()
+            .collect::<Vec<u8>>();
+        assert_eq!(v, "Many hands make light work..".as_bytes().to_vec());
+    }
+
+    #[test]
+    fn test_alphabet_1() {
+        assert_eq!(B64Alphabet::STANDARD.symbol(62), '+');
+        assert_eq!(B64Alphabet::URL_SAFE.symbol(63), '_');
+        assert!(B64Alphabet::STANDARD.char_to_ord(&'/').is_ok());
+        assert!(B64Alphabet::STANDARD.char_to_ord(&'=').is_padding());
+        assert!(B64Alphabet::STANDARD.char_to_ord(&'\n').is_whitespace());
+        assert!(B64Alphabet::URL_SAFE.char_to_ord(&'/').is_invalid());
+        assert!(B64Alphabet::STANDARD.char_to_ord(&'é').is_invalid());
+
+        assert!(B64Alphabet::new(&"A".repeat(64)).is_none());
+        assert!(B64Alphabet::new(&"A".repeat(65)).is_none());
+        let mut table = B64_TABLE;
+        table[0] = '=';
+        assert!(B64Alphabet::from_table(&table).is_none());
+        table[0] = ' ';
+        assert!(B64Alphabet::from_table(&table).is_none());
+    }
+
+    #[test]
+    fn test_config_custom_alphabet() {
+        // bcrypt style alphabet
+        let alpha =
+            B64Alphabet::new("./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789")
+                .unwrap();
+        let cfg = B64Config::new(alpha).with_padding(false);
+        let data = "Many hands make light work..".as_bytes();
+        let s = cfg.encode(data);
+        assert_eq!(s.as_str(), "RUDscQ/mWU3iaw/rWUrjGEvnX0fyGFbtakqsJe");
+        assert_eq!(cfg.iter_encode(data).collect::<String>(), s);
+        assert_eq!(cfg.decode(s.as_str()), Some(data.to_vec()));
+        assert_eq!(cfg.iter_decode(s.as_str()).collect::<Vec<u8>>(), data);
+    }
+
+    #[test]
+    fn test_config_padding() {
+        let data = "Many hands make light work..".as_bytes();
+        let padded = "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLg==";
+        let unpadded = "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsuLg";
+
+        assert_eq!(B64Config::STANDARD.encode(data).as_str(), padded);
+        let cfg = B64Config::STANDARD.with_padding(false);
+        assert_eq!(cfg.encode(data).as_str(), unpadded);
+        assert_eq!(cfg.iter_encode(data).collect::<String>().as_str(), unpadded);
+
+        let cfg = B64Config::STANDARD.with_strict(true);
+        assert_eq!(cfg.decode(padded), Some(data.to_vec()));
+        assert_eq!(cfg.decode(unpadded), Some(data.to_vec()));
+
+        let cfg = cfg.with_decode_padding(B64PadMode::Require);
+        assert_eq!(cfg.decode(padded), Some(data.to_vec()));
+        assert_eq!(cfg.decode(unpadded), None);
+
+        let cfg = cfg.with_decode_padding(B64PadMode::Forbid);
+        assert_eq!(cfg.decode(padded), None);
+        assert_eq!(cfg.decode(unpadded), Some(data.to_vec()));
+
+        // too much padding
+        let cfg = cfg.with_decode_padding(B64PadMode::Indifferent);
+        assert_eq!(cfg.decode("TWE=="), None);
+        assert_eq!(cfg.decode("TW=E"), None);
+        assert_eq!(cfg.decode("T==="), None);
+    }
+
+    #[test]
+    fn test_config_whitespace() {
+        let data = "Many hands make light work...".as_bytes();
+        let enc = "TWFueSBoYW5kcyBtYWtl\r\nIGxpZ2h0IHdvcmsuLi4=\n";
+        let cfg = B64Config::STANDARD.with_strict(true);
+        assert_eq!(cfg.decode(enc), Some(data.to_vec()));
+
+        let cfg = cfg.with_whitespace(B64Whitespace::Reject);
+        assert_eq!(cfg.decode(enc), None);
+
+        // lenient config stops at the whitespace, returning what it has
+        let cfg = cfg.with_strict(false);
+        assert_eq!(cfg.decode(enc), Some("Many hands make".as_bytes().to_vec()));
+    }
+
+    #[test]
+    fn test_config_strict() {
+        let cfg = B64Config::STANDARD.with_strict(true);
+        assert_eq!(cfg.decode(""), Some(vec![]));
+        assert_eq!(B64Config::STANDARD.decode(""), None);
+
+        // invalid symbol, trailing data, truncated quantum, non-zero trailing bits
+        for s in ["TWFu&", "TWE=TWE=", "TWFuT", "TWF="] {
+            assert_eq!(cfg.decode(s), None, "{}", s);
+            assert!(B64Config::STANDARD.decode(s).is_some(), "{}", s);
+        }
+        assert_eq!(B64Config::STANDARD.decode("TWE=TWE="), Some(b"Ma".to_vec()));
+    }
+
+    #[test]
+    fn test_config_iter_strict() {
+        let cfg = B64Config::STANDARD.with_strict(true);
+        let v = "TWFuTW&=".iter_b64_to_byte_with(&cfg).collect::<Vec<u8>>();
+        assert_eq!(v.as_slice(), b"Man");
+        let v = "TWFuTW&=".iter_b64_to_byte().collect::<Vec<u8>>();
+        assert_eq!(v.as_slice(), b"ManM");
+    }
+
+    #[test]
+    fn test_config_traits() {
+        let cfg = B64Config::URL_SAFE;
+        let data = vec![0xFBu8, 0xFF, 0xBF, 0x01];
+        assert_eq!(data.to_b64_string_with(&cfg).as_str(), "-_-_AQ==");
+        assert_eq!(
+            data.as_slice().iter_to_b64_with(&cfg).collect::<String>(),
+            "-_-_AQ=="
+        );
+        assert_eq!(
+            "-_-_AQ==".iter_b64_to_byte_with(&cfg).collect::<Vec<u8>>(),
+            data
+        );
+    }
+
+    #[test]
+    fn test_strict_errors() {
+        use B64DecodeErrorKind::*;
+        let cases = [
+            ("TWFu&", InvalidSymbol, 4),
+            ("TWFué", InvalidSymbol, 4),
+            ("TWé&", InvalidSymbol, 2),
+            ("TW=E", TrailingData, 3),
+            ("TWE=TWE=", TrailingData, 4),
+            ("TWE==", InvalidPadding, 4),
+            ("T===", InvalidPadding, 1),
+            ("TW=", InvalidPadding, 3),
+            ("TWFuT", TruncatedQuantum, 4),
+            ("TWF=", NonCanonical, 2),
+            ("TWFuTR==", NonCanonical, 5),
+        ];
+        for (s, kind, index) in cases {
+            assert_eq!(
+                b64_to_bytes_strict(s),
+                Err(B64DecodeError::new(kind, index)),
+                "{}",
+                s
+            );
+        }
+
+        assert_eq!(b64_to_bytes_strict(""), Ok(vec![]));
+        assert_eq!(b64_to_bytes_strict("TWE=\r\n"), Ok(b"Ma".to_vec()));
+        assert_eq!(b64_to_bytes_strict("TW E=\r\n"), Ok(b"Ma".to_vec()));
+    }
+
+    #[test]
+    fn test_strict_config_errors() {
+        let cfg = B64Config::STANDARD.with_decode_padding(B64PadMode::Require);
+        assert_eq!(
+            cfg.try_decode("TWE"),
+            Err(B64DecodeError::new(B64DecodeErrorKind::InvalidPadding, 3))
+        );
+        let cfg = B64Config::STANDARD.with_decode_padding(B64PadMode::Forbid);
+        assert_eq!(
+            cfg.try_decode("TWE="),
+            Err(B64DecodeError::new(B64DecodeErrorKind::InvalidPadding, 3))
+        );
+        let cfg = B64Config::STANDARD.with_whitespace(B64Whitespace::Reject);
+        assert_eq!(
+            cfg.try_decode("TWFu\nTWFu"),
+            Err(B64DecodeError::new(B64DecodeErrorKind::InvalidSymbol, 4))
+        );
+        assert_eq!(
+            b64_url_to_bytes_strict("+/+/"),
+            Err(B64DecodeError::new(B64DecodeErrorKind::InvalidSymbol, 0))
+        );
+    }
+
+    #[test]
+    fn test_strict_error_display() {
+        let e = B64DecodeError::new(B64DecodeErrorKind::TrailingData, 12);
+        assert_eq!(
+            e.to_string().as_str(),
+            "Base64 data after padding at byte 12"
+        );
+    }
+
+    #[test]
+    fn test_iter_error() {
+        let mut it = "TWFueSBo&YW5k".iter_b64_to_byte();
+        let v = it.by_ref().collect::<Vec<u8>>();
+        assert_eq!(v.as_slice(), b"Many h");
+        assert_eq!(
+            it.error(),
+            Some(B64DecodeError::new(B64DecodeErrorKind::InvalidSymbol, 8))
+        );
+
+        let cfg = B64Config::STANDARD.with_strict(true);
+        let mut it = "TWFuTWF=".iter_b64_to_byte_with(&cfg);
+        let v = it.by_ref().collect::<Vec<u8>>();
+        assert_eq!(v.as_slice(), b"Man");
+        assert_eq!(
+            it.error(),
+            Some(B64DecodeError::new(B64DecodeErrorKind::NonCanonical, 6))
+        );
+
+        let mut it = "TWFu".iter_b64_to_byte();
+        assert_eq!(it.by_ref().count(), 3);
+        assert_eq!(it.error(), None);
+    }
+
+    #[test]
+    fn test_encoded_len() {
+        for n in 0..64usize {
+            let v = vec![0xA5u8; n];
+            for cfg in [B64Config::STANDARD, B64Config::URL_SAFE_NO_PAD] {
+                assert_eq!(cfg.encoded_len(n), cfg.encode(&v).len());
+                let est = cfg.decoded_len_estimate(cfg.encoded_len(n));
+                assert!(est >= n && est < n + 3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_to_slice() {
+        let mut buf = [0u8; 64];
+        let n = B64Config::STANDARD
+            .encode_to_slice(b"Many hands make light work.", &mut buf)
+            .unwrap();
+        assert_eq!(&buf[..n], b"TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsu");
+
+        let n = B64Config::URL_SAFE_NO_PAD
+            .encode_to_slice(&[0xFB, 0xFF, 0xBF, 0x01], &mut buf[..6])
+            .unwrap();
+        assert_eq!(&buf[..n], b"-_-_AQ");
+
+        assert_eq!(B64Config::STANDARD.encode_to_slice(b"", &mut []), Ok(0));
+        assert_eq!(
+            B64Config::STANDARD.encode_to_slice(b"Ma", &mut buf[..3]),
+            Err(B64EncodeError {
+                needed: 4,
+                available: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_into() {
+        let mut s = String::new();
+        B64Config::STANDARD.encode_into(b"Man", &mut s);
+        B64Config::STANDARD.encode_into(b"", &mut s);
+        B64Config::URL_SAFE_NO_PAD.encode_into(&[0xFB, 0xFF], &mut s);
+        assert_eq!(s.as_str(), "TWFu-_8");
+    }
+
+    #[test]
+    fn test_decode_to_slice() {
+        let mut buf = [0u8; 32];
+        let s = "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsu";
+        let n = B64Config::STANDARD.decode_to_slice(s, &mut buf).unwrap();
+        assert_eq!(&buf[..n], b"Many hands make light work.");
+
+        // exact room is enough, even though the estimate is larger
+        let n = B64Config::STANDARD
+            .decode_to_slice("TWE=", &mut buf[..2])
+            .unwrap();
+        assert_eq!(&buf[..n], b"Ma");
+
+        assert_eq!(
+            B64Config::STANDARD.decode_to_slice("TWFuTWE=", &mut buf[..4]),
+            Err(B64DecodeError::new(B64DecodeErrorKind::OutputTooSmall, 8))
+        );
+        assert_eq!(
+            B64Config::STANDARD.decode_to_slice("TWFuTWFu", &mut buf[..4]),
+            Err(B64DecodeError::new(B64DecodeErrorKind::OutputTooSmall, 7))
+        );
+        assert_eq!(
+            B64Config::STANDARD.decode_to_slice("TW&u", &mut buf),
+            Err(B64DecodeError::new(B64DecodeErrorKind::InvalidSymbol, 2))
+        );
+    }
+
+    #[test]
+    fn test_decode_into() {
+        let mut v = Vec::new();
+        assert_eq!(B64Config::STANDARD.decode_into("TWFu", &mut v), Ok(3));
+        assert_eq!(B64Config::URL_SAFE_NO_PAD.decode_into("-_8", &mut v), Ok(2));
+        assert_eq!(B64Config::STANDARD.decode_into("", &mut v), Ok(0));
+        assert_eq!(v.as_slice(), &[b'M', b'a', b'n', 0xFB, 0xFF]);
+
+        assert_eq!(
+            B64Config::STANDARD.decode_into("TWFuT", &mut v),
+            Err(B64DecodeError::new(B64DecodeErrorKind::TruncatedQuantum, 4))
+        );
+        assert_eq!(v.len(), 5);
+    }
+
+    // reader that hands out at most `step` bytes per read
+    struct StepReader<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl std::io::Read for StepReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.step.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_writer() {
+        use std::io::Write;
+        let data = (0..5000u32)
+            .map(|x| (x * 7 + x / 3) as u8)
+            .collect::<Vec<u8>>();
+        for step in [1usize, 2, 3, 5, 64, 1000, 5000] {
+            for cfg in [B64Config::STANDARD, B64Config::URL_SAFE_NO_PAD] {
+                let mut w = B64Writer::new_with(Vec::new(), &cfg);
+                for chunk in data.chunks(step) {
+                    w.write_all(chunk).unwrap();
+                }
+                let v = w.finish().unwrap();
+                assert_eq!(String::from_utf8(v).unwrap(), cfg.encode(&data));
+            }
+        }
+
+        let w = B64Writer::new(Vec::new());
+        assert!(w.finish().unwrap().is_empty());
+
+        // dropping writes the final quantum
+        let mut v = Vec::new();
+        {
+            let mut w = B64Writer::new(&mut v);
+            w.write_all(b"Ma").unwrap();
+            w.flush().unwrap();
+            assert!(w.get_ref().is_empty());
+        }
+        assert_eq!(v.as_slice(), b"TWE=");
+
+        let mut w = B64Writer::new_url(Vec::new());
+        w.write_all(&[0xFB, 0xFF, 0xBF, 0x01]).unwrap();
+        assert_eq!(w.finish().unwrap().as_slice(), b"-_-_AQ");
+
+        // a failed finish does not write the tail again on drop
+        let mut f = FailFlush::default();
+        let mut w = B64Writer::new(&mut f);
+        w.write_all(b"Ma").unwrap();
+        assert!(w.finish().is_err());
+        assert_eq!((f.writes, f.flushes), (1, 1));
+    }
+
+    // writer that counts its writes and flushes, failing every flush
+    #[derive(Default)]
+    struct FailFlush {
+        writes: usize,
+        flushes: usize,
+    }
+
+    impl std::io::Write for FailFlush {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.writes += 1;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.flushes += 1;
+            Err(std::io::Error::other("flush failed"))
+        }
+    }
+
+    #[test]
+    fn test_reader() {
+        use std::io::Read;
+        let data = (0..5000u32)
+            .map(|x| (x * 7 + x / 3) as u8)
+            .collect::<Vec<u8>>();
+        let mut enc = String::new();
+        for (i, c) in bytes_to_b64(&data).chars().enumerate() {
+            if i > 0 && i % 76 == 0 {
+                enc.push_str("\r\n");
+            }
+            enc.push(c);
+        }
+        for step in [1usize, 3, 4, 77, 1024, 10000] {
+            let mut r = B64Reader::new(StepReader {
+                data: enc.as_bytes(),
+                step,
+            });
+            let mut v = Vec::new();
+            r.read_to_end(&mut v).unwrap();
+            assert_eq!(v, data);
+            assert_eq!(r.error(), None);
+        }
+
+        let mut v = Vec::new();
+        B64Reader::new_url("-_-_AQ".as_bytes())
+            .read_to_end(&mut v)
+            .unwrap();
+        assert_eq!(v.as_slice(), &[0xFB, 0xFF, 0xBF, 0x01]);
+    }
+
+    #[test]
+    fn test_reader_error() {
+        use std::io::Read;
+        // lenient stops at the problem, keeping what it decoded
+        let mut r = B64Reader::new("TWFueSBo&YW5k".as_bytes());
+        let mut v = Vec::new();
+        r.read_to_end(&mut v).unwrap();
+        assert_eq!(v.as_slice(), b"Many h");
+        assert_eq!(
+            r.error(),
+            Some(B64DecodeError::new(B64DecodeErrorKind::InvalidSymbol, 8))
+        );
+
+        // strict fails the read, with the offset across reads
+        let src = format!("{}\u{e9}", "TWFu".repeat(300));
+        let cfg = B64Config::STANDARD.with_strict(true);
+        let mut r = B64Reader::new_with(
+            StepReader {
+                data: src.as_bytes(),
+                step: 7,
+            },
+            &cfg,
+        );
+        let mut v = Vec::new();
+        let e = r.read_to_end(&mut v).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(v.len(), 900);
+        assert_eq!(
+            r.error(),
+            Some(B64DecodeError::new(B64DecodeErrorKind::InvalidSymbol, 1200))
+        );
+
+        let mut r = B64Reader::new_with("TWFuTWF=".as_bytes(), &cfg);
+        let mut v = Vec::new();
+        assert!(r.read_to_end(&mut v).is_err());
+        assert_eq!(v.as_slice(), b"Man");
+        assert_eq!(
+            r.error(),
+            Some(B64DecodeError::new(B64DecodeErrorKind::NonCanonical, 6))
+        );
+    }
+
+    #[test]
+    fn test_line_wrap() {
+        let data = (0..1000u32).map(|x| (x * 13) as u8).collect::<Vec<u8>>();
+        let flat = bytes_to_b64(&data);
+        for cfg in [
+            B64Config::MIME,
+            B64Config::PEM,
+            B64Config::URL_SAFE_NO_PAD.with_line_wrap(5, B64LineEnding::Lf),
+        ] {
+            let (line_len, eol) = cfg.line_wrap().unwrap();
+            for n in [0usize, 1, 2, 3, 47, 48, 57, 58, 59, 60, 1000] {
+                let s = cfg.encode(&data[..n]);
+                assert_eq!(s.len(), cfg.encoded_len(n));
+                assert!(!s.ends_with('\n'));
+                for line in s.split(eol.as_str()).filter(|_| n > 0) {
+                    assert!(!line.is_empty() && line.len() <= line_len);
+                }
+                assert_eq!(
+                    s.replace(eol.as_str(), ""),
+                    cfg.with_line_wrap(0, eol).encode(&data[..n])
+                );
+                assert_eq!(cfg.iter_encode(&data[..n]).collect::<String>(), s);
+                assert_eq!(cfg.decode(&s).unwrap_or_default(), &data[..n]);
+            }
+        }
+        assert_eq!(B64Config::MIME.encode(&data).replace("\r\n", ""), flat);
+        assert_eq!(bytes_to_b64_mime(&data), B64Config::MIME.encode(&data));
+        assert_eq!(bytes_to_b64_pem(&data), B64Config::PEM.encode(&data));
+        assert_eq!(B64Config::STANDARD.line_wrap(), None);
+
+        // exactly one full line has no line ending
+        assert_eq!(bytes_to_b64_pem(&[0u8; 48]), "A".repeat(64));
+
+        let mut buf = [0u8; 10];
+        let cfg = B64Config::STANDARD.with_line_wrap(4, B64LineEnding::CrLf);
+        assert_eq!(cfg.encode_to_slice(b"Many", &mut buf), Ok(10));
+        assert_eq!(&buf, b"TWFu\r\neQ==");
+    }
+
+    #[test]
+    fn test_writer_line_wrap() {
+        use std::io::Write;
+        let data = (0..5000u32).map(|x| (x * 13) as u8).collect::<Vec<u8>>();
+        for step in [1usize, 2, 3, 57, 100, 5000] {
+            for cfg in [
+                B64Config::MIME,
+                B64Config::PEM,
+                B64Config::STANDARD.with_line_wrap(1, B64LineEnding::Lf),
+            ] {
+                let mut w = B64Writer::new_with(Vec::new(), &cfg);
+                for chunk in data.chunks(step) {
+                    w.write_all(chunk).unwrap();
+                }
+                let v = w.finish().unwrap();
+                assert_eq!(String::from_utf8(v).unwrap(), cfg.encode(&data));
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_bytes() {
+        let cases = [
+            "TWFueSBoYW5kcyBtYWtlIGxpZ2h0IHdvcmsu",
+            "TWFueSBo\r\nYW5kcw==",
+            "TWFueQ",
+            "TWFu&TWFu",
+            "TWFu\u{e9}TWFu",
+            "TWFuTWF=",
+            "TWE=TWE=",
+            "T",
+            "",
+        ];
+        for s in cases {
+            let v = s.as_bytes().to_vec();
+            assert_eq!(b64_to_bytes(&v), b64_to_bytes(s));
+            assert_eq!(b64_to_bytes_strict(&v), b64_to_bytes_strict(s));
+            assert_eq!(
+                v.iter_b64_to_byte().collect::<Vec<u8>>(),
+                s.iter_b64_to_byte().collect::<Vec<u8>>()
+            );
+            let sl = v.as_slice();
+            let mut a = sl.iter_b64_to_byte();
+            let mut b = s.iter_b64_to_byte();
+            assert_eq!(a.by_ref().count(), b.by_ref().count());
+            assert_eq!(a.error(), b.error());
+        }
+
+        assert_eq!(
+            b64_url_to_bytes(b"-_-_AQ"),
+            Some(vec![0xFBu8, 0xFF, 0xBF, 0x01])
+        );
+        assert_eq!(
+            b64_url_to_bytes_strict(b"-_-_AQ"),
+            Ok(vec![0xFBu8, 0xFF, 0xBF, 0x01])
+        );
+        assert_eq!(
+            b"-_-_AQ"
+                .as_slice()
+                .iter_b64_url_to_byte()
+                .collect::<Vec<u8>>(),
+            vec![0xFBu8, 0xFF, 0xBF, 0x01]
+        );
+        let cfg = B64Config::STANDARD.with_strict(true);
+        assert_eq!(
+            cfg.iter_decode(b"TWE=").collect::<Vec<u8>>().as_slice(),
+            b"Ma"
+        );
+        assert_eq!(
+            b"TWE="
+                .to_vec()
+                .iter_b64_to_byte_with(&cfg)
+                .collect::<Vec<u8>>()
+                .as_slice(),
+            b"Ma"
+        );
+    }
+
+    #[test]
+    fn test_byte_to_ord() {
+        for b in 0..=255u8 {
+            assert_eq!(
+                B64Alphabet::STANDARD.byte_to_ord(b),
+                B64Alphabet::STANDARD.char_to_ord(&(b as char))
+            );
+            assert_eq!(
+                B64Alphabet::URL_SAFE.byte_to_ord(b),
+                *b64_url_char_to_ord(&(b as char))
+            );
+        }
+    }
+
+    #[test]
+    fn test_b64_long() {
+        let data = (0..1000u32)
+            .map(|x| (x * 7 + x / 5) as u8)
+            .collect::<Vec<u8>>();
+        let bcrypt =
+            B64Alphabet::new("./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789")
+                .unwrap();
+        for cfg in [
+            B64Config::STANDARD,
+            B64Config::URL_SAFE_NO_PAD,
+            B64Config::new(bcrypt),
+        ] {
+            for n in [998usize, 999, 1000] {
+                let s = cfg.encode(&data[..n]);
+                assert_eq!(s, cfg.iter_encode(&data[..n]).collect::<String>());
+                assert_eq!(cfg.decode(&s).unwrap(), &data[..n]);
+                assert_eq!(
+                    cfg.iter_decode(&s).collect::<Vec<u8>>().as_slice(),
+                    &data[..n]
+                );
+            }
+        }
+
+        // problems past the fast path are found at the same offset
+        let s = bytes_to_b64(&data);
+        for pos in [0usize, 15, 16, 700, 1332] {
+            let mut bad = s.clone().into_bytes();
+            bad[pos] = b'&';
+            let e = b64_to_bytes_strict(&bad).unwrap_err();
+            assert_eq!(
+                e,
+                B64DecodeError::new(B64DecodeErrorKind::InvalidSymbol, pos)
+            );
+            let v = b64_to_bytes(&bad).unwrap_or_default();
+            assert_eq!(v.as_slice(), &data[..pos * 3 / 4]);
+        }
+    }
+
+    #[test]
+    fn test_more_types() {
+        use std::borrow::Cow;
+        let arr = *b"Many hands";
+        let boxed: Box<[u8]> = Box::from(&arr[..]);
+        let cow: Cow<[u8]> = Cow::Borrowed(&arr);
+        let s = "TWFueSBoYW5kcw==";
+        assert_eq!(arr.to_b64_string(), s);
+        assert_eq!(boxed.to_b64_string(), s);
+        assert_eq!(cow.to_b64_string(), s);
+        assert_eq!(arr.iter_to_b64().collect::<String>(), s);
+        assert_eq!(
+            boxed.iter_to_b64_url().collect::<String>(),
+            "TWFueSBoYW5kcw"
+        );
+        assert_eq!(
+            cow.iter_to_b64_with(&B64Config::URL_SAFE)
+                .collect::<String>(),
+            s
+        );
+
+        let b = s.as_bytes().to_vec().into_boxed_slice();
+        assert_eq!(b.iter_b64_to_byte().collect::<Vec<u8>>(), arr);
+    }
+
+    #[test]
+    fn test_from_b64() {
+        let s = "TWFueSBoYW5kcw==";
+        assert_eq!(<[u8; 10]>::from_b64(s), Ok(*b"Many hands"));
+        assert_eq!(Vec::<u8>::from_b64(s), Ok(b"Many hands".to_vec()));
+        assert_eq!(
+            <Box<[u8]>>::from_b64(s.as_bytes()).unwrap().as_ref(),
+            b"Many hands"
+        );
+        assert_eq!(<[u8; 0]>::from_b64(""), Ok([]));
+        assert_eq!(
+            <[u8; 4]>::from_b64_url("-_-_AQ"),
+            Ok([0xFB, 0xFF, 0xBF, 0x01])
+        );
+
+        // too short is reported at the end of the input, too long where the extra bytes are completed
+        assert_eq!(
+            <[u8; 11]>::from_b64(s),
+            Err(B64DecodeError::new(B64DecodeErrorKind::LengthMismatch, 16))
+        );
+        assert_eq!(
+            <[u8; 9]>::from_b64(s),
+            Err(B64DecodeError::new(B64DecodeErrorKind::LengthMismatch, 16))
+        );
+        assert_eq!(
+            <[u8; 3]>::from_b64("TWFueSBo"),
+            Err(B64DecodeError::new(B64DecodeErrorKind::LengthMismatch, 7))
+        );
+        // decode problems keep their own kind
+        assert_eq!(
+            <[u8; 10]>::from_b64("TWFueSBoYW5kc&=="),
+            Err(B64DecodeError::new(B64DecodeErrorKind::InvalidSymbol, 13))
+        );
+        let cfg = B64Config::STANDARD.with_strict(true);
+        assert_eq!(<[u8; 2]>::from_b64_with("TWE=", &cfg), Ok(*b"Ma"));
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_from_b64_bytes() {
+        let b = bytes::Bytes::from_b64("TWFueSBoYW5kcw==").unwrap();
+        assert_eq!(&b[..], b"Many hands");
+        assert_eq!(b.to_b64_string(), "TWFueSBoYW5kcw==");
+    }
+
+    #[test]
+    fn test_ct() {
+        let data = (0..64u32).map(|x| (x * 181 + 7) as u8).collect::<Vec<u8>>();
+        for n in 0..data.len() {
+            let s = bytes_to_b64(&data[..n]);
+            assert_eq!(b64_to_bytes_ct(&s).unwrap(), &data[..n]);
+            assert_eq!(
+                b64_to_bytes_ct(s.trim_end_matches('=')).unwrap(),
+                &data[..n]
+            );
+            let s = bytes_to_b64_url(&data[..n]);
+            assert_eq!(b64_url_to_bytes_ct(&s).unwrap(), &data[..n]);
+        }
+
+        // agrees with the strict decoder on every byte in every position of a quantum
+        for b in 0..=255u8 {
+            for i in 0..4 {
+                let mut q = *b"QUJD";
+                q[i] = b;
+                let strict = b64_to_bytes_strict(q).ok();
+                let whitespace = b.is_ascii_whitespace() || b == b'=';
+                if !whitespace {
+                    assert_eq!(b64_to_bytes_ct(q), strict);
+                }
+                let url = b64_url_to_bytes_strict(q).ok();
+                if !whitespace {
+                    assert_eq!(b64_url_to_bytes_ct(q), url);
+                }
+            }
+        }
+        assert_eq!(b64_to_bytes_ct("Q"), None);
+        assert_eq!(b64_to_bytes_ct("QUJD="), None);
+        assert_eq!(b64_to_bytes_ct("QUI=="), None);
+        assert_eq!(b64_to_bytes_ct(""), Some(vec![]));
+    }
+
     #[test]
     fn test_b64_docs() {
         let mut s2 = String::new();
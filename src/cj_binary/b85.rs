@@ -0,0 +1,1180 @@
+//! structs, methods and traits for working with Ascii85 and Z85 encoding/decoding
+//!
+//! # Quick Start
+//!
+//!```
+//! use cj_common::prelude::*;
+//!
+//! let s = "Man is".to_a85_string();
+//! assert_eq!(s.as_str(), "9jqo^Bla");
+//! assert_eq!(a85_to_bytes("9jqo^Bla"), Some(b"Man is".to_vec()));
+//!
+//! // Adobe's variant wraps the data in <~ ~>, and all zero groups become 'z'
+//! let s = bytes_to_a85_adobe(&[0, 0, 0, 0, 1]);
+//! assert_eq!(s.as_str(), "<~z!<~>");
+//! assert_eq!(a85_to_bytes(&s), Some(vec![0, 0, 0, 0, 1]));
+//!
+//! // ZeroMQ's Z85
+//! let s = bytes_to_z85(&[0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B]);
+//! assert_eq!(s.as_str(), "HelloWorld");
+//!
+//! // iter example
+//! let s = "Man is".iter_to_a85().collect::<String>();
+//! let v = s.iter_a85_to_byte().collect::<Vec<u8>>();
+//! assert_eq!(v.as_slice(), b"Man is");
+//! ```
+
+use std::slice::Iter;
+
+// Z85 table (ZeroMQ RFC 32).
+const B85_Z85_TABLE: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+// marks a byte that is not part of an alphabet in B85Alphabet's decode table
+const B85_NO_ORD: u8 = 0xFF;
+
+///
+/// an 85 symbol alphabet.
+///
+/// [`B85Alphabet::ASCII85`] (`!` to `u`) and [`B85Alphabet::Z85`] are provided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B85Alphabet {
+    encode: [u8; 85],
+    decode: [u8; 256],
+}
+
+impl B85Alphabet {
+    /// Ascii85 alphabet, the 85 chars from `!` to `u`
+    pub const ASCII85: B85Alphabet = {
+        let mut table = [0u8; 85];
+        let mut i = 0;
+        while i < 85 {
+            table[i] = b'!' + i as u8;
+            i += 1;
+        }
+        match B85Alphabet::from_bytes(&table) {
+            Some(a) => a,
+            None => panic!("invalid b85 table"),
+        }
+    };
+    /// ZeroMQ Z85 alphabet, safe to use in source code strings and XML
+    pub const Z85: B85Alphabet = match B85Alphabet::from_bytes(B85_Z85_TABLE) {
+        Some(a) => a,
+        None => panic!("invalid b85 table"),
+    };
+
+    // builds an alphabet from 85 ASCII bytes, where the index of each symbol is its value
+    const fn from_bytes(table: &[u8; 85]) -> Option<Self> {
+        let mut encode = [0u8; 85];
+        let mut decode = [B85_NO_ORD; 256];
+        let mut i = 0;
+        while i < 85 {
+            let b = table[i];
+            if !b.is_ascii_graphic() || decode[b as usize] != B85_NO_ORD {
+                return None;
+            }
+            encode[i] = b;
+            decode[b as usize] = i as u8;
+            i += 1;
+        }
+        Some(Self { encode, decode })
+    }
+
+    /// the symbol for a value below 85
+    #[inline(always)]
+    pub const fn symbol(&self, ord: u8) -> char {
+        self.encode[ord as usize % 85] as char
+    }
+
+    /// the value of a symbol, or None if it is not part of the alphabet
+    #[inline]
+    pub const fn byte_to_ord(&self, b: u8) -> Option<u8> {
+        match self.decode[b as usize] {
+            B85_NO_ORD => None,
+            o => Some(o),
+        }
+    }
+}
+
+///
+/// Ascii85 / Z85 engine bundling the alphabet, `z` shorthand, `<~ ~>` delimiters and strictness
+/// options used for encoding and decoding.
+///
+/// every 4 bytes become 5 chars, and a final group of n bytes becomes n + 1 chars.
+///
+/// when decoding, whitespace is skipped, and `<~ ~>` delimiters are accepted even when they are not
+/// written. a lenient config (the default) stops at the first problem in the input and returns what
+/// was decoded up to that point. a strict config returns None instead.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// let cfg = B85Config::ADOBE;
+/// let s = cfg.encode(b"Man is");
+/// assert_eq!(s.as_str(), "<~9jqo^Bla~>");
+/// assert_eq!(cfg.decode(&s), Some(b"Man is".to_vec()));
+///
+/// // delimiters are required when the config writes them
+/// assert_eq!(cfg.with_strict(true).decode("9jqo^Bla"), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B85Config {
+    alphabet: B85Alphabet,
+    zero_group: bool,
+    delimiters: bool,
+    strict: bool,
+}
+
+impl B85Config {
+    /// Ascii85 alphabet with `z` shorthand and no delimiters, lenient decoding
+    pub const ASCII85: B85Config = B85Config::new(B85Alphabet::ASCII85).with_zero_group(true);
+    /// Adobe's Ascii85, with `z` shorthand and `<~ ~>` delimiters, lenient decoding
+    pub const ADOBE: B85Config = B85Config::ASCII85.with_delimiters(true);
+    /// ZeroMQ Z85, lenient decoding
+    pub const Z85: B85Config = B85Config::new(B85Alphabet::Z85);
+
+    /// new config for an alphabet, with no `z` shorthand or delimiters, and lenient decoding
+    pub const fn new(alphabet: B85Alphabet) -> Self {
+        Self {
+            alphabet,
+            zero_group: false,
+            delimiters: false,
+            strict: false,
+        }
+    }
+
+    /// sets the alphabet
+    pub const fn with_alphabet(mut self, alphabet: B85Alphabet) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+
+    /// sets whether a group of 4 zero bytes is written as `z`, and `z` is read as one. only for
+    /// alphabets that do not use `z` as a symbol.
+    pub const fn with_zero_group(mut self, zero_group: bool) -> Self {
+        self.zero_group = zero_group;
+        self
+    }
+
+    /// sets whether encoded output is wrapped in `<~ ~>`, and whether decoding requires them
+    pub const fn with_delimiters(mut self, delimiters: bool) -> Self {
+        self.delimiters = delimiters;
+        self
+    }
+
+    /// sets whether decoding fails outright on bad input (strict), or returns what was decoded
+    /// before the bad input (lenient)
+    pub const fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    #[inline]
+    pub const fn alphabet(&self) -> &B85Alphabet {
+        &self.alphabet
+    }
+
+    #[inline]
+    pub const fn zero_group(&self) -> bool {
+        self.zero_group
+    }
+
+    #[inline]
+    pub const fn delimiters(&self) -> bool {
+        self.delimiters
+    }
+
+    #[inline]
+    pub const fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// upper bound of the length of the encoding of `byte_len` bytes with this config. `z`
+    /// shorthand makes the actual length shorter.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(B85Config::Z85.encoded_len_max(8), 10);
+    /// assert_eq!(B85Config::ADOBE.encoded_len_max(6), 12);
+    /// ```
+    pub const fn encoded_len_max(&self, byte_len: usize) -> usize {
+        let rem = byte_len % 4;
+        let mut len = byte_len / 4 * 5;
+        if rem > 0 {
+            len += rem + 1;
+        }
+        if self.delimiters {
+            len += 4;
+        }
+        len
+    }
+
+    /// upper bound of the number of bytes decoded from `char_len` chars. `z` shorthand can make
+    /// the actual length longer, so this is only a starting capacity.
+    pub const fn decoded_len_estimate(&self, char_len: usize) -> usize {
+        char_len.div_ceil(5) * 4
+    }
+
+    /// converts a slice of bytes into an Ascii85 / Z85 encoded String
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let mut s = String::with_capacity(self.encoded_len_max(bytes.len()));
+        self.encode_each(bytes, |c| s.push(c as char));
+        s
+    }
+
+    // feeds each ASCII char of the encoding, delimiters included, to put
+    #[inline]
+    fn encode_each(&self, bytes: &[u8], mut put: impl FnMut(u8)) {
+        if self.delimiters {
+            put(b'<');
+            put(b'~');
+        }
+        for chunk in bytes.chunks(4) {
+            B85Chars::from_chunk(self, chunk)
+                .as_slice()
+                .iter()
+                .for_each(|c| put(*c));
+        }
+        if self.delimiters {
+            put(b'~');
+            put(b'>');
+        }
+    }
+
+    /// converts Ascii85 / Z85 encoded data, either a str or ASCII bytes, into a vec of bytes.
+    ///
+    /// see [`B85Config::try_decode`] to find out why decoding failed.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(B85Config::ASCII85.decode("9jqo^"), Some(b"Man ".to_vec()));
+    /// assert_eq!(B85Config::ASCII85.decode("9jqo^v"), Some(b"Man ".to_vec()));
+    /// ```
+    pub fn decode(&self, data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+        let data = data.as_ref();
+        let mut v = Vec::<u8>::with_capacity(self.decoded_len_estimate(data.len()));
+        let mut state = B85DecodeState::new();
+        let r = self.decode_each(data, &mut state, |g| v.extend_from_slice(g.as_slice()));
+        match r {
+            Ok(()) if self.strict => Some(v),
+            Err(_) if self.strict => None,
+            r => {
+                if r.is_err() {
+                    // we were able to decode something, even if there was an error. keep what we have.
+                    v.extend_from_slice(state.partial().as_slice());
+                }
+                if v.is_empty() { None } else { Some(v) }
+            }
+        }
+    }
+
+    /// converts Ascii85 / Z85 encoded data, either a str or ASCII bytes, into a vec of bytes,
+    /// failing on the first problem in the input regardless of [`B85Config::is_strict`].
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let e = B85Config::ASCII85.try_decode("9jqo^v").unwrap_err();
+    /// assert_eq!(e, B85DecodeError::new(B85DecodeErrorKind::InvalidSymbol, 5));
+    ///
+    /// let e = B85Config::ASCII85.try_decode("s8W-\"").unwrap_err();
+    /// assert_eq!(e, B85DecodeError::new(B85DecodeErrorKind::Overflow, 0));
+    /// ```
+    pub fn try_decode(&self, data: impl AsRef<[u8]>) -> Result<Vec<u8>, B85DecodeError> {
+        let data = data.as_ref();
+        let mut v = Vec::<u8>::with_capacity(self.decoded_len_estimate(data.len()));
+        let mut state = B85DecodeState::new();
+        self.decode_each(data, &mut state, |g| v.extend_from_slice(g.as_slice()))?;
+        Ok(v)
+    }
+
+    // decodes data, feeding each group to put. stops at the first problem, in which case state holds
+    // the partial group the problem was found in.
+    #[inline]
+    fn decode_each(
+        &self,
+        data: &[u8],
+        state: &mut B85DecodeState,
+        mut put: impl FnMut(B85Group),
+    ) -> Result<(), B85DecodeError> {
+        for (i, b) in data.iter().enumerate() {
+            if let Some(g) = state.push(self, *b, i)? {
+                put(g);
+            }
+        }
+        if let Some(g) = state.finish(self, data.len())? {
+            put(g);
+        }
+        Ok(())
+    }
+
+    /// Iterator over a slice of bytes that produces Ascii85 / Z85 encoded chars
+    pub fn iter_encode<'a>(&'a self, bytes: &'a [u8]) -> ToBase85Iter<'a> {
+        ToBase85Iter::new_with(bytes.iter(), self)
+    }
+
+    /// Iterator over Ascii85 / Z85 encoded data, either a str or ASCII bytes, that produces decoded bytes
+    pub fn iter_decode<'a, T: AsRef<[u8]> + ?Sized>(&'a self, data: &'a T) -> FromBase85Iter<'a> {
+        FromBase85Iter::new_with(data.as_ref().iter(), self)
+    }
+}
+
+impl Default for B85Config {
+    fn default() -> Self {
+        Self::ASCII85
+    }
+}
+
+/// the kind of problem found while decoding Ascii85 / Z85
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum B85DecodeErrorKind {
+    /// a char that is not part of the alphabet, or a `z` inside a group
+    InvalidSymbol,
+    /// a group of 5 chars with a value over `u32::MAX`
+    Overflow,
+    /// the input ends with a single char, which can not make a byte
+    TruncatedGroup,
+    /// a missing, partial or required but absent `<~` or `~>`
+    InvalidDelimiter,
+    /// something other than whitespace following `~>`
+    TrailingData,
+}
+
+/// error returned by strict Ascii85 / Z85 decoding, with the kind of problem and the byte offset
+/// in the input where it was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct B85DecodeError {
+    pub kind: B85DecodeErrorKind,
+    pub index: usize,
+}
+
+impl B85DecodeError {
+    #[inline]
+    pub const fn new(kind: B85DecodeErrorKind, index: usize) -> Self {
+        Self { kind, index }
+    }
+}
+
+impl std::fmt::Display for B85DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self.kind {
+            B85DecodeErrorKind::InvalidSymbol => "invalid Base85 symbol",
+            B85DecodeErrorKind::Overflow => "Base85 group overflows 32 bits",
+            B85DecodeErrorKind::TruncatedGroup => "truncated Base85 group",
+            B85DecodeErrorKind::InvalidDelimiter => "invalid Base85 delimiter",
+            B85DecodeErrorKind::TrailingData => "Base85 data after delimiter",
+        };
+        write!(f, "{} at byte {}", msg, self.index)
+    }
+}
+
+impl std::error::Error for B85DecodeError {}
+
+#[inline(always)]
+const fn b85_is_whitespace(b: u8) -> bool {
+    matches!(b, b' ' | b'\r' | b'\n' | b'\t' | b'\x0C' | b'\0')
+}
+
+// up to 5 encoded chars of a group
+#[derive(Debug, Clone, Copy, Default)]
+struct B85Chars {
+    chars: [u8; 5],
+    len: usize,
+}
+
+impl B85Chars {
+    // encodes up to 4 bytes
+    #[inline]
+    fn from_chunk(config: &B85Config, chunk: &[u8]) -> Self {
+        if chunk == [0, 0, 0, 0] && config.zero_group {
+            return Self {
+                chars: [b'z', 0, 0, 0, 0],
+                len: 1,
+            };
+        }
+        let mut buf = [0u8; 4];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let mut n = u32::from_be_bytes(buf);
+        let mut chars = [0u8; 5];
+        for c in chars.iter_mut().rev() {
+            *c = config.alphabet.encode[(n % 85) as usize];
+            n /= 85;
+        }
+        Self {
+            chars,
+            len: chunk.len() + 1,
+        }
+    }
+
+    #[inline(always)]
+    fn as_slice(&self) -> &[u8] {
+        &self.chars[..self.len]
+    }
+}
+
+// up to 4 decoded bytes of a group
+#[derive(Debug, Clone, Copy, Default)]
+struct B85Group {
+    bytes: [u8; 4],
+    len: usize,
+}
+
+impl B85Group {
+    #[inline(always)]
+    fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+// where the decoder is relative to the `<~ ~>` delimiters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum B85Phase {
+    // nothing but whitespace yet
+    Start,
+    // a '<' that may start `<~`
+    Lt,
+    Body,
+    // a '~' that must be followed by '>'
+    Tilde,
+    // after `~>`
+    End,
+}
+
+// decoding state shared by B85Config::decode and FromBase85Iter. symbols are collected into
+// groups of 5, and a group's bytes are only released once it is complete.
+#[derive(Debug, Clone, Copy)]
+struct B85DecodeState {
+    acc: u64,
+    ct: usize,
+    phase: B85Phase,
+    // whether the input started with `<~`, making `~>` required
+    opened: bool,
+    // offset of the first symbol of the current group, or of the '<' while in Lt
+    first_index: usize,
+}
+
+impl B85DecodeState {
+    const fn new() -> Self {
+        Self {
+            acc: 0,
+            ct: 0,
+            phase: B85Phase::Start,
+            opened: false,
+            first_index: 0,
+        }
+    }
+
+    // b is the byte at offset index in the input
+    fn push(
+        &mut self,
+        config: &B85Config,
+        b: u8,
+        index: usize,
+    ) -> Result<Option<B85Group>, B85DecodeError> {
+        let err = |kind| Err(B85DecodeError::new(kind, index));
+        match self.phase {
+            B85Phase::Start => {
+                if b85_is_whitespace(b) {
+                    return Ok(None);
+                }
+                if b == b'<' {
+                    self.phase = B85Phase::Lt;
+                    self.first_index = index;
+                    return Ok(None);
+                }
+                if config.delimiters {
+                    return err(B85DecodeErrorKind::InvalidDelimiter);
+                }
+                self.phase = B85Phase::Body;
+            }
+            B85Phase::Lt => {
+                if b == b'~' {
+                    self.phase = B85Phase::Body;
+                    self.opened = true;
+                    return Ok(None);
+                }
+                if config.delimiters {
+                    return err(B85DecodeErrorKind::InvalidDelimiter);
+                }
+                // the '<' was a symbol after all. one symbol can not complete a group.
+                self.phase = B85Phase::Body;
+                self.symbol(config, b'<', self.first_index)?;
+            }
+            B85Phase::Tilde => {
+                if b == b'>' {
+                    self.phase = B85Phase::End;
+                    return self.flush(index);
+                }
+                return err(B85DecodeErrorKind::InvalidDelimiter);
+            }
+            B85Phase::End => {
+                if b85_is_whitespace(b) {
+                    return Ok(None);
+                }
+                return err(B85DecodeErrorKind::TrailingData);
+            }
+            B85Phase::Body => {}
+        }
+        if b == b'~' {
+            self.phase = B85Phase::Tilde;
+            return Ok(None);
+        }
+        self.symbol(config, b, index)
+    }
+
+    #[inline]
+    fn symbol(
+        &mut self,
+        config: &B85Config,
+        b: u8,
+        index: usize,
+    ) -> Result<Option<B85Group>, B85DecodeError> {
+        if b85_is_whitespace(b) {
+            return Ok(None);
+        }
+        let o = match config.alphabet.byte_to_ord(b) {
+            Some(o) => o,
+            None if b == b'z' && config.zero_group && self.ct == 0 => {
+                return Ok(Some(B85Group {
+                    bytes: [0; 4],
+                    len: 4,
+                }));
+            }
+            None => {
+                return Err(B85DecodeError::new(
+                    B85DecodeErrorKind::InvalidSymbol,
+                    index,
+                ));
+            }
+        };
+        if self.ct == 0 {
+            self.first_index = index;
+        }
+        self.acc = self.acc * 85 + o as u64;
+        self.ct += 1;
+        if self.ct == 5 {
+            return self.flush(index).map(Some).map(Option::flatten);
+        }
+        Ok(None)
+    }
+
+    // releases the current group, padding a partial one with the highest symbol
+    fn flush(&mut self, index: usize) -> Result<Option<B85Group>, B85DecodeError> {
+        match self.ct {
+            0 => Ok(None),
+            1 => Err(B85DecodeError::new(
+                B85DecodeErrorKind::TruncatedGroup,
+                self.first_index,
+            )),
+            _ => match self.padded() {
+                Some(g) => {
+                    self.acc = 0;
+                    self.ct = 0;
+                    Ok(Some(g))
+                }
+                None => Err(B85DecodeError::new(
+                    B85DecodeErrorKind::Overflow,
+                    if self.ct == 5 {
+                        self.first_index
+                    } else {
+                        index
+                    },
+                )),
+            },
+        }
+    }
+
+    // the bytes of the current group, or None if it overflows 32 bits
+    #[inline]
+    fn padded(&self) -> Option<B85Group> {
+        let mut acc = self.acc;
+        for _ in self.ct..5 {
+            acc = acc * 85 + 84;
+        }
+        let n = u32::try_from(acc).ok()?;
+        Some(B85Group {
+            bytes: n.to_be_bytes(),
+            len: self.ct - 1,
+        })
+    }
+
+    // the complete bytes of the current partial group, for lenient decoding
+    #[inline]
+    fn partial(&self) -> B85Group {
+        if self.ct > 1 {
+            self.padded().unwrap_or_default()
+        } else {
+            B85Group::default()
+        }
+    }
+
+    // end of input, end_index being the length of the input.
+    // returns the bytes of the final partial group, if any.
+    fn finish(
+        &mut self,
+        config: &B85Config,
+        end_index: usize,
+    ) -> Result<Option<B85Group>, B85DecodeError> {
+        let err = |kind| Err(B85DecodeError::new(kind, end_index));
+        match self.phase {
+            B85Phase::End => Ok(None),
+            B85Phase::Tilde => err(B85DecodeErrorKind::InvalidDelimiter),
+            _ if config.delimiters || self.opened => err(B85DecodeErrorKind::InvalidDelimiter),
+            B85Phase::Lt => {
+                self.phase = B85Phase::Body;
+                self.symbol(config, b'<', self.first_index)?;
+                self.flush(end_index)
+            }
+            _ => self.flush(end_index),
+        }
+    }
+}
+
+///
+/// converts a slice of bytes into an ascii85 encoded string, with `z` shorthand and no delimiters
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_a85;
+///
+/// let s2 = bytes_to_a85(b"Man is d");
+/// assert_eq!(s2.as_str(), "9jqo^BlbD-");
+/// ```
+pub fn bytes_to_a85(bytes: &[u8]) -> String {
+    B85Config::ASCII85.encode(bytes)
+}
+
+///
+/// converts a slice of bytes into Adobe's ascii85, wrapped in `<~ ~>`
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_a85_adobe;
+///
+/// let s2 = bytes_to_a85_adobe(b"Man is d");
+/// assert_eq!(s2.as_str(), "<~9jqo^BlbD-~>");
+/// ```
+pub fn bytes_to_a85_adobe(bytes: &[u8]) -> String {
+    B85Config::ADOBE.encode(bytes)
+}
+
+///
+/// converts ascii85 encoded data, either a str or ASCII bytes, into a vec of bytes. whitespace
+/// is skipped, and `<~ ~>` delimiters are optional.
+///
+/// decoding stops at the first problem, returning what was decoded up to that point,
+/// or None if nothing could be decoded. see [`a85_to_bytes_strict`] to reject bad input.
+///
+/// ```
+///  # use crate::cj_common::prelude::a85_to_bytes;
+///
+/// assert_eq!(a85_to_bytes("9jqo^ BlbD-"), Some(b"Man is d".to_vec()));
+/// assert_eq!(a85_to_bytes("<~9jqo^BlbD-~>"), Some(b"Man is d".to_vec()));
+/// ```
+pub fn a85_to_bytes(data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    B85Config::ASCII85.decode(data)
+}
+
+/// converts ascii85 encoded data into a vec of bytes, failing with the kind and location of the
+/// first problem in the input
+/// ```
+/// # use cj_common::prelude::*;
+/// let e = a85_to_bytes_strict("<~9jqo^").unwrap_err();
+/// assert_eq!(e, B85DecodeError::new(B85DecodeErrorKind::InvalidDelimiter, 7));
+/// ```
+pub fn a85_to_bytes_strict(data: impl AsRef<[u8]>) -> Result<Vec<u8>, B85DecodeError> {
+    B85Config::ASCII85.try_decode(data)
+}
+
+///
+/// converts a slice of bytes into Z85 (ZeroMQ RFC 32). the RFC only covers multiples of 4 bytes;
+/// any other final group is written as n + 1 chars, like ascii85.
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_z85;
+///
+/// let s2 = bytes_to_z85(&[0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B]);
+/// assert_eq!(s2.as_str(), "HelloWorld");
+/// ```
+pub fn bytes_to_z85(bytes: &[u8]) -> String {
+    B85Config::Z85.encode(bytes)
+}
+
+///
+/// converts Z85 encoded data, either a str or ASCII bytes, into a vec of bytes
+///
+/// decoding stops at the first problem, returning what was decoded up to that point,
+/// or None if nothing could be decoded. see [`z85_to_bytes_strict`] to reject bad input.
+///
+/// ```
+///  # use crate::cj_common::prelude::z85_to_bytes;
+///
+/// let v = z85_to_bytes("HelloWorld").unwrap();
+/// assert_eq!(v, vec![0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B]);
+/// ```
+pub fn z85_to_bytes(data: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    B85Config::Z85.decode(data)
+}
+
+/// converts Z85 encoded data into a vec of bytes, failing with the kind and location of the first
+/// problem in the input
+/// ```
+/// # use cj_common::prelude::*;
+/// let e = z85_to_bytes_strict("Hello,World").unwrap_err();
+/// assert_eq!(e, B85DecodeError::new(B85DecodeErrorKind::InvalidSymbol, 5));
+/// ```
+pub fn z85_to_bytes_strict(data: impl AsRef<[u8]>) -> Result<Vec<u8>, B85DecodeError> {
+    B85Config::Z85.try_decode(data)
+}
+
+///
+/// Iterator for a slice of bytes that returns Ascii85 / Z85
+///
+/// ```
+///  # use cj_common::prelude::*;
+/// let s2 = ToBase85Iter::new_z85([0x86u8, 0x4F, 0xD2, 0x6F].iter()).collect::<String>();
+/// assert_eq!(s2.as_str(), "Hello");
+/// ```
+pub struct ToBase85Iter<'a> {
+    pend: B85Chars,
+    pend_pos: usize,
+    lead: &'static [u8],
+    tail: Option<&'static [u8]>,
+    config: &'a B85Config,
+    inner: Iter<'a, u8>,
+}
+
+impl<'a> ToBase85Iter<'a> {
+    /// Ascii85 with `z` shorthand and no delimiters
+    pub fn new(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B85Config::ASCII85)
+    }
+
+    /// Z85 variant of [`ToBase85Iter::new`]
+    pub fn new_z85(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B85Config::Z85)
+    }
+
+    /// same as [`ToBase85Iter::new`], but encodes using the given config
+    pub fn new_with(i: Iter<'a, u8>, config: &'a B85Config) -> Self {
+        Self {
+            pend: B85Chars::default(),
+            pend_pos: 0,
+            lead: if config.delimiters { b"<~" } else { b"" },
+            tail: None,
+            config,
+            inner: i,
+        }
+    }
+}
+
+impl Iterator for ToBase85Iter<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((c, rest)) = self.lead.split_first() {
+            self.lead = rest;
+            return Some(*c as char);
+        }
+        if self.pend_pos == self.pend.len && self.tail.is_none() {
+            let mut chunk = [0u8; 4];
+            let mut n = 0;
+            while n < 4 {
+                match self.inner.next() {
+                    Some(b) => chunk[n] = *b,
+                    None => break,
+                }
+                n += 1;
+            }
+            if n == 0 {
+                self.tail = Some(if self.config.delimiters { b"~>" } else { b"" });
+            } else {
+                self.pend = B85Chars::from_chunk(self.config, &chunk[..n]);
+                self.pend_pos = 0;
+            }
+        }
+        if let Some(tail) = self.tail {
+            let (c, rest) = tail.split_first()?;
+            self.tail = Some(rest);
+            return Some(*c as char);
+        }
+        let c = self.pend.chars[self.pend_pos];
+        self.pend_pos += 1;
+        Some(c as char)
+    }
+}
+
+pub trait CjToBase85Iter {
+    fn iter_to_a85(&self) -> ToBase85Iter<'_>;
+    fn iter_to_z85(&self) -> ToBase85Iter<'_>;
+    fn iter_to_b85_with<'a>(&'a self, config: &'a B85Config) -> ToBase85Iter<'a>;
+}
+
+impl<T: AsRef<[u8]> + ?Sized> CjToBase85Iter for T {
+    /// Iterator for anything holding bytes that produces Ascii85 encoded chars
+    /// ```
+    /// # use cj_common::prelude::CjToBase85Iter;
+    /// let s2 = "Man is d".iter_to_a85().collect::<String>();
+    /// assert_eq!(s2.as_str(), "9jqo^BlbD-");
+    /// ```
+    fn iter_to_a85(&self) -> ToBase85Iter<'_> {
+        ToBase85Iter::new(self.as_ref().iter())
+    }
+    /// Iterator for anything holding bytes that produces Z85 encoded chars
+    /// ```
+    /// # use cj_common::prelude::CjToBase85Iter;
+    /// let s2 = [0x86u8, 0x4F, 0xD2, 0x6F].iter_to_z85().collect::<String>();
+    /// assert_eq!(s2.as_str(), "Hello");
+    /// ```
+    fn iter_to_z85(&self) -> ToBase85Iter<'_> {
+        ToBase85Iter::new_z85(self.as_ref().iter())
+    }
+    /// Iterator for anything holding bytes that produces encoded chars using the given config
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let s2 = "Man is d".iter_to_b85_with(&B85Config::ADOBE).collect::<String>();
+    /// assert_eq!(s2.as_str(), "<~9jqo^BlbD-~>");
+    /// ```
+    fn iter_to_b85_with<'a>(&'a self, config: &'a B85Config) -> ToBase85Iter<'a> {
+        ToBase85Iter::new_with(self.as_ref().iter(), config)
+    }
+}
+
+///
+/// Iterator for Ascii85 / Z85 encoded data, either a str or ASCII bytes, that returns decoded bytes
+///
+/// ```
+///  # use cj_common::prelude::*;
+/// let v = "<~9jqo^BlbD-~>".iter_a85_to_byte().collect::<Vec<u8>>();
+/// assert_eq!(v.as_slice(), b"Man is d");
+/// ```
+pub struct FromBase85Iter<'a> {
+    state: B85DecodeState,
+    pend: B85Group,
+    pend_pos: usize,
+    done: bool,
+    index: usize,
+    error: Option<B85DecodeError>,
+    config: &'a B85Config,
+    inner: Iter<'a, u8>,
+}
+
+impl<'a> FromBase85Iter<'a> {
+    /// Ascii85 with `z` shorthand and optional delimiters
+    pub fn new(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B85Config::ASCII85)
+    }
+
+    /// Z85 variant of [`FromBase85Iter::new`]
+    pub fn new_z85(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &B85Config::Z85)
+    }
+
+    /// same as [`FromBase85Iter::new`], but decodes using the given config.
+    ///
+    /// a strict config ends the iterator at the first problem in the input, without returning
+    /// the bytes of the group it was found in.
+    pub fn new_with(i: Iter<'a, u8>, config: &'a B85Config) -> Self {
+        Self {
+            state: B85DecodeState::new(),
+            pend: B85Group::default(),
+            pend_pos: 0,
+            done: false,
+            index: 0,
+            error: None,
+            config,
+            inner: i,
+        }
+    }
+
+    /// the problem that ended decoding, if any. byte offsets are relative to the start of the
+    /// str or bytes the iterator was created from.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut it = "9jqo^~>x".iter_a85_to_byte();
+    /// let v = it.by_ref().collect::<Vec<u8>>();
+    /// assert_eq!(v.as_slice(), b"Man ");
+    /// assert_eq!(
+    ///     it.error(),
+    ///     Some(B85DecodeError::new(B85DecodeErrorKind::TrailingData, 7))
+    /// );
+    /// ```
+    pub fn error(&self) -> Option<B85DecodeError> {
+        self.error
+    }
+
+    #[inline]
+    fn next_pending(&mut self) -> Option<u8> {
+        if self.pend_pos < self.pend.len {
+            let b = self.pend.bytes[self.pend_pos];
+            self.pend_pos += 1;
+            return Some(b);
+        }
+        None
+    }
+
+    #[inline]
+    fn start_group(&mut self, g: B85Group) -> Option<u8> {
+        self.pend = g;
+        self.pend_pos = 0;
+        self.next_pending()
+    }
+
+    // decoding stopped at a problem in the input. a lenient config keeps what it can.
+    fn fail(&mut self, e: B85DecodeError) -> Option<u8> {
+        self.done = true;
+        self.error = Some(e);
+        if self.config.strict {
+            None
+        } else {
+            self.start_group(self.state.partial())
+        }
+    }
+}
+
+impl Iterator for FromBase85Iter<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(b) = self.next_pending() {
+            return Some(b);
+        }
+        if self.done {
+            return None;
+        }
+        while let Some(b) = self.inner.next() {
+            let i = self.index;
+            self.index += 1;
+            match self.state.push(self.config, *b, i) {
+                Ok(Some(g)) => return self.start_group(g),
+                Ok(None) => {}
+                Err(e) => return self.fail(e),
+            }
+        }
+
+        self.done = true;
+        match self.state.finish(self.config, self.index) {
+            Ok(Some(g)) => self.start_group(g),
+            Ok(None) => None,
+            Err(e) => self.fail(e),
+        }
+    }
+}
+
+pub trait CjFromBase85Iter {
+    fn iter_a85_to_byte(&self) -> FromBase85Iter<'_>;
+    fn iter_z85_to_byte(&self) -> FromBase85Iter<'_>;
+    fn iter_b85_to_byte_with<'a>(&'a self, config: &'a B85Config) -> FromBase85Iter<'a>;
+}
+
+impl<T: AsRef<[u8]> + ?Sized> CjFromBase85Iter for T {
+    /// Iterator for decoding Ascii85 held in a str or as ASCII bytes to bytes
+    /// ```
+    /// # use cj_common::prelude::CjFromBase85Iter;
+    /// let v = "9jqo^BlbD-".iter_a85_to_byte().collect::<Vec<u8>>();
+    /// assert_eq!(v.as_slice(), b"Man is d");
+    /// ```
+    fn iter_a85_to_byte(&self) -> FromBase85Iter<'_> {
+        FromBase85Iter::new(self.as_ref().iter())
+    }
+    /// Iterator for decoding Z85 held in a str or as ASCII bytes to bytes
+    /// ```
+    /// # use cj_common::prelude::CjFromBase85Iter;
+    /// let v = "Hello".iter_z85_to_byte().collect::<Vec<u8>>();
+    /// assert_eq!(v, vec![0x86u8, 0x4F, 0xD2, 0x6F]);
+    /// ```
+    fn iter_z85_to_byte(&self) -> FromBase85Iter<'_> {
+        FromBase85Iter::new_z85(self.as_ref().iter())
+    }
+    /// Iterator for decoding data held in a str or as ASCII bytes to bytes using the given config
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let cfg = B85Config::ADOBE.with_strict(true);
+    /// let v = "<~9jqo^~>".iter_b85_to_byte_with(&cfg).collect::<Vec<u8>>();
+    /// assert_eq!(v.as_slice(), b"Man ");
+    /// ```
+    fn iter_b85_to_byte_with<'a>(&'a self, config: &'a B85Config) -> FromBase85Iter<'a> {
+        FromBase85Iter::new_with(self.as_ref().iter(), config)
+    }
+}
+
+pub trait CjToBase85 {
+    fn to_a85_string(&self) -> String;
+    fn to_z85_string(&self) -> String;
+    fn to_b85_string_with(&self, config: &B85Config) -> String;
+}
+
+impl<T: AsRef<[u8]> + ?Sized> CjToBase85 for T {
+    /// converts anything holding bytes (`&str`, `String`, `Vec<u8>`, `[u8; N]`, ...) to an Ascii85
+    /// encoded String, with `z` shorthand and no delimiters
+    /// ```
+    /// # use cj_common::prelude::CjToBase85;
+    /// let s2 = "Man is d".to_a85_string();
+    /// assert_eq!(s2.as_str(), "9jqo^BlbD-");
+    /// ```
+    fn to_a85_string(&self) -> String {
+        bytes_to_a85(self.as_ref())
+    }
+    /// converts anything holding bytes to a Z85 encoded String
+    /// ```
+    /// # use cj_common::prelude::CjToBase85;
+    /// let s2 = vec![0x86u8, 0x4F, 0xD2, 0x6F].to_z85_string();
+    /// assert_eq!(s2.as_str(), "Hello");
+    /// ```
+    fn to_z85_string(&self) -> String {
+        bytes_to_z85(self.as_ref())
+    }
+    /// converts anything holding bytes to an encoded String using the given config
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let s2 = "Man is d".to_b85_string_with(&B85Config::ADOBE);
+    /// assert_eq!(s2.as_str(), "<~9jqo^BlbD-~>");
+    /// ```
+    fn to_b85_string_with(&self, config: &B85Config) -> String {
+        config.encode(self.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEVIATHAN: &str = "Man is distinguished, not only by his reason, but by this singular passion from other animals, which is a lust of the mind, that by a perseverance of delight in the continued and indefatigable generation of knowledge, exceeds the short vehemence of any carnal pleasure.";
+
+    #[test]
+    fn test_vectors() {
+        let s = bytes_to_a85_adobe(LEVIATHAN.as_bytes());
+        assert!(s.starts_with(
+            "<~9jqo^BlbD-BleB1DJ+*+F(f,q/0JhKF<GL>Cj@.4Gp$d7F!,L7@<6@)/0JDEF<G%<+EV:2F!,"
+        ));
+        assert!(s.ends_with("'+CT/5+Cei#DII?(E,9)oF*2M7/c~>"));
+        assert_eq!(a85_to_bytes_strict(&s).unwrap(), LEVIATHAN.as_bytes());
+
+        // ZeroMQ RFC 32
+        let z = [0x86u8, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B];
+        assert_eq!(bytes_to_z85(&z), "HelloWorld");
+        assert_eq!(z85_to_bytes_strict("HelloWorld"), Ok(z.to_vec()));
+
+        assert_eq!(bytes_to_a85(&[0, 0, 0, 0]), "z");
+        assert_eq!(bytes_to_a85(&[0, 0, 0]), "!!!!");
+        assert_eq!(bytes_to_z85(&[0, 0, 0, 0]), "00000");
+        assert_eq!(bytes_to_a85(&[0xFF; 4]), "s8W-!");
+        assert_eq!(bytes_to_a85(b""), "");
+        assert_eq!(bytes_to_a85_adobe(b""), "<~~>");
+        assert_eq!(a85_to_bytes_strict("<~~>"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut data = (0..64u32).map(|x| (x * 89 + 3) as u8).collect::<Vec<u8>>();
+        data[8..16].fill(0);
+        data[30..34].fill(0xFF);
+        for cfg in [
+            B85Config::ASCII85,
+            B85Config::ADOBE,
+            B85Config::Z85,
+            B85Config::ADOBE.with_zero_group(false),
+        ] {
+            for n in 0..data.len() {
+                let s = cfg.encode(&data[..n]);
+                assert!(s.len() <= cfg.encoded_len_max(n));
+                assert_eq!(s, cfg.iter_encode(&data[..n]).collect::<String>());
+                assert_eq!(cfg.try_decode(&s).unwrap(), &data[..n]);
+                let mut it = cfg.iter_decode(&s);
+                assert_eq!(it.by_ref().collect::<Vec<u8>>(), &data[..n]);
+                assert_eq!(it.error(), None);
+                // whitespace is skipped anywhere but inside a delimiter
+                let d = if cfg.delimiters() { 2 } else { 0 };
+                let body = &s[d..s.len() - d];
+                let mut spaced = format!(" {}", &s[..d]);
+                for c in body.chars() {
+                    spaced.push(c);
+                    spaced.push('\n');
+                }
+                spaced.push_str(&s[s.len() - d..]);
+                assert_eq!(cfg.try_decode(&spaced).unwrap(), &data[..n]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let e = |kind, index| Err(B85DecodeError::new(kind, index));
+        assert_eq!(
+            a85_to_bytes_strict("9jqo^B"),
+            e(B85DecodeErrorKind::TruncatedGroup, 5)
+        );
+        assert_eq!(
+            a85_to_bytes_strict("9jzo^"),
+            e(B85DecodeErrorKind::InvalidSymbol, 2)
+        );
+        assert_eq!(
+            a85_to_bytes_strict("9jqo^v"),
+            e(B85DecodeErrorKind::InvalidSymbol, 5)
+        );
+        assert_eq!(
+            a85_to_bytes_strict("s8W-\""),
+            e(B85DecodeErrorKind::Overflow, 0)
+        );
+        assert_eq!(
+            a85_to_bytes_strict("!!!!!uuu"),
+            e(B85DecodeErrorKind::Overflow, 8)
+        );
+        assert_eq!(
+            a85_to_bytes_strict("<~9jqo^~"),
+            e(B85DecodeErrorKind::InvalidDelimiter, 8)
+        );
+        assert_eq!(
+            a85_to_bytes_strict("<~9jqo^~x"),
+            e(B85DecodeErrorKind::InvalidDelimiter, 8)
+        );
+        assert_eq!(
+            a85_to_bytes_strict("9jqo^~> x"),
+            e(B85DecodeErrorKind::TrailingData, 8)
+        );
+        assert_eq!(a85_to_bytes_strict("9jqo^~> \n"), Ok(b"Man ".to_vec()));
+
+        // a leading '<' that does not start a delimiter is a symbol
+        assert_eq!(
+            a85_to_bytes_strict("<<<<<"),
+            B85Config::ASCII85.try_decode("<<<<<")
+        );
+        assert_eq!(a85_to_bytes_strict("<<<<<").unwrap().len(), 4);
+        assert_eq!(
+            a85_to_bytes_strict("<"),
+            e(B85DecodeErrorKind::TruncatedGroup, 0)
+        );
+
+        let cfg = B85Config::ADOBE;
+        assert_eq!(
+            cfg.try_decode("9jqo^"),
+            e(B85DecodeErrorKind::InvalidDelimiter, 0)
+        );
+        assert_eq!(
+            cfg.try_decode("<9jqo^"),
+            e(B85DecodeErrorKind::InvalidDelimiter, 1)
+        );
+        assert_eq!(
+            cfg.try_decode("<~9jqo^"),
+            e(B85DecodeErrorKind::InvalidDelimiter, 7)
+        );
+        assert_eq!(
+            cfg.try_decode(""),
+            e(B85DecodeErrorKind::InvalidDelimiter, 0)
+        );
+
+        // Z85 has no 'z' shorthand or '~'
+        assert_eq!(
+            z85_to_bytes_strict("Hell~"),
+            e(B85DecodeErrorKind::InvalidDelimiter, 5)
+        );
+        assert_eq!(
+            z85_to_bytes_strict("Hello World"),
+            Ok(vec![0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B])
+        );
+
+        // lenient decoding keeps what came before the problem
+        assert_eq!(a85_to_bytes("9jqo^BlbD-v"), Some(b"Man is d".to_vec()));
+        assert_eq!(a85_to_bytes("9jqo^Blbv"), Some(b"Man is".to_vec()));
+        assert_eq!(a85_to_bytes("v"), None);
+        let cfg = B85Config::ASCII85.with_strict(true);
+        assert_eq!(cfg.decode("9jqo^Blbv"), None);
+        assert_eq!(cfg.iter_decode("9jqo^Blbv").collect::<Vec<u8>>(), b"Man ");
+
+        let s = format!("{}", B85DecodeError::new(B85DecodeErrorKind::Overflow, 3));
+        assert_eq!(s.as_str(), "Base85 group overflows 32 bits at byte 3");
+    }
+}
@@ -357,7 +357,6 @@ impl Bitflag for u128 {
 ///     }
 /// }
 /// ```
-
 // TO.DO these need moved out of bitbuf and into a dedicated file
 const DEF_U8: &u8 = &0;
 const DEF_U16: &u16 = &0;
@@ -0,0 +1,68 @@
+//! constant-time helpers for secret material, such as API keys and private keys
+//!
+//! the constant-time decoders live with their codecs, [`crate::cj_binary::hex::hex_str_to_u8_vec_ct`]
+//! and [`crate::cj_binary::b64::b64_to_bytes_ct`]. they look up each char with arithmetic instead of
+//! tables or matches, and read the whole input before reporting a problem. lengths are not secret.
+//!
+//! # Quick Start
+//!
+//!```
+//! use cj_common::prelude::*;
+//!
+//! let key = hex_str_to_u8_vec_ct("DEADbeef").unwrap();
+//! assert!(ct_eq(&key, &[0xDE, 0xAD, 0xBE, 0xEF]));
+//! assert!(!ct_eq(&key, &[0xDE, 0xAD, 0xBE, 0xEE]));
+//! ```
+
+use std::hint::black_box;
+
+/// compares two slices of bytes in time that depends only on their lengths
+/// ```
+/// # use cj_common::prelude::ct_eq;
+/// assert!(ct_eq(b"secret", b"secret"));
+/// assert!(!ct_eq(b"secret", b"secreT"));
+/// assert!(!ct_eq(b"secret", b"secrets"));
+/// ```
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b) {
+        diff |= x ^ y;
+    }
+    black_box(diff) == 0
+}
+
+// 0xFF if lo <= c <= hi, else 0, without branching on c
+#[inline(always)]
+pub(crate) const fn ct_in_range(c: u8, lo: u8, hi: u8) -> u8 {
+    let c = c as i16;
+    // both differences are negative only inside the range, and the sign fills the result
+    (((lo as i16 - 1 - c) & (c - hi as i16 - 1)) >> 15) as u8
+}
+
+// 0xFF if x is zero, else 0, without branching on x
+#[inline(always)]
+pub(crate) const fn ct_is_zero(x: u32) -> u8 {
+    0u8.wrapping_sub(((x as u64).wrapping_sub(1) >> 63) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_masks() {
+        for c in 0..=255u8 {
+            let expect = if c.is_ascii_digit() { 0xFF } else { 0 };
+            assert_eq!(ct_in_range(c, b'0', b'9'), expect);
+            assert_eq!(ct_in_range(c, c, c), 0xFF);
+            assert_eq!(ct_in_range(c, 0, 255), 0xFF);
+        }
+        assert_eq!(ct_is_zero(0), 0xFF);
+        assert_eq!(ct_is_zero(1), 0);
+        assert_eq!(ct_is_zero(u32::MAX), 0);
+        assert!(ct_eq(b"", b""));
+    }
+}
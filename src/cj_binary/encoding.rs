@@ -0,0 +1,330 @@
+//! the [`Encoding`] trait, shared by the text encodings of `cj_binary`, so code can be generic over
+//! which one is used
+//!
+//! # Quick Start
+//!
+//!```
+//! use cj_common::prelude::*;
+//!
+//! // a field stored as text, with the encoding chosen by the caller
+//! struct Field<E: Encoding> {
+//!     encoding: E,
+//!     text: String,
+//! }
+//!
+//! impl<E: Encoding> Field<E> {
+//!     fn new(encoding: E, value: &[u8]) -> Self {
+//!         let text = encoding.encode(value);
+//!         Self { encoding, text }
+//!     }
+//!
+//!     fn value(&self) -> Result<Vec<u8>, E::Error> {
+//!         self.encoding.decode(&self.text)
+//!     }
+//! }
+//!
+//! let f = Field::new(B64Config::STANDARD, b"Hello");
+//! assert_eq!(f.text.as_str(), "SGVsbG8=");
+//! let f = Field::new(HexConfig::LOWER, b"Hello");
+//! assert_eq!(f.text.as_str(), "48656c6c6f");
+//! assert_eq!(f.value().unwrap(), b"Hello");
+//! let f = Field::new(RadixAlphabet::BASE62, b"Hello");
+//! assert_eq!(f.value().unwrap(), b"Hello");
+//! ```
+
+use crate::cj_binary::b32::{B32Config, B32DecodeError, FromBase32Iter, ToBase32Iter};
+use crate::cj_binary::b58::{B58Alphabet, B58DecodeError};
+use crate::cj_binary::b64::{B64Config, B64DecodeError, FromBase64Iter, ToBase64Iter};
+use crate::cj_binary::b85::{B85Config, B85DecodeError, FromBase85Iter, ToBase85Iter};
+use crate::cj_binary::hex::{FromHexIter, HexConfig, HexDecodeError, ToHexCharIter};
+use crate::cj_binary::radix::{RadixAlphabet, RadixDecodeError};
+
+///
+/// a text encoding of bytes.
+///
+/// implemented by the engine of each codec: [`B64Config`], [`B32Config`], [`B58Alphabet`],
+/// [`B85Config`], [`HexConfig`] and [`RadixAlphabet`]. the engines also have inherent methods of
+/// the same names, which may differ, such as `decode` returning an Option.
+///
+/// Base58 and radix encodings are one number, so they can not be streamed: the encoder and decoder
+/// of [`B58Alphabet`] and [`RadixAlphabet`] build the whole output up front, and the decoder
+/// yields nothing at all for invalid input. use [`Encoding::decode`] to see why.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// fn round_trip<E: Encoding>(e: &E, bytes: &[u8]) -> bool {
+///     let s = e.encoder(bytes).collect::<String>();
+///     s.len() <= e.encoded_len(bytes.len())
+///         && e.decoder(&s).collect::<Vec<u8>>() == bytes
+///         && Encoding::decode(e, &s).is_ok_and(|v| v == bytes)
+/// }
+///
+/// assert!(round_trip(&B64Config::URL_SAFE_NO_PAD, b"any bytes"));
+/// assert!(round_trip(&B32Config::CROCKFORD, b"any bytes"));
+/// assert!(round_trip(&B58Alphabet::BITCOIN, b"any bytes"));
+/// assert!(round_trip(&B85Config::Z85, b"any bytes"));
+/// assert!(round_trip(&HexConfig::UPPER, b"any bytes"));
+/// assert!(round_trip(&RadixAlphabet::BASE36, b"any bytes"));
+/// ```
+pub trait Encoding {
+    /// error returned by [`Encoding::decode`]
+    type Error: std::error::Error + 'static;
+    /// Iterator returned by [`Encoding::encoder`]
+    type Encoder<'a>: Iterator<Item = char>
+    where
+        Self: 'a;
+    /// Iterator returned by [`Encoding::decoder`]
+    type Decoder<'a>: Iterator<Item = u8>
+    where
+        Self: 'a;
+
+    /// converts a slice of bytes into an encoded String
+    fn encode(&self, bytes: &[u8]) -> String;
+
+    /// converts encoded data into a vec of bytes, failing on the first problem in the input
+    fn decode(&self, data: &str) -> Result<Vec<u8>, Self::Error>;
+
+    /// length in chars of the encoding of `byte_len` bytes. exact for encodings of fixed size
+    /// groups (Base64, Base32, hex), and an upper bound for the others.
+    fn encoded_len(&self, byte_len: usize) -> usize;
+
+    /// Iterator over a slice of bytes that produces encoded chars
+    fn encoder<'a>(&'a self, bytes: &'a [u8]) -> Self::Encoder<'a>;
+
+    /// Iterator over encoded data that produces decoded bytes. it ends at the first problem in
+    /// the input, keeping what the codec's lenient decoding would, which for Base58 and radix is
+    /// nothing.
+    fn decoder<'a>(&'a self, data: &'a str) -> Self::Decoder<'a>;
+}
+
+impl Encoding for B64Config {
+    type Error = B64DecodeError;
+    type Encoder<'a> = ToBase64Iter<'a>;
+    type Decoder<'a> = FromBase64Iter<'a>;
+
+    fn encode(&self, bytes: &[u8]) -> String {
+        B64Config::encode(self, bytes)
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<u8>, Self::Error> {
+        self.try_decode(data)
+    }
+
+    fn encoded_len(&self, byte_len: usize) -> usize {
+        B64Config::encoded_len(self, byte_len)
+    }
+
+    fn encoder<'a>(&'a self, bytes: &'a [u8]) -> Self::Encoder<'a> {
+        self.iter_encode(bytes)
+    }
+
+    fn decoder<'a>(&'a self, data: &'a str) -> Self::Decoder<'a> {
+        self.iter_decode(data)
+    }
+}
+
+impl Encoding for B32Config {
+    type Error = B32DecodeError;
+    type Encoder<'a> = ToBase32Iter<'a>;
+    type Decoder<'a> = FromBase32Iter<'a>;
+
+    fn encode(&self, bytes: &[u8]) -> String {
+        B32Config::encode(self, bytes)
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<u8>, Self::Error> {
+        self.try_decode(data)
+    }
+
+    fn encoded_len(&self, byte_len: usize) -> usize {
+        B32Config::encoded_len(self, byte_len)
+    }
+
+    fn encoder<'a>(&'a self, bytes: &'a [u8]) -> Self::Encoder<'a> {
+        self.iter_encode(bytes)
+    }
+
+    fn decoder<'a>(&'a self, data: &'a str) -> Self::Decoder<'a> {
+        self.iter_decode(data)
+    }
+}
+
+// upper bound of the length of a number of byte_len bytes written in radix, where each leading zero
+// byte is one symbol. the extra char covers rounding of the log.
+fn radix_encoded_len_max(radix: usize, byte_len: usize) -> usize {
+    if radix.is_power_of_two() {
+        let bits = radix.trailing_zeros() as usize;
+        return (byte_len * 8).div_ceil(bits);
+    }
+    ((byte_len as f64) * 8.0 / (radix as f64).log2()).ceil() as usize + 1
+}
+
+/// Base58 can not be streamed, as the whole input is one number. its encoder and decoder work on
+/// the whole input at once, and the decoder yields no bytes if any of the input is invalid.
+impl Encoding for B58Alphabet {
+    type Error = B58DecodeError;
+    type Encoder<'a> = std::vec::IntoIter<char>;
+    type Decoder<'a> = std::vec::IntoIter<u8>;
+
+    fn encode(&self, bytes: &[u8]) -> String {
+        B58Alphabet::encode(self, bytes)
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<u8>, Self::Error> {
+        self.try_decode(data)
+    }
+
+    fn encoded_len(&self, byte_len: usize) -> usize {
+        radix_encoded_len_max(58, byte_len)
+    }
+
+    fn encoder<'a>(&'a self, bytes: &'a [u8]) -> Self::Encoder<'a> {
+        B58Alphabet::encode(self, bytes)
+            .chars()
+            .collect::<Vec<char>>()
+            .into_iter()
+    }
+
+    fn decoder<'a>(&'a self, data: &'a str) -> Self::Decoder<'a> {
+        B58Alphabet::decode(self, data)
+            .unwrap_or_default()
+            .into_iter()
+    }
+}
+
+impl Encoding for B85Config {
+    type Error = B85DecodeError;
+    type Encoder<'a> = ToBase85Iter<'a>;
+    type Decoder<'a> = FromBase85Iter<'a>;
+
+    fn encode(&self, bytes: &[u8]) -> String {
+        B85Config::encode(self, bytes)
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<u8>, Self::Error> {
+        self.try_decode(data)
+    }
+
+    fn encoded_len(&self, byte_len: usize) -> usize {
+        self.encoded_len_max(byte_len)
+    }
+
+    fn encoder<'a>(&'a self, bytes: &'a [u8]) -> Self::Encoder<'a> {
+        self.iter_encode(bytes)
+    }
+
+    fn decoder<'a>(&'a self, data: &'a str) -> Self::Decoder<'a> {
+        self.iter_decode(data)
+    }
+}
+
+impl Encoding for HexConfig {
+    type Error = HexDecodeError;
+    type Encoder<'a> = ToHexCharIter<'a>;
+    type Decoder<'a> = FromHexIter<'a>;
+
+    fn encode(&self, bytes: &[u8]) -> String {
+        HexConfig::encode(self, bytes)
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<u8>, Self::Error> {
+        self.try_decode(data)
+    }
+
+    fn encoded_len(&self, byte_len: usize) -> usize {
+        HexConfig::encoded_len(self, byte_len)
+    }
+
+    fn encoder<'a>(&'a self, bytes: &'a [u8]) -> Self::Encoder<'a> {
+        self.iter_encode(bytes)
+    }
+
+    fn decoder<'a>(&'a self, data: &'a str) -> Self::Decoder<'a> {
+        self.iter_decode(data)
+    }
+}
+
+/// like Base58, a radix encoding is one number, so its encoder and decoder work on the whole input
+/// at once, and the decoder yields no bytes if any of the input is invalid.
+impl Encoding for RadixAlphabet {
+    type Error = RadixDecodeError;
+    type Encoder<'a> = std::vec::IntoIter<char>;
+    type Decoder<'a> = std::vec::IntoIter<u8>;
+
+    fn encode(&self, bytes: &[u8]) -> String {
+        RadixAlphabet::encode(self, bytes)
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<u8>, Self::Error> {
+        self.try_decode(data)
+    }
+
+    fn encoded_len(&self, byte_len: usize) -> usize {
+        radix_encoded_len_max(self.radix(), byte_len)
+    }
+
+    fn encoder<'a>(&'a self, bytes: &'a [u8]) -> Self::Encoder<'a> {
+        RadixAlphabet::encode(self, bytes)
+            .chars()
+            .collect::<Vec<char>>()
+            .into_iter()
+    }
+
+    fn decoder<'a>(&'a self, data: &'a str) -> Self::Decoder<'a> {
+        RadixAlphabet::decode(self, data)
+            .unwrap_or_default()
+            .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check<E: Encoding>(e: &E, data: &[u8]) {
+        for n in 0..data.len() {
+            let bytes = &data[..n];
+            let s = Encoding::encode(e, bytes);
+            assert_eq!(s, e.encoder(bytes).collect::<String>());
+            assert!(s.chars().count() <= e.encoded_len(n));
+            assert_eq!(Encoding::decode(e, &s).unwrap(), bytes);
+            assert_eq!(e.decoder(&s).collect::<Vec<u8>>(), bytes);
+        }
+        assert!(Encoding::decode(e, "\u{1F600}").is_err());
+    }
+
+    #[test]
+    fn test_encodings() {
+        let data = (0..48u32)
+            .map(|x| (x * 37 + 5) as u8)
+            .chain([0, 0, 0, 0, 0xFF])
+            .collect::<Vec<u8>>();
+        check(&B64Config::STANDARD, &data);
+        check(&B64Config::MIME, &data);
+        check(&B32Config::HEX_NO_PAD, &data);
+        check(&B32Config::CROCKFORD_CHECK, &data);
+        check(&B58Alphabet::FLICKR, &data);
+        check(&B85Config::ADOBE, &data);
+        check(&HexConfig::LOWER, &data);
+        check(&HexConfig::COLON, &data);
+        check(&HexConfig::PREFIXED, &data);
+        check(&RadixAlphabet::BASE10, &data);
+        check(&RadixAlphabet::BASE2, &data);
+
+        // exact for fixed size groups
+        assert_eq!(Encoding::encoded_len(&HexConfig::UPPER, 5), 10);
+        assert_eq!(Encoding::encoded_len(&B64Config::STANDARD, 5), 8);
+        assert_eq!(Encoding::encoded_len(&RadixAlphabet::BASE2, 3), 24);
+        let e = Encoding::decode(&RadixAlphabet::BASE10, "12x4").unwrap_err();
+        assert_eq!(e, RadixDecodeError { index: 2 });
+
+        // streaming decoders keep the bytes before a problem, while whole number ones keep none
+        assert_eq!(
+            HexConfig::UPPER.decoder("ABCDxx").collect::<Vec<u8>>(),
+            [0xAB, 0xCD]
+        );
+        assert_eq!(RadixAlphabet::BASE10.decoder("12x4").count(), 0);
+        assert_eq!(B58Alphabet::BITCOIN.decoder("2NEpo0").count(), 0);
+        assert!(Encoding::decode(&B58Alphabet::BITCOIN, "2NEpo0").is_err());
+    }
+}
@@ -19,6 +19,13 @@
 //! assert_eq!(s.as_str(), "Many hands make light work.");
 //! ```
 
+use crate::cj_binary::ct::ct_in_range;
+use crate::cj_binary::simd;
+use std::io::{self, Read, Write};
+use std::num::{
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize, NonZeroU8,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+};
 use std::slice::Iter;
 use std::str::Chars;
 
@@ -129,7 +136,11 @@ pub fn u8_to_hex_low(value: &u8) -> String {
 #[inline(always)]
 pub fn u8_array_to_hex(value: &[u8]) -> String {
     let mut rslt = String::with_capacity(value.len() * 2);
-    value.iter().for_each(|f| rslt.push_str(u8_to_hex_str(f)));
+    // SAFETY: only ASCII hex digits are appended
+    let done = simd::hex_encode_prefix(value, true, unsafe { rslt.as_mut_vec() });
+    value[done..]
+        .iter()
+        .for_each(|f| rslt.push_str(u8_to_hex_str(f)));
 
     rslt
 }
@@ -144,7 +155,9 @@ pub fn u8_array_to_hex(value: &[u8]) -> String {
 #[inline(always)]
 pub fn u8_array_to_hex_low(value: &[u8]) -> String {
     let mut rslt = String::with_capacity(value.len() * 2);
-    value
+    // SAFETY: only ASCII hex digits are appended
+    let done = simd::hex_encode_prefix(value, false, unsafe { rslt.as_mut_vec() });
+    value[done..]
         .iter()
         .for_each(|f| rslt.push_str(u8_to_hex_low_str(f)));
 
@@ -185,16 +198,20 @@ pub const fn hex_char_to_u8(hex1: &char) -> Option<u8> {
     }
 }
 
-/// converts a hex str to u8
+/// converts a hex str to u8.
+///
+/// lenient: only the first two chars are read, and a second char that is not hex is ignored, so
+/// "AZ" gives 0x0A. see [`hex_str_to_u8_strict`] to reject such input.
 /// ```
 /// # use cj_common::prelude::*;
 ///
 /// assert_eq!(hex_str_to_u8("AB"),Some(0xAB));
 /// assert_eq!(hex_str_to_u8("G"),None);
+/// assert_eq!(hex_str_to_u8("AZ"),Some(0x0A));
 /// ```
 #[inline(always)]
 pub fn hex_str_to_u8(hex2: &str) -> Option<u8> {
-    if hex2.len() > 0 {
+    if !hex2.is_empty() {
         let mut r: u8;
         if let Some(x) = hex_char_to_u8(&hex2.chars().next()?) {
             r = x << 4;
@@ -212,14 +229,153 @@ pub fn hex_str_to_u8(hex2: &str) -> Option<u8> {
     }
 }
 
+/// converts one or two hex chars to u8, failing with the kind and location of any problem
+/// ```
+/// # use cj_common::prelude::*;
+///
+/// assert_eq!(hex_str_to_u8_strict("AB"), Ok(0xAB));
+/// assert_eq!(hex_str_to_u8_strict("b"), Ok(0x0B));
+///
+/// let e = hex_str_to_u8_strict("AZ").unwrap_err();
+/// assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 1));
+/// let e = hex_str_to_u8_strict("ABC").unwrap_err();
+/// assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::Overflow, 2));
+/// let e = hex_str_to_u8_strict("").unwrap_err();
+/// assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::Empty, 0));
+/// ```
+pub fn hex_str_to_u8_strict(hex2: &str) -> Result<u8, HexDecodeError> {
+    let mut r = 0u8;
+    let mut digits = 0;
+    for (i, c) in hex2.char_indices() {
+        let x =
+            hex_char_to_u8(&c).ok_or(HexDecodeError::new(HexDecodeErrorKind::InvalidChar, i))?;
+        if digits == 2 {
+            return Err(HexDecodeError::new(HexDecodeErrorKind::Overflow, i));
+        }
+        r = (r << 4) | x;
+        digits += 1;
+    }
+    if digits == 0 {
+        return Err(HexDecodeError::new(HexDecodeErrorKind::Empty, 0));
+    }
+    Ok(r)
+}
+
+///
+/// Iterator for a str of hex that returns bytes.
+///
+/// lenient by default: a char that is not hex ends the iterator, after returning the lone nibble
+/// before it, if any. strict iterators, from [`FromHexIter::new_strict`], end without the lone
+/// nibble and record the problem, see [`FromHexIter::error`].
 pub struct FromHexIter<'a> {
     padded: bool,
+    strict: bool,
+    index: usize,
+    separators: u128,
+    error: Option<HexDecodeError>,
     inner: Chars<'a>,
 }
 
 impl<'a> FromHexIter<'a> {
+    /// lenient iterator. padded means the first char is a lone low nibble, as for a str of odd
+    /// length.
     pub fn new(padded: bool, i: Chars<'a>) -> Self {
-        Self { padded, inner: i }
+        Self {
+            padded,
+            strict: false,
+            index: 0,
+            separators: 0,
+            error: None,
+            inner: i,
+        }
+    }
+
+    /// strict iterator, which ends at the first char that is not hex, or at a final lone char
+    pub fn new_strict(i: Chars<'a>) -> Self {
+        Self {
+            padded: false,
+            strict: true,
+            index: 0,
+            separators: 0,
+            error: None,
+            inner: i,
+        }
+    }
+
+    /// iterator using the prefix, separators and strictness of the given config. a lenient config
+    /// without separators gives a lenient iterator, anything else a strict one that skips
+    /// separators between bytes.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let it = FromHexIter::new_with("0xDEAD".chars(), &HexConfig::PREFIXED);
+    /// assert_eq!(it.collect::<Vec<u8>>(), vec![0xDE, 0xAD]);
+    ///
+    /// let mut it = FromHexIter::new_with("de:a:d".chars(), &HexConfig::COLON);
+    /// assert_eq!(it.by_ref().collect::<Vec<u8>>(), vec![0xDE]);
+    /// assert_eq!(it.error(), Some(HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 4)));
+    /// ```
+    pub fn new_with(i: Chars<'a>, config: &HexConfig) -> Self {
+        let (rest, skip) = config.strip(i.as_str());
+        let mut it = if config.strict || config.separators != 0 {
+            Self::new_strict(rest.chars())
+        } else {
+            Self::new(!rest.len().is_multiple_of(2), rest.chars())
+        };
+        it.index = skip;
+        it.separators = config.separators;
+        it
+    }
+
+    /// the problem that ended a strict iterator, if any. byte offsets are relative to the start of
+    /// the str the iterator was created from.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut it = "ABCDE".iter_hex_to_byte_strict();
+    /// assert_eq!(it.by_ref().collect::<Vec<u8>>(), vec![0xAB, 0xCD]);
+    /// assert_eq!(it.error(), Some(HexDecodeError::new(HexDecodeErrorKind::OddLength, 5)));
+    /// ```
+    pub fn error(&self) -> Option<HexDecodeError> {
+        self.error
+    }
+
+    #[inline]
+    fn next_char(&mut self) -> Option<(usize, char)> {
+        let c = self.inner.next()?;
+        let i = self.index;
+        self.index += c.len_utf8();
+        Some((i, c))
+    }
+
+    fn next_byte_strict(&mut self) -> Option<u8> {
+        if self.error.is_some() {
+            return None;
+        }
+        // separators are only skipped between bytes
+        while let Some(c) = self.inner.clone().next()
+            && (c as u32) < 128
+            && (self.separators >> (c as u32)) & 1 == 1
+        {
+            self.next_char();
+        }
+        let (i, c) = self.next_char()?;
+        let r = match (hex_char_to_u8(&c), self.next_char()) {
+            (None, _) => Err(HexDecodeError::new(HexDecodeErrorKind::InvalidChar, i)),
+            (Some(_), None) => Err(HexDecodeError::new(
+                HexDecodeErrorKind::OddLength,
+                self.index,
+            )),
+            (Some(hi), Some((i, c))) => match hex_char_to_u8(&c) {
+                Some(lo) => Ok((hi << 4) | lo),
+                None => Err(HexDecodeError::new(HexDecodeErrorKind::InvalidChar, i)),
+            },
+        };
+        match r {
+            Ok(b) => Some(b),
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
     }
 
     fn next_byte(&mut self) -> Option<u8> {
@@ -248,12 +404,16 @@ impl Iterator for FromHexIter<'_> {
     type Item = u8;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.strict {
+            return self.next_byte_strict();
+        }
         self.next_byte()
     }
 }
 
 pub trait CjFromHexIter {
     fn iter_hex_to_byte(&self) -> FromHexIter<'_>;
+    fn iter_hex_to_byte_strict(&self) -> FromHexIter<'_>;
 }
 
 impl CjFromHexIter for &str {
@@ -269,9 +429,20 @@ impl CjFromHexIter for &str {
     /// ```
     fn iter_hex_to_byte(&self) -> FromHexIter<'_> {
         //let padded = self.chars().count() % 2 > 0; // this is extra overhead, but we need char count to know if the value is padded: 0ABC vs ABC.
-        let padded = self.len() % 2 > 0; // since this is a hex string, we can assume that the chars are valid ASCII.
+        let padded = !self.len().is_multiple_of(2); // since this is a hex string, we can assume that the chars are valid ASCII.
         FromHexIter::new(padded, self.chars())
     }
+    /// Iterator for str that produces bytes from hex, ending at the first problem, which
+    /// [`FromHexIter::error`] then returns
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut it = "4D61Z".iter_hex_to_byte_strict();
+    /// assert_eq!(it.by_ref().collect::<Vec<u8>>(), vec![0x4D, 0x61]);
+    /// assert_eq!(it.error(), Some(HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 4)));
+    /// ```
+    fn iter_hex_to_byte_strict(&self) -> FromHexIter<'_> {
+        FromHexIter::new_strict(self.chars())
+    }
 }
 
 pub struct ToHexIter<'a> {
@@ -439,11 +610,15 @@ pub fn hex_chars_to_u8(hex2: &HexArray) -> Option<u8> {
     }
 }
 
-/// converts a str of hex to vec of u8
+/// converts a str of hex to vec of u8.
+///
+/// lenient: an odd number of digits starts with a lone low nibble. see [`hex_str_to_u8_vec_strict`]
+/// to reject odd lengths and find out where a problem is.
 /// ```
 /// # use cj_common::prelude::*;
 ///
 /// assert_eq!(hex_str_to_u8_vec("AABBCC"),Some(vec![0xAAu8,0xBB,0xCC]));
+/// assert_eq!(hex_str_to_u8_vec("ABC"),Some(vec![0x0Au8,0xBC]));
 /// assert_eq!(hex_str_to_u8_vec("NOPE"),None);
 /// ```
 #[inline]
@@ -453,32 +628,1233 @@ pub fn hex_str_to_u8_vec(hexstr: &str) -> Option<Vec<u8>> {
 
     let mut v: Vec<u8> = Vec::with_capacity((hexstr.len() / 2) + 1);
 
-    if hexstr.len() % 2 != 0 {
-        ct = 1;
-        ca[0] = '0';
+    let mut crs = hexstr.chars();
+    if !hexstr.len().is_multiple_of(2) {
+        // odd length, so the first char is a lone low nibble
+        ca[1] = crs.next()?;
+        v.push(hex_chars_to_u8(&ca)?);
+    }
+    // runs of valid hex go through the SIMD path. what it leaves starts on a char boundary, as all
+    // it took was ASCII.
+    let rest = crs.as_str();
+    let done = simd::hex_decode_prefix(rest.as_bytes(), &mut v);
+    let crs = rest[done..].chars();
+    for c in crs {
+        match ct {
+            0 => {
+                ca[0] = c;
+                ct = 1
+            }
+            1 => {
+                ca[1] = c;
+                ct = 0;
+
+                if let Some(b) = hex_chars_to_u8(&ca) {
+                    v.push(b);
+                } else {
+                    return None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(v)
+}
+
+/// converts a str of hex to vec of u8, failing with the kind and location of the first problem:
+/// a char that is not hex, or a final lone char
+/// ```
+/// # use cj_common::prelude::*;
+///
+/// assert_eq!(hex_str_to_u8_vec_strict("AABBCC"), Ok(vec![0xAAu8, 0xBB, 0xCC]));
+///
+/// let e = hex_str_to_u8_vec_strict("AABBC").unwrap_err();
+/// assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::OddLength, 5));
+/// let e = hex_str_to_u8_vec_strict("AABXCC").unwrap_err();
+/// assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 3));
+/// ```
+pub fn hex_str_to_u8_vec_strict(hexstr: &str) -> Result<Vec<u8>, HexDecodeError> {
+    let mut v: Vec<u8> = Vec::with_capacity(hexstr.len() / 2);
+    // runs of valid hex go through the SIMD path. what it leaves starts on a char boundary, as all
+    // it took was ASCII.
+    let done = simd::hex_decode_prefix(hexstr.as_bytes(), &mut v);
+    let mut it = FromHexIter::new_strict(hexstr[done..].chars());
+    v.extend(it.by_ref());
+    match it.error() {
+        Some(e) => Err(HexDecodeError::new(e.kind, e.index + done)),
+        None => Ok(v),
+    }
+}
+
+// value of a hex char and 0xFF, or 0 and 0 if it is not one, without branching on c
+#[inline(always)]
+const fn ct_hex_nibble(c: u8) -> (u8, u8) {
+    let digit = ct_in_range(c, b'0', b'9');
+    let upper = ct_in_range(c, b'A', b'F');
+    let lower = ct_in_range(c, b'a', b'f');
+    let v = (digit & c.wrapping_sub(b'0'))
+        | (upper & c.wrapping_sub(b'A' - 10))
+        | (lower & c.wrapping_sub(b'a' - 10));
+    (v, digit | upper | lower)
+}
+
+/// constant-time variant of [`hex_str_to_u8_vec`] for secret material. each char is decoded with
+/// arithmetic rather than a table, and the whole str is read before any problem is reported.
+///
+/// only the timing of the str's length leaks. as with [`hex_str_to_u8_vec`], an odd number of
+/// digits starts with a lone low nibble. returns None if any char is not hex.
+/// ```
+/// # use cj_common::prelude::*;
+/// assert_eq!(hex_str_to_u8_vec_ct("AAbbCC"), Some(vec![0xAAu8, 0xBB, 0xCC]));
+/// assert_eq!(hex_str_to_u8_vec_ct("ABC"), Some(vec![0x0Au8, 0xBC]));
+/// assert_eq!(hex_str_to_u8_vec_ct("NOPE"), None);
+/// ```
+pub fn hex_str_to_u8_vec_ct(hexstr: &str) -> Option<Vec<u8>> {
+    let b = hexstr.as_bytes();
+    let mut v = Vec::with_capacity(b.len().div_ceil(2));
+    let mut valid = 0xFFu8;
+    let (head, rest) = b.split_at(b.len() % 2);
+    for c in head {
+        let (x, ok) = ct_hex_nibble(*c);
+        valid &= ok;
+        v.push(x);
+    }
+    for p in rest.chunks_exact(2) {
+        let (hi, ok_hi) = ct_hex_nibble(p[0]);
+        let (lo, ok_lo) = ct_hex_nibble(p[1]);
+        valid &= ok_hi & ok_lo;
+        v.push((hi << 4) | lo);
+    }
+    if std::hint::black_box(valid) == 0xFF {
+        Some(v)
+    } else {
+        None
+    }
+}
+
+///
+/// Iterator for a slice of bytes that produces hex one char at a time
+///
+/// ```
+///  # use cj_common::prelude::*;
+/// let s = ToHexCharIter::new_low([0xABu8, 0x01].iter()).collect::<String>();
+/// assert_eq!(s.as_str(), "ab01");
+///
+/// let s = ToHexCharIter::new_with([0xABu8, 0x01].iter(), &HexConfig::COLON).collect::<String>();
+/// assert_eq!(s.as_str(), "ab:01");
+/// ```
+pub struct ToHexCharIter<'a> {
+    pend: Option<char>,
+    lead: Chars<'static>,
+    held: Option<u8>,
+    count: usize,
+    config: HexConfig,
+    inner: Iter<'a, u8>,
+}
+
+impl<'a> ToHexCharIter<'a> {
+    /// uppercase hex
+    pub fn new(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &HexConfig::UPPER)
+    }
+
+    /// lowercase hex
+    pub fn new_low(i: Iter<'a, u8>) -> Self {
+        Self::new_with(i, &HexConfig::LOWER)
+    }
+
+    /// hex with the case, prefix and separators of the given config
+    pub fn new_with(i: Iter<'a, u8>, config: &HexConfig) -> Self {
+        Self {
+            pend: None,
+            lead: config.prefix.chars(),
+            held: None,
+            count: 0,
+            config: *config,
+            inner: i,
+        }
+    }
+}
+
+impl Iterator for ToHexCharIter<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(c) = self.pend.take() {
+            return Some(c);
+        }
+        if let Some(c) = self.lead.next() {
+            return Some(c);
+        }
+        let b = match self.held.take() {
+            Some(b) => b,
+            None => {
+                let b = *self.inner.next()?;
+                // the separator goes out first, holding the byte back until it is done
+                self.lead = self.config.gap(self.count).chars();
+                if let Some(c) = self.lead.next() {
+                    self.held = Some(b);
+                    return Some(c);
+                }
+                b
+            }
+        };
+        self.count += 1;
+        let s = if self.config.lower {
+            u8_to_hex_low_str(&b)
+        } else {
+            u8_to_hex_str(&b)
+        }
+        .as_bytes();
+        self.pend = Some(s[1] as char);
+        Some(s[0] as char)
+    }
+}
+
+// ASCII chars, other than hex digits, as a mask of bits
+const fn hex_separator_mask(chars: &str) -> u128 {
+    let b = chars.as_bytes();
+    let mut mask = 0u128;
+    let mut i = 0;
+    while i < b.len() {
+        if b[i] < 128 && !b[i].is_ascii_hexdigit() {
+            mask |= 1u128 << b[i];
+        }
+        i += 1;
+    }
+    mask
+}
+
+///
+/// hex engine bundling the case, prefix, separator and strictness options used for encoding and
+/// decoding, for use where a codec is passed around as a value, such as
+/// [`crate::cj_binary::encoding::Encoding`].
+///
+/// decoding reads either case, skips one leading `0x`, `0X`, `#` or the config's own prefix when
+/// prefixes are stripped, and skips separator chars between bytes. a lenient config without
+/// separators (the default) decodes like [`hex_str_to_u8_vec`], where an odd number of digits
+/// starts with a lone low nibble. otherwise it decodes like [`hex_str_to_u8_vec_strict`].
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// let cfg = HexConfig::LOWER;
+/// let s = cfg.encode(&[0xDE, 0xAD]);
+/// assert_eq!(s.as_str(), "dead");
+/// assert_eq!(cfg.decode(&s), Some(vec![0xDE, 0xAD]));
+/// assert_eq!(cfg.decode("EAD"), Some(vec![0x0E, 0xAD]));
+/// assert_eq!(cfg.with_strict(true).decode("EAD"), None);
+///
+/// let e = cfg.try_decode("DEAX").unwrap_err();
+/// assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 3));
+///
+/// // MAC addresses, fingerprints and dumps
+/// let cfg = HexConfig::UPPER.with_group("-", 2).with_separators(":- ");
+/// assert_eq!(cfg.encode(&[0xDE, 0xAD, 0xBE, 0xEF]), "DEAD-BEEF");
+/// assert_eq!(cfg.decode("de:ad be-ef"), Some(vec![0xDE, 0xAD, 0xBE, 0xEF]));
+///
+/// // a separator may not split a byte
+/// let e = cfg.try_decode("d:ead").unwrap_err();
+/// assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexConfig {
+    lower: bool,
+    strict: bool,
+    prefix: &'static str,
+    strip_prefix: bool,
+    separator: &'static str,
+    group: usize,
+    separators: u128,
+    line_len: usize,
+    line_ending: &'static str,
+}
+
+impl HexConfig {
+    /// uppercase hex, lenient decoding
+    pub const UPPER: HexConfig = HexConfig {
+        lower: false,
+        strict: false,
+        prefix: "",
+        strip_prefix: false,
+        separator: "",
+        group: 0,
+        separators: 0,
+        line_len: 0,
+        line_ending: "\n",
+    };
+    /// lowercase hex, lenient decoding
+    pub const LOWER: HexConfig = HexConfig::UPPER.with_lowercase(true);
+    /// lowercase bytes separated by `:`, as in MAC addresses and fingerprints
+    pub const COLON: HexConfig = HexConfig::LOWER.with_group(":", 1);
+    /// lowercase bytes separated by spaces, as in dumps. decoding skips any whitespace.
+    pub const SPACED: HexConfig = HexConfig::LOWER
+        .with_separators(" \t\r\n")
+        .with_group(" ", 1);
+    /// lowercase bytes each written as `\x`, as in C and Python string escapes
+    pub const ESCAPED: HexConfig = HexConfig::LOWER.with_prefix("\\x").with_group("\\x", 1);
+    /// uppercase with a leading `0x`, which decoding skips if present, along with `0X` or `#`
+    pub const PREFIXED: HexConfig = HexConfig::UPPER.with_prefix("0x");
+
+    /// sets whether encoded output is lowercase
+    pub const fn with_lowercase(mut self, lower: bool) -> Self {
+        self.lower = lower;
+        self
+    }
+
+    /// sets whether decoding fails on an odd number of digits or a char that is not hex (strict),
+    /// or decodes what it can (lenient)
+    pub const fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// sets the prefix written before encoded output, and has decoding skip it, or one of `0x`,
+    /// `0X` or `#`, if present
+    pub const fn with_prefix(mut self, prefix: &'static str) -> Self {
+        self.prefix = prefix;
+        self.strip_prefix = true;
+        self
+    }
+
+    /// sets whether decoding skips a leading `0x`, `0X`, `#` or the config's own prefix
+    pub const fn with_prefix_stripping(mut self, strip: bool) -> Self {
+        self.strip_prefix = strip;
+        self
+    }
+
+    /// sets the separator written between every `group` bytes of encoded output, 0 for none, and
+    /// adds its chars to those skipped by decoding
+    pub const fn with_group(mut self, separator: &'static str, group: usize) -> Self {
+        self.separator = separator;
+        self.group = group;
+        self.separators |= hex_separator_mask(separator);
+        self
+    }
+
+    /// sets the number of bytes per line of encoded output, and the line ending written between
+    /// lines in place of a separator. a `line_len` of 0 turns wrapping off. no line ending follows
+    /// the last line.
+    ///
+    /// when wrapping is on, the chars of the line ending are added to those skipped by decoding.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let cfg = HexConfig::COLON.with_line_wrap(4, "\r\n");
+    /// let s = cfg.encode(b"Many hands");
+    /// assert_eq!(s.as_str(), "4d:61:6e:79\r\n20:68:61:6e\r\n64:73");
+    /// assert_eq!(cfg.decode(&s), Some(b"Many hands".to_vec()));
+    /// ```
+    pub const fn with_line_wrap(mut self, line_len: usize, line_ending: &'static str) -> Self {
+        self.line_len = line_len;
+        self.line_ending = line_ending;
+        if line_len > 0 {
+            self.separators |= hex_separator_mask(line_ending);
+        }
+        self
+    }
+
+    /// sets the ASCII chars that decoding skips between bytes. hex digits and other chars are
+    /// ignored. the chars of the group separator and line ending are kept, so the config still
+    /// decodes its own output whichever order the builders are called in.
+    pub const fn with_separators(mut self, chars: &str) -> Self {
+        self.separators = hex_separator_mask(chars) | self.own_separators();
+        self
+    }
+
+    // chars of the config's own output that decoding skips
+    const fn own_separators(&self) -> u128 {
+        let mut mask = hex_separator_mask(self.separator);
+        if self.line_len > 0 {
+            mask |= hex_separator_mask(self.line_ending);
+        }
+        mask
+    }
+
+    #[inline]
+    pub const fn is_lowercase(&self) -> bool {
+        self.lower
+    }
+
+    #[inline]
+    pub const fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    #[inline]
+    pub const fn prefix(&self) -> &'static str {
+        self.prefix
+    }
+
+    #[inline]
+    pub const fn strips_prefix(&self) -> bool {
+        self.strip_prefix
+    }
+
+    /// the separator and the number of bytes between separators, or None if output is not grouped
+    #[inline]
+    pub const fn group(&self) -> Option<(&'static str, usize)> {
+        if self.group == 0 {
+            None
+        } else {
+            Some((self.separator, self.group))
+        }
+    }
+
+    /// the number of bytes per line and the line ending, or None if output is not wrapped
+    #[inline]
+    pub const fn line_wrap(&self) -> Option<(usize, &'static str)> {
+        if self.line_len == 0 {
+            None
+        } else {
+            Some((self.line_len, self.line_ending))
+        }
+    }
+
+    // what is written before the byte at index, after the first: a line ending, a separator or
+    // nothing
+    #[inline]
+    const fn gap(&self, index: usize) -> &'static str {
+        if index == 0 {
+            ""
+        } else if self.line_len > 0 && index.is_multiple_of(self.line_len) {
+            self.line_ending
+        } else if self.group > 0 && index.is_multiple_of(self.group) {
+            self.separator
+        } else {
+            ""
+        }
+    }
+
+    /// whether decoding skips the char between bytes
+    #[inline]
+    pub const fn is_separator(&self, c: char) -> bool {
+        (c as u32) < 128 && (self.separators >> (c as u32)) & 1 == 1
+    }
+
+    /// exact length of the hex encoding of `byte_len` bytes with this config
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(HexConfig::UPPER.encoded_len(4), 8);
+    /// assert_eq!(HexConfig::COLON.encoded_len(4), 11);
+    /// assert_eq!(HexConfig::ESCAPED.encoded_len(4), 16);
+    /// assert_eq!(HexConfig::COLON.with_line_wrap(2, "\r\n").encoded_len(4), 12);
+    /// ```
+    #[inline]
+    pub const fn encoded_len(&self, byte_len: usize) -> usize {
+        let mut len = self.prefix.len() + byte_len * 2;
+        if byte_len == 0 {
+            return len;
+        }
+        let gaps = byte_len - 1;
+        let lines = match gaps.checked_div(self.line_len) {
+            Some(n) => n,
+            None => 0,
+        };
+        len += lines * self.line_ending.len();
+        if self.group > 0 {
+            // a line ending takes the place of a separator where both fall
+            let both = if self.line_len > 0 {
+                let mut a = self.group;
+                let mut b = self.line_len;
+                while b > 0 {
+                    (a, b) = (b, a % b);
+                }
+                gaps / (self.group / a * self.line_len)
+            } else {
+                0
+            };
+            len += (gaps / self.group - both) * self.separator.len();
+        }
+        len
+    }
+
+    /// converts a slice of bytes into a hex String
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let v = [0xDEu8, 0xAD, 0xBE, 0xEF];
+    /// assert_eq!(HexConfig::PREFIXED.encode(&v), "0xDEADBEEF");
+    /// assert_eq!(HexConfig::COLON.encode(&v), "de:ad:be:ef");
+    /// assert_eq!(HexConfig::SPACED.encode(&v), "de ad be ef");
+    /// assert_eq!(HexConfig::ESCAPED.encode(&v), "\\xde\\xad\\xbe\\xef");
+    /// ```
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        if self.prefix.is_empty() && self.group == 0 && self.line_len == 0 {
+            return if self.lower {
+                u8_array_to_hex_low(bytes)
+            } else {
+                u8_array_to_hex(bytes)
+            };
+        }
+        let mut s = String::with_capacity(self.encoded_len(bytes.len()));
+        s.push_str(self.prefix);
+        for (i, b) in bytes.iter().enumerate() {
+            s.push_str(self.gap(i));
+            s.push_str(if self.lower {
+                u8_to_hex_low_str(b)
+            } else {
+                u8_to_hex_str(b)
+            });
+        }
+        s
+    }
+
+    // data without its prefix, and the length of the prefix
+    fn strip<'a>(&self, data: &'a str) -> (&'a str, usize) {
+        if self.strip_prefix {
+            for p in [self.prefix, "0x", "0X", "#"] {
+                if !p.is_empty()
+                    && let Some(rest) = data.strip_prefix(p)
+                {
+                    return (rest, p.len());
+                }
+            }
+        }
+        (data, 0)
+    }
+
+    /// converts a str of hex into a vec of bytes, or None if it could not be decoded
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(HexConfig::PREFIXED.decode("#ABC"), Some(vec![0x0A, 0xBC]));
+    /// assert_eq!(HexConfig::ESCAPED.decode("\\xde\\xad"), Some(vec![0xDE, 0xAD]));
+    /// ```
+    pub fn decode(&self, data: &str) -> Option<Vec<u8>> {
+        if self.strict || self.separators != 0 {
+            return self.try_decode(data).ok();
+        }
+        hex_str_to_u8_vec(self.strip(data).0)
+    }
+
+    /// converts a str of hex into a vec of bytes, failing with the kind and location of the first
+    /// problem regardless of [`HexConfig::is_strict`]
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let e = HexConfig::SPACED.try_decode("de ad b").unwrap_err();
+    /// assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::OddLength, 7));
+    /// ```
+    pub fn try_decode(&self, data: &str) -> Result<Vec<u8>, HexDecodeError> {
+        if self.separators != 0 {
+            let mut it = FromHexIter::new_with(data.chars(), self);
+            let v = it.by_ref().collect::<Vec<u8>>();
+            return match it.error() {
+                Some(e) => Err(e),
+                None => Ok(v),
+            };
+        }
+        let (rest, skip) = self.strip(data);
+        hex_str_to_u8_vec_strict(rest).map_err(|e| HexDecodeError::new(e.kind, e.index + skip))
+    }
+
+    /// Iterator over a slice of bytes that produces hex chars
+    pub fn iter_encode<'a>(&self, bytes: &'a [u8]) -> ToHexCharIter<'a> {
+        ToHexCharIter::new_with(bytes.iter(), self)
+    }
+
+    /// Iterator over a str of hex that produces bytes, ending at the first problem
+    pub fn iter_decode<'a>(&self, data: &'a str) -> FromHexIter<'a> {
+        FromHexIter::new_with(data.chars(), self)
+    }
+}
+
+impl Default for HexConfig {
+    fn default() -> Self {
+        Self::UPPER
+    }
+}
+
+// size of the internal buffers used by HexWriter and HexReader
+const HEX_IO_BUF_LEN: usize = 1024;
+
+/// hex encoder that writes the encoded chars to any [`Write`] sink as bytes are written to it,
+/// with the case, prefix, separators and line wrapping of its config.
+///
+/// hex needs no final quantum, so everything written is passed on straight away.
+/// [`HexWriter::finish`] flushes the sink and returns it.
+/// ```
+/// # use cj_common::prelude::*;
+/// use std::io::Write;
+/// let mut w = HexWriter::new_with(Vec::new(), &HexConfig::LOWER.with_line_wrap(8, "\n"));
+/// w.write_all(b"Many hands ").unwrap();
+/// w.write_all(b"make light work.").unwrap();
+/// let v = w.finish().unwrap();
+/// assert_eq!(
+///     String::from_utf8(v).unwrap(),
+///     "4d616e792068616e\n6473206d616b6520\n6c6967687420776f\n726b2e"
+/// );
+/// ```
+pub struct HexWriter<W: Write> {
+    inner: W,
+    config: HexConfig,
+    count: usize,
+    out: Vec<u8>,
+}
+
+impl<W: Write> HexWriter<W> {
+    /// uppercase hex
+    pub fn new(inner: W) -> Self {
+        Self::new_with(inner, &HexConfig::UPPER)
+    }
+
+    /// lowercase hex
+    pub fn new_low(inner: W) -> Self {
+        Self::new_with(inner, &HexConfig::LOWER)
+    }
+
+    /// same as [`HexWriter::new`], but encodes using (a copy of) the given config.
+    pub fn new_with(inner: W, config: &HexConfig) -> Self {
+        Self {
+            inner,
+            config: *config,
+            count: 0,
+            out: Vec::new(),
+        }
+    }
+
+    /// flushes and returns the inner writer. if no bytes were written, the config's prefix is
+    /// written first, so the output always matches [`HexConfig::encode`].
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.count == 0 {
+            self.inner.write_all(self.config.prefix.as_bytes())?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    /// the inner writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// the inner writer. writing to it directly may corrupt the hex output.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+}
+
+impl<W: Write> Write for HexWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = buf.len().min(HEX_IO_BUF_LEN);
+        self.out.clear();
+        // the count only moves on once the bytes are written, so a failed write can be retried
+        let mut count = self.count;
+        for b in &buf[..n] {
+            let lead = if count == 0 {
+                self.config.prefix
+            } else {
+                self.config.gap(count)
+            };
+            self.out.extend_from_slice(lead.as_bytes());
+            let hex = if self.config.lower {
+                u8_to_hex_low_str(b)
+            } else {
+                u8_to_hex_str(b)
+            };
+            self.out.extend_from_slice(hex.as_bytes());
+            count += 1;
+        }
+        self.inner.write_all(&self.out)?;
+        self.count = count;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// hex decoder that reads encoded chars from any [`Read`] source and returns the decoded bytes.
+///
+/// a leading prefix is skipped as [`HexConfig::decode`] does, and separators and line endings are
+/// skipped between bytes. as the length of a stream is not known up front, bytes are always read
+/// as pairs of digits, like [`hex_str_to_u8_vec_strict`]. a lenient config ends the stream at the
+/// first problem, keeping the bytes decoded so far, while a strict config fails the read with
+/// [`io::ErrorKind::InvalidData`]. either way [`HexReader::error`] returns the problem.
+/// ```
+/// # use cj_common::prelude::*;
+/// use std::io::Read;
+/// let src = "4d:61:6e:79\r\n20:68:61:6e\r\n64:73".as_bytes();
+/// let mut r = HexReader::new_with(src, &HexConfig::COLON.with_line_wrap(4, "\r\n"));
+/// let mut s = String::new();
+/// r.read_to_string(&mut s).unwrap();
+/// assert_eq!(s.as_str(), "Many hands");
+///
+/// let mut r = HexReader::new("4D61Z".as_bytes());
+/// let mut v = Vec::new();
+/// r.read_to_end(&mut v).unwrap();
+/// assert_eq!(v, b"Ma");
+/// assert_eq!(r.error(), Some(HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 4)));
+/// ```
+pub struct HexReader<R: Read> {
+    inner: R,
+    config: HexConfig,
+    enc: [u8; HEX_IO_BUF_LEN],
+    enc_pos: usize,
+    enc_len: usize,
+    index: usize,
+    high: Option<(u8, usize)>,
+    started: bool,
+    done: bool,
+    error: Option<HexDecodeError>,
+}
+
+impl<R: Read> HexReader<R> {
+    /// reads either case, with no prefix or separators
+    pub fn new(inner: R) -> Self {
+        Self::new_with(inner, &HexConfig::UPPER)
+    }
+
+    /// same as [`HexReader::new`], but decodes using (a copy of) the given config.
+    pub fn new_with(inner: R, config: &HexConfig) -> Self {
+        Self {
+            inner,
+            config: *config,
+            enc: [0; HEX_IO_BUF_LEN],
+            enc_pos: 0,
+            enc_len: 0,
+            index: 0,
+            high: None,
+            started: false,
+            done: false,
+            error: None,
+        }
+    }
+
+    /// the problem that ended decoding, if any. the index is the byte offset in the source.
+    pub fn error(&self) -> Option<HexDecodeError> {
+        self.error
+    }
+
+    /// the inner reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// the inner reader. reading from it directly skips that data.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// returns the inner reader, dropping any encoded chars read from it but not yet decoded
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    // reads more encoded chars after those held, returning false at the end of the source
+    fn fill(&mut self) -> io::Result<bool> {
+        if self.enc_pos == self.enc_len {
+            self.enc_pos = 0;
+            self.enc_len = 0;
+        }
+        loop {
+            match self.inner.read(&mut self.enc[self.enc_len..]) {
+                Ok(n) => {
+                    self.enc_len += n;
+                    return Ok(n > 0);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // skips a prefix at the start of the source, reading enough of it to tell
+    fn skip_prefix(&mut self) -> io::Result<()> {
+        self.started = true;
+        if !self.config.strip_prefix {
+            return Ok(());
+        }
+        let want = self.config.prefix.len().clamp(2, HEX_IO_BUF_LEN);
+        while self.enc_len < want && self.fill()? {}
+        // prefixes are ASCII, so a char cut off at the end of what was read does not matter
+        let head = &self.enc[..self.enc_len];
+        let head = match std::str::from_utf8(head) {
+            Ok(h) => h,
+            Err(e) => std::str::from_utf8(&head[..e.valid_up_to()]).unwrap_or_default(),
+        };
+        let (_, skip) = self.config.strip(head);
+        self.enc_pos = skip;
+        self.index = skip;
+        Ok(())
+    }
+
+    fn fail(&mut self, kind: HexDecodeErrorKind, index: usize) {
+        self.done = true;
+        self.error = Some(HexDecodeError::new(kind, index));
+    }
+}
+
+impl<R: Read> Read for HexReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.started {
+            self.skip_prefix()?;
+        }
+        let mut n = 0;
+        while n < buf.len() && !self.done {
+            if self.enc_pos == self.enc_len && !self.fill()? {
+                self.done = true;
+                if let Some((_, i)) = self.high {
+                    self.fail(HexDecodeErrorKind::OddLength, i);
+                }
+                break;
+            }
+            let c = self.enc[self.enc_pos];
+            let i = self.index;
+            self.enc_pos += 1;
+            self.index += 1;
+            match (self.high, hex_char_to_u8(&(c as char))) {
+                (None, Some(x)) => self.high = Some((x, i)),
+                (Some((h, _)), Some(x)) => {
+                    self.high = None;
+                    buf[n] = (h << 4) | x;
+                    n += 1;
+                }
+                // separators are only skipped between bytes
+                (None, None) if self.config.is_separator(c as char) => {}
+                _ => self.fail(HexDecodeErrorKind::InvalidChar, i),
+            }
+        }
+        if n == 0
+            && self.config.strict
+            && let Some(e) = self.error
+        {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+        }
+        Ok(n)
+    }
+}
+
+/// the kind of problem found while decoding hex
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexDecodeErrorKind {
+    /// a char that is not a hex digit
+    InvalidChar,
+    /// a final lone digit, where bytes need two
+    OddLength,
+    /// more digits than the value being decoded can hold
+    Overflow,
+    /// no digits where at least one is needed
+    Empty,
+    /// zero, read for a type that can not hold it, such as `NonZeroU32`
+    Zero,
+}
+
+/// error returned by strict hex decoding, with the kind of problem and the byte offset in the
+/// input where it was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexDecodeError {
+    pub kind: HexDecodeErrorKind,
+    pub index: usize,
+}
+
+impl HexDecodeError {
+    #[inline]
+    pub const fn new(kind: HexDecodeErrorKind, index: usize) -> Self {
+        Self { kind, index }
+    }
+}
+
+impl std::fmt::Display for HexDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self.kind {
+            HexDecodeErrorKind::InvalidChar => "invalid hex char",
+            HexDecodeErrorKind::OddLength => "odd number of hex digits",
+            HexDecodeErrorKind::Overflow => "too many hex digits",
+            HexDecodeErrorKind::Empty => "no hex digits",
+            HexDecodeErrorKind::Zero => "zero value",
+        };
+        write!(f, "{} at byte {}", msg, self.index)
+    }
+}
+
+impl std::error::Error for HexDecodeError {}
+
+/// the layout of the lines of a hex dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexDumpStyle {
+    /// `xxd`: the offset and a colon, groups of bytes, then the ASCII gutter after two spaces
+    Xxd,
+    /// `hexdump -C`: the offset, spaced bytes, then the ASCII gutter between bars, and a last
+    /// line with the offset of the end
+    Canonical,
+}
+
+///
+/// settings for a hex dump: the style, bytes per line, bytes per group, case, ASCII gutter and
+/// offset of the first byte.
+///
+/// groups are separated by a space, and in the [`HexDumpStyle::Canonical`] style the bytes
+/// within a group are too. the gutter shows printable ASCII as is, and other bytes as `.`.
+/// offsets are always lowercase, as in `xxd -u`.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// let data = b"Many hands make light work.";
+/// assert_eq!(
+///     HexDumpConfig::XXD.dump(data).to_string(),
+///     "00000000: 4d61 6e79 2068 616e 6473 206d 616b 6520  Many hands make \n\
+///      00000010: 6c69 6768 7420 776f 726b 2e              light work.\n"
+/// );
+/// assert_eq!(
+///     HexDumpConfig::CANONICAL.dump(data).to_string(),
+///     "00000000  4d 61 6e 79 20 68 61 6e  64 73 20 6d 61 6b 65 20  |Many hands make |\n\
+///      00000010  6c 69 67 68 74 20 77 6f  72 6b 2e                 |light work.|\n\
+///      0000001b\n"
+/// );
+///
+/// let cfg = HexDumpConfig::XXD
+///     .with_width(8)
+///     .with_group(4)
+///     .with_lowercase(false)
+///     .with_ascii(false)
+///     .with_offset(0x100);
+/// assert_eq!(
+///     cfg.dump(&data[..12]).to_string(),
+///     "00000100: 4D616E79 2068616E\n00000108: 6473206D\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexDumpConfig {
+    style: HexDumpStyle,
+    width: usize,
+    group: usize,
+    lower: bool,
+    ascii: bool,
+    offset: usize,
+}
+
+impl HexDumpConfig {
+    /// `xxd`: 16 lowercase bytes per line in groups of 2, with the ASCII gutter
+    pub const XXD: HexDumpConfig = HexDumpConfig {
+        style: HexDumpStyle::Xxd,
+        width: 16,
+        group: 2,
+        lower: true,
+        ascii: true,
+        offset: 0,
+    };
+    /// `hexdump -C`: 16 lowercase bytes per line in groups of 8, with the ASCII gutter
+    pub const CANONICAL: HexDumpConfig = HexDumpConfig {
+        style: HexDumpStyle::Canonical,
+        width: 16,
+        group: 8,
+        lower: true,
+        ascii: true,
+        offset: 0,
+    };
+
+    /// sets the layout of each line
+    pub const fn with_style(mut self, style: HexDumpStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// sets the number of bytes per line, at least 1
+    pub const fn with_width(mut self, width: usize) -> Self {
+        self.width = if width == 0 { 1 } else { width };
+        self
+    }
+
+    /// sets the number of bytes per group, 0 for no grouping
+    pub const fn with_group(mut self, group: usize) -> Self {
+        self.group = group;
+        self
+    }
+
+    /// sets whether bytes are written in lowercase hex
+    pub const fn with_lowercase(mut self, lower: bool) -> Self {
+        self.lower = lower;
+        self
+    }
+
+    /// sets whether each line ends with the ASCII gutter
+    pub const fn with_ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// sets the offset shown for the first byte, as in `xxd -o`
+    pub const fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    #[inline]
+    pub const fn style(&self) -> HexDumpStyle {
+        self.style
+    }
+
+    #[inline]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub const fn group(&self) -> usize {
+        self.group
+    }
+
+    #[inline]
+    pub const fn is_lowercase(&self) -> bool {
+        self.lower
+    }
+
+    #[inline]
+    pub const fn has_ascii(&self) -> bool {
+        self.ascii
+    }
+
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// a hex dump of a slice of bytes, written when displayed
+    pub const fn dump<'a>(&self, bytes: &'a [u8]) -> HexDump<'a> {
+        HexDump::new_with(bytes, self)
+    }
+
+    // writes one line of the dump, for the bytes starting at offset
+    fn write_line(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        offset: usize,
+        line: &[u8],
+    ) -> std::fmt::Result {
+        let canonical = self.style == HexDumpStyle::Canonical;
+        let mut s = String::with_capacity(self.width * 5 + 16);
+        s.push_str(&format!("{:08x}", offset));
+        s.push_str(if canonical { "  " } else { ": " });
+        let mut spaced = false;
+        for i in 0..self.width {
+            match line.get(i) {
+                Some(b) if self.lower => s.push_str(u8_to_hex_low_str(b)),
+                Some(b) => s.push_str(u8_to_hex_str(b)),
+                None => s.push_str("  "),
+            }
+            if canonical {
+                s.push(' ');
+            }
+            spaced = self.group > 0 && (i + 1).is_multiple_of(self.group);
+            if spaced {
+                s.push(' ');
+            }
+        }
+        if self.ascii {
+            if !spaced {
+                s.push(' ');
+            }
+            let gutter = line.iter().map(|&b| {
+                if (0x20..0x7F).contains(&b) {
+                    b as char
+                } else {
+                    '.'
+                }
+            });
+            if canonical {
+                s.push('|');
+                s.extend(gutter);
+                s.push('|');
+            } else {
+                s.push(' ');
+                s.extend(gutter);
+            }
+        } else {
+            s.truncate(s.trim_end().len());
+        }
+        writeln!(f, "{}", s)
+    }
+}
+
+impl Default for HexDumpConfig {
+    fn default() -> Self {
+        Self::XXD
+    }
+}
+
+///
+/// a hex dump of a slice of bytes, one line per [`HexDumpConfig::width`] bytes, each ending in a
+/// newline. it is written by [`std::fmt::Display`], so it can go straight into `format!`, `println!`
+/// or a log.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// let packet = [0x45u8, 0x00, 0x00, 0x1C, 0x7F, 0x41];
+/// let s = format!("rx:\n{}", packet.hex_dump());
+/// assert_eq!(s.as_str(), "rx:\n00000000: 4500 001c 7f41                           E....A\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexDump<'a> {
+    bytes: &'a [u8],
+    config: HexDumpConfig,
+}
+
+impl<'a> HexDump<'a> {
+    /// dump in the style of `xxd`
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        Self::new_with(bytes, &HexDumpConfig::XXD)
+    }
+
+    /// dump with the given settings
+    pub const fn new_with(bytes: &'a [u8], config: &HexDumpConfig) -> Self {
+        Self {
+            bytes,
+            config: *config,
+        }
+    }
+}
+
+impl std::fmt::Display for HexDump<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cfg = &self.config;
+        let mut offset = cfg.offset;
+        for line in self.bytes.chunks(cfg.width) {
+            cfg.write_line(f, offset, line)?;
+            offset += line.len();
+        }
+        if cfg.style == HexDumpStyle::Canonical && !self.bytes.is_empty() {
+            writeln!(f, "{:08x}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+pub trait CjToHexDump {
+    fn hex_dump(&self) -> HexDump<'_>;
+    fn hex_dump_with(&self, config: &HexDumpConfig) -> HexDump<'_>;
+}
+
+impl<T: AsRef<[u8]> + ?Sized> CjToHexDump for T {
+    /// hex dump in the style of `xxd`
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let s = "Hi!".hex_dump().to_string();
+    /// assert_eq!(s.as_str(), "00000000: 4869 21                                  Hi!\n");
+    /// ```
+    fn hex_dump(&self) -> HexDump<'_> {
+        HexDump::new(self.as_ref())
+    }
+
+    /// hex dump with the given settings
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let s = b"Hi!".hex_dump_with(&HexDumpConfig::CANONICAL).to_string();
+    /// assert_eq!(
+    ///     s.as_str(),
+    ///     "00000000  48 69 21                                          |Hi!|\n00000003\n"
+    /// );
+    /// ```
+    fn hex_dump_with(&self, config: &HexDumpConfig) -> HexDump<'_> {
+        HexDump::new_with(self.as_ref(), config)
     }
-    let crs = hexstr.chars();
-    for c in crs {
-        match ct {
-            0 => {
-                ca[0] = c;
-                ct = 1
-            }
-            1 => {
-                ca[1] = c;
-                ct = 0;
+}
 
-                if let Some(b) = hex_chars_to_u8(&ca) {
-                    v.push(b);
-                } else {
-                    return None;
+///
+/// reads the bytes back from a hex dump in the style of `xxd` or `hexdump -C`, of any width and
+/// grouping, and in either case. the offset column must be hex, but the bytes of each line are
+/// appended in order rather than placed at their offsets, so squeezed lines (`*`) are not read.
+///
+/// in `xxd` lines the bytes end at two spaces, and in `hexdump -C` lines at the `|` of the
+/// gutter. a last line of just an offset, as `hexdump -C` ends with, is skipped. lines of only
+/// hex digits before any line with an offset are read as plain hex, as `xxd -p` writes it, but
+/// among lines with offsets they are an error. error indices are byte offsets in the whole dump.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// let dump = "00000000: 4d61 6e79 2068 616e 6473 206d 616b 6520  Many hands make \n\
+///             00000010: 6c69 6768 7420 776f 726b 2e              light work.\n";
+/// assert_eq!(hex_dump_to_bytes(dump), Ok(b"Many hands make light work.".to_vec()));
+///
+/// let dump = "00000000  48 69 21                                          |Hi!|\n00000003\n";
+/// assert_eq!(hex_dump_to_bytes(dump), Ok(b"Hi!".to_vec()));
+///
+/// assert_eq!(hex_dump_to_bytes("4869210a\n"), Ok(b"Hi!\n".to_vec()));
+///
+/// let e = hex_dump_to_bytes("00000000: 4869 2x  Hi!\n").unwrap_err();
+/// assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 16));
+/// ```
+pub fn hex_dump_to_bytes(dump: &str) -> Result<Vec<u8>, HexDecodeError> {
+    let mut v = Vec::with_capacity(dump.len() / 4);
+    let mut base = 0;
+    // digits in the offset column of the last line that had one, or 0 before any
+    let mut offset_len = 0;
+    for line in dump.split_inclusive('\n') {
+        let l = line.trim_end_matches(['\n', '\r']);
+        if l.is_empty() {
+            // blank lines are skipped
+        } else if !l.bytes().all(|c| c.is_ascii_hexdigit()) {
+            offset_len = hex_dump_line_to_bytes(l, base, &mut v)?;
+        } else if offset_len == 0 {
+            let bytes = hex_str_to_u8_vec_strict(l)
+                .map_err(|e| HexDecodeError::new(e.kind, base + e.index))?;
+            v.extend_from_slice(&bytes);
+        } else if l.len() > offset_len.max(8) || !dump[base + line.len()..].trim().is_empty() {
+            // among lines with offsets, only the closing offset of hexdump -C stands alone
+            return Err(HexDecodeError::new(
+                HexDecodeErrorKind::InvalidChar,
+                base + l.len(),
+            ));
+        }
+        base += line.len();
+    }
+    Ok(v)
+}
+
+// appends the bytes of one line of a dump, which starts at base in the whole dump and is not
+// only hex digits, returning the digits in its offset column
+fn hex_dump_line_to_bytes(
+    line: &str,
+    base: usize,
+    v: &mut Vec<u8>,
+) -> Result<usize, HexDecodeError> {
+    let b = line.as_bytes();
+    let err = |kind, i| Err(HexDecodeError::new(kind, base + i));
+    let mut i = 0;
+    while b[i].is_ascii_hexdigit() {
+        i += 1;
+    }
+    let offset_len = i;
+    let xxd = match b[i] {
+        b':' if i > 0 => {
+            i += 1;
+            true
+        }
+        b' ' if i > 0 => false,
+        _ => return err(HexDecodeErrorKind::InvalidChar, i),
+    };
+    if xxd && i < b.len() && b[i] == b' ' {
+        i += 1;
+    }
+    loop {
+        // spaces separate bytes, and in xxd lines two of them end the bytes
+        if xxd {
+            if i < b.len() && b[i] == b' ' {
+                if i + 1 >= b.len() || b[i + 1] == b' ' {
+                    return Ok(offset_len);
                 }
+                i += 1;
+            }
+        } else {
+            while i < b.len() && b[i] == b' ' {
+                i += 1;
             }
-            _ => {}
         }
+        if i == b.len() || (!xxd && b[i] == b'|') {
+            return Ok(offset_len);
+        }
+        let hi = match hex_char_to_u8(&(b[i] as char)) {
+            Some(x) => x,
+            _ => return err(HexDecodeErrorKind::InvalidChar, i),
+        };
+        let lo = match b.get(i + 1) {
+            None | Some(b' ') | Some(b'|') => return err(HexDecodeErrorKind::OddLength, i),
+            Some(&c) => match hex_char_to_u8(&(c as char)) {
+                Some(x) => x,
+                _ => return err(HexDecodeErrorKind::InvalidChar, i + 1),
+            },
+        };
+        v.push((hi << 4) | lo);
+        i += 2;
     }
-
-    Some(v)
 }
 
 /// i16 to big endian hex
@@ -489,12 +1865,7 @@ pub fn hex_str_to_u8_vec(hexstr: &str) -> Option<Vec<u8>> {
 /// ```
 #[inline]
 pub fn i16be_to_hex(i: i16) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be()
 }
 /// i16 to big endian lowercase hex
 /// ```
@@ -504,12 +1875,7 @@ pub fn i16be_to_hex(i: i16) -> String {
 /// ```
 #[inline]
 pub fn i16be_to_hex_low(i: i16) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be_low()
 }
 
 /// converts up to first 4 chars of big endian hex to i16
@@ -521,24 +1887,7 @@ pub fn i16be_to_hex_low(i: i16) -> String {
 /// ```
 #[inline]
 pub fn i16_from_hex_be(value: &str) -> Option<i16> {
-    let mut r: i16 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(4) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as i16,
-                1..=3 => {
-                    r <<= 4;
-                    r += i as i16;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found { Some(r) } else { None }
+    i16::from_hex_be(value)
 }
 
 /// i16 to little endian hex
@@ -549,12 +1898,7 @@ pub fn i16_from_hex_be(value: &str) -> Option<i16> {
 /// ```
 #[inline]
 pub fn i16le_to_hex(i: i16) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le()
 }
 /// i16 to little endian lowercase hex
 /// ```
@@ -564,12 +1908,7 @@ pub fn i16le_to_hex(i: i16) -> String {
 /// ```
 #[inline]
 pub fn i16le_to_hex_low(i: i16) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le_low()
 }
 
 /// converts up to first 4 chars of little endian hex to i16
@@ -581,28 +1920,7 @@ pub fn i16le_to_hex_low(i: i16) -> String {
 /// ```
 #[inline]
 pub fn i16_from_hex_le(value: &str) -> Option<i16> {
-    let mut r: i16 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(4) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as i16,
-                1..=3 => {
-                    r <<= 4;
-                    r += i as i16;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found {
-        Some(i16::from_le_bytes(r.to_be_bytes()))
-    } else {
-        None
-    }
+    i16::from_hex_le(value)
 }
 
 ///
@@ -610,12 +1928,7 @@ pub fn i16_from_hex_le(value: &str) -> Option<i16> {
 ///
 #[inline]
 pub fn i16ne_to_hex(i: i16) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne()
 }
 
 ///
@@ -623,12 +1936,7 @@ pub fn i16ne_to_hex(i: i16) -> String {
 ///
 #[inline]
 pub fn i16ne_to_hex_low(i: i16) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne_low()
 }
 
 /// u16 to big endian hex
@@ -639,12 +1947,7 @@ pub fn i16ne_to_hex_low(i: i16) -> String {
 /// ```
 #[inline]
 pub fn u16be_to_hex(i: u16) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be()
 }
 /// u16 to big endian lowercase hex
 /// ```
@@ -654,12 +1957,7 @@ pub fn u16be_to_hex(i: u16) -> String {
 /// ```
 #[inline]
 pub fn u16be_to_hex_low(i: u16) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be_low()
 }
 
 /// converts up to first 4 chars of big endian hex to u16
@@ -671,24 +1969,7 @@ pub fn u16be_to_hex_low(i: u16) -> String {
 /// ```
 #[inline]
 pub fn u16_from_hex_be(value: &str) -> Option<u16> {
-    let mut r: u16 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(4) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as u16,
-                1..=3 => {
-                    r <<= 4;
-                    r += i as u16;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found { Some(r) } else { None }
+    u16::from_hex_be(value)
 }
 
 /// u16 to little endian hex
@@ -699,12 +1980,7 @@ pub fn u16_from_hex_be(value: &str) -> Option<u16> {
 /// ```
 #[inline]
 pub fn u16le_to_hex(i: u16) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le()
 }
 /// u16 to little endian lowercase hex
 /// ```
@@ -714,12 +1990,7 @@ pub fn u16le_to_hex(i: u16) -> String {
 /// ```
 #[inline]
 pub fn u16le_to_hex_low(i: u16) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le_low()
 }
 
 /// converts up to first 4 chars of little endian hex to u16
@@ -731,28 +2002,7 @@ pub fn u16le_to_hex_low(i: u16) -> String {
 /// ```
 #[inline]
 pub fn u16_from_hex_le(value: &str) -> Option<u16> {
-    let mut r: u16 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(4) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as u16,
-                1..=3 => {
-                    r <<= 4;
-                    r += i as u16;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found {
-        Some(u16::from_le_bytes(r.to_be_bytes()))
-    } else {
-        None
-    }
+    u16::from_hex_le(value)
 }
 
 ///
@@ -760,24 +2010,14 @@ pub fn u16_from_hex_le(value: &str) -> Option<u16> {
 ///
 #[inline]
 pub fn u16ne_to_hex(i: u16) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne()
 }
 ///
 /// u16 to native endian lowercase hex
 ///
 #[inline]
 pub fn u16ne_to_hex_low(i: u16) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne_low()
 }
 
 /// i32 to big endian hex
@@ -788,12 +2028,7 @@ pub fn u16ne_to_hex_low(i: u16) -> String {
 /// ```
 #[inline]
 pub fn i32be_to_hex(i: i32) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be()
 }
 /// i32 to big endian lowercase hex
 /// ```
@@ -803,12 +2038,7 @@ pub fn i32be_to_hex(i: i32) -> String {
 /// ```
 #[inline]
 pub fn i32be_to_hex_low(i: i32) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be_low()
 }
 
 /// converts up to first 8 chars of big endian hex to i32
@@ -820,24 +2050,7 @@ pub fn i32be_to_hex_low(i: i32) -> String {
 /// ```
 #[inline]
 pub fn i32_from_hex_be(value: &str) -> Option<i32> {
-    let mut r: i32 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(8) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as i32,
-                1..=7 => {
-                    r <<= 4;
-                    r += i as i32;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found { Some(r) } else { None }
+    i32::from_hex_be(value)
 }
 
 /// i32 to little endian hex
@@ -848,12 +2061,7 @@ pub fn i32_from_hex_be(value: &str) -> Option<i32> {
 /// ```
 #[inline]
 pub fn i32le_to_hex(i: i32) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le()
 }
 /// i32 to little endian lowercase hex
 /// ```
@@ -863,12 +2071,7 @@ pub fn i32le_to_hex(i: i32) -> String {
 /// ```
 #[inline]
 pub fn i32le_to_hex_low(i: i32) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le_low()
 }
 
 /// converts up to first 8 chars of little endian hex to i32
@@ -880,28 +2083,7 @@ pub fn i32le_to_hex_low(i: i32) -> String {
 /// ```
 #[inline]
 pub fn i32_from_hex_le(value: &str) -> Option<i32> {
-    let mut r: i32 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(8) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as i32,
-                1..=7 => {
-                    r <<= 4;
-                    r += i as i32;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found {
-        Some(i32::from_le_bytes(r.to_be_bytes()))
-    } else {
-        None
-    }
+    i32::from_hex_le(value)
 }
 
 ///
@@ -909,24 +2091,14 @@ pub fn i32_from_hex_le(value: &str) -> Option<i32> {
 ///
 #[inline]
 pub fn i32ne_to_hex(i: i32) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne()
 }
 ///
 /// i32 to native endian lowercase hex
 ///
 #[inline]
 pub fn i32ne_to_hex_low(i: i32) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne_low()
 }
 
 /// u32 to big endian hex
@@ -937,12 +2109,7 @@ pub fn i32ne_to_hex_low(i: i32) -> String {
 /// ```
 #[inline]
 pub fn u32be_to_hex(i: u32) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be()
 }
 /// u32 to big endian lowercase hex
 /// ```
@@ -952,12 +2119,7 @@ pub fn u32be_to_hex(i: u32) -> String {
 /// ```
 #[inline]
 pub fn u32be_to_hex_low(i: u32) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be_low()
 }
 
 /// converts up to first 8 chars of big endian hex to u32
@@ -969,24 +2131,7 @@ pub fn u32be_to_hex_low(i: u32) -> String {
 /// ```
 #[inline]
 pub fn u32_from_hex_be(value: &str) -> Option<u32> {
-    let mut r: u32 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(8) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as u32,
-                1..=7 => {
-                    r <<= 4;
-                    r += i as u32;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found { Some(r) } else { None }
+    u32::from_hex_be(value)
 }
 
 /// u32 to little endian hex
@@ -997,12 +2142,7 @@ pub fn u32_from_hex_be(value: &str) -> Option<u32> {
 /// ```
 #[inline]
 pub fn u32le_to_hex(i: u32) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le()
 }
 /// u32 to little endian lowercase hex
 /// ```
@@ -1012,12 +2152,7 @@ pub fn u32le_to_hex(i: u32) -> String {
 /// ```
 #[inline]
 pub fn u32le_to_hex_low(i: u32) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le_low()
 }
 
 /// converts up to first 8 chars of little endian hex to u32
@@ -1029,28 +2164,7 @@ pub fn u32le_to_hex_low(i: u32) -> String {
 /// ```
 #[inline]
 pub fn u32_from_hex_le(value: &str) -> Option<u32> {
-    let mut r: u32 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(8) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as u32,
-                1..=7 => {
-                    r <<= 4;
-                    r += i as u32;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found {
-        Some(u32::from_le_bytes(r.to_be_bytes()))
-    } else {
-        None
-    }
+    u32::from_hex_le(value)
 }
 
 ///
@@ -1058,24 +2172,14 @@ pub fn u32_from_hex_le(value: &str) -> Option<u32> {
 ///
 #[inline]
 pub fn u32ne_to_hex(i: u32) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne()
 }
 ///
 /// u32 to native endian lowercase hex
 ///
 #[inline]
 pub fn u32ne_to_hex_low(i: u32) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne_low()
 }
 
 /// i64 to big endian hex
@@ -1086,12 +2190,7 @@ pub fn u32ne_to_hex_low(i: u32) -> String {
 /// ```
 #[inline]
 pub fn i64be_to_hex(i: i64) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be()
 }
 /// i64 to big endian lowercase hex
 /// ```
@@ -1101,12 +2200,7 @@ pub fn i64be_to_hex(i: i64) -> String {
 /// ```
 #[inline]
 pub fn i64be_to_hex_low(i: i64) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be_low()
 }
 
 /// converts up to first 16 chars of big endian hex to i64
@@ -1118,24 +2212,7 @@ pub fn i64be_to_hex_low(i: i64) -> String {
 /// ```
 #[inline]
 pub fn i64_from_hex_be(value: &str) -> Option<i64> {
-    let mut r: i64 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(16) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as i64,
-                1..=15 => {
-                    r <<= 4;
-                    r += i as i64;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found { Some(r) } else { None }
+    i64::from_hex_be(value)
 }
 
 /// i64 to little endian hex
@@ -1146,12 +2223,7 @@ pub fn i64_from_hex_be(value: &str) -> Option<i64> {
 /// ```
 #[inline]
 pub fn i64le_to_hex(i: i64) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le()
 }
 /// i64 to little endian lowercase hex
 /// ```
@@ -1161,12 +2233,7 @@ pub fn i64le_to_hex(i: i64) -> String {
 /// ```
 #[inline]
 pub fn i64le_to_hex_low(i: i64) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le_low()
 }
 
 /// converts up to first 16 chars of little endian hex to i64
@@ -1178,28 +2245,7 @@ pub fn i64le_to_hex_low(i: i64) -> String {
 /// ```
 #[inline]
 pub fn i64_from_hex_le(value: &str) -> Option<i64> {
-    let mut r: i64 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(16) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as i64,
-                1..=15 => {
-                    r <<= 4;
-                    r += i as i64;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found {
-        Some(i64::from_le_bytes(r.to_be_bytes()))
-    } else {
-        None
-    }
+    i64::from_hex_le(value)
 }
 
 ///
@@ -1207,24 +2253,14 @@ pub fn i64_from_hex_le(value: &str) -> Option<i64> {
 ///
 #[inline]
 pub fn i64ne_to_hex(i: i64) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne()
 }
 ///
 /// i64 to native endian lowercase hex
 ///
 #[inline]
 pub fn i64ne_to_hex_low(i: i64) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne_low()
 }
 
 /// u64 to big endian hex
@@ -1235,12 +2271,7 @@ pub fn i64ne_to_hex_low(i: i64) -> String {
 /// ```
 #[inline]
 pub fn u64be_to_hex(i: u64) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be()
 }
 /// u64 to big endian lowercase hex
 /// ```
@@ -1250,12 +2281,7 @@ pub fn u64be_to_hex(i: u64) -> String {
 /// ```
 #[inline]
 pub fn u64be_to_hex_low(i: u64) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be_low()
 }
 
 /// converts up to first 16 chars of big endian hex to u64
@@ -1267,24 +2293,7 @@ pub fn u64be_to_hex_low(i: u64) -> String {
 /// ```
 #[inline]
 pub fn u64_from_hex_be(value: &str) -> Option<u64> {
-    let mut r: u64 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(16) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as u64,
-                1..=15 => {
-                    r <<= 4;
-                    r += i as u64;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found { Some(r) } else { None }
+    u64::from_hex_be(value)
 }
 
 /// u64 to little endian hex
@@ -1295,12 +2304,7 @@ pub fn u64_from_hex_be(value: &str) -> Option<u64> {
 /// ```
 #[inline]
 pub fn u64le_to_hex(i: u64) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le()
 }
 /// u64 to little endian lowercase hex
 /// ```
@@ -1310,12 +2314,7 @@ pub fn u64le_to_hex(i: u64) -> String {
 /// ```
 #[inline]
 pub fn u64le_to_hex_low(i: u64) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le_low()
 }
 
 /// converts up to first 16 chars of little endian hex to u64
@@ -1327,28 +2326,7 @@ pub fn u64le_to_hex_low(i: u64) -> String {
 /// ```
 #[inline]
 pub fn u64_from_hex_le(value: &str) -> Option<u64> {
-    let mut r: u64 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(16) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as u64,
-                1..=15 => {
-                    r <<= 4;
-                    r += i as u64;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found {
-        Some(u64::from_le_bytes(r.to_be_bytes()))
-    } else {
-        None
-    }
+    u64::from_hex_le(value)
 }
 
 ///
@@ -1356,24 +2334,14 @@ pub fn u64_from_hex_le(value: &str) -> Option<u64> {
 ///
 #[inline]
 pub fn u64ne_to_hex(i: u64) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne()
 }
 ///
 /// u64 to native endian lowercase hex
 ///
 #[inline]
 pub fn u64ne_to_hex_low(i: u64) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne_low()
 }
 
 /// i128 to big endian hex
@@ -1384,12 +2352,7 @@ pub fn u64ne_to_hex_low(i: u64) -> String {
 /// ```
 #[inline]
 pub fn i128be_to_hex(i: i128) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be()
 }
 /// i128 to big endian lowercase hex
 /// ```
@@ -1399,12 +2362,7 @@ pub fn i128be_to_hex(i: i128) -> String {
 /// ```
 #[inline]
 pub fn i128be_to_hex_low(i: i128) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be_low()
 }
 
 /// converts up to first 32 chars of big endian hex to i128
@@ -1416,24 +2374,7 @@ pub fn i128be_to_hex_low(i: i128) -> String {
 /// ```
 #[inline]
 pub fn i128_from_hex_be(value: &str) -> Option<i128> {
-    let mut r: i128 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(32) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as i128,
-                1..=31 => {
-                    r <<= 4;
-                    r += i as i128;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found { Some(r) } else { None }
+    i128::from_hex_be(value)
 }
 
 /// i128 to little endian hex
@@ -1444,12 +2385,7 @@ pub fn i128_from_hex_be(value: &str) -> Option<i128> {
 /// ```
 #[inline]
 pub fn i128le_to_hex(i: i128) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le()
 }
 /// i128 to little endian lowercase hex
 /// ```
@@ -1459,12 +2395,7 @@ pub fn i128le_to_hex(i: i128) -> String {
 /// ```
 #[inline]
 pub fn i128le_to_hex_low(i: i128) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le_low()
 }
 
 /// converts up to first 32 chars of little endian hex to i128
@@ -1476,28 +2407,7 @@ pub fn i128le_to_hex_low(i: i128) -> String {
 /// ```
 #[inline]
 pub fn i128_from_hex_le(value: &str) -> Option<i128> {
-    let mut r: i128 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(32) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as i128,
-                1..=31 => {
-                    r <<= 4;
-                    r += i as i128;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found {
-        Some(i128::from_le_bytes(r.to_be_bytes()))
-    } else {
-        None
-    }
+    i128::from_hex_le(value)
 }
 
 ///
@@ -1505,24 +2415,14 @@ pub fn i128_from_hex_le(value: &str) -> Option<i128> {
 ///
 #[inline]
 pub fn i128ne_to_hex(i: i128) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne()
 }
 ///
 /// u64 to native endian lowercase hex
 ///
 #[inline]
 pub fn i128ne_to_hex_low(i: i128) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne_low()
 }
 
 /// u128 to big endian hex
@@ -1533,12 +2433,7 @@ pub fn i128ne_to_hex_low(i: i128) -> String {
 /// ```
 #[inline]
 pub fn u128be_to_hex(i: u128) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be()
 }
 /// u128 to big endian lowercase hex
 /// ```
@@ -1548,12 +2443,7 @@ pub fn u128be_to_hex(i: u128) -> String {
 /// ```
 #[inline]
 pub fn u128be_to_hex_low(i: u128) -> String {
-    let r = i
-        .to_be_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_be_low()
 }
 
 /// converts up to first 32 chars of big endian hex to u128
@@ -1565,24 +2455,7 @@ pub fn u128be_to_hex_low(i: u128) -> String {
 /// ```
 #[inline]
 pub fn u128_from_hex_be(value: &str) -> Option<u128> {
-    let mut r: u128 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(32) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as u128,
-                1..=31 => {
-                    r <<= 4;
-                    r += i as u128;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found { Some(r) } else { None }
+    u128::from_hex_be(value)
 }
 
 /// u128 to little endian hex
@@ -1593,12 +2466,7 @@ pub fn u128_from_hex_be(value: &str) -> Option<u128> {
 /// ```
 #[inline]
 pub fn u128le_to_hex(i: u128) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le()
 }
 /// u128 to little endian lowercase hex
 /// ```
@@ -1608,12 +2476,7 @@ pub fn u128le_to_hex(i: u128) -> String {
 /// ```
 #[inline]
 pub fn u128le_to_hex_low(i: u128) -> String {
-    let r = i
-        .to_le_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_le_low()
 }
 
 /// converts up to first 32 chars of little endian hex to u128
@@ -1625,28 +2488,7 @@ pub fn u128le_to_hex_low(i: u128) -> String {
 /// ```
 #[inline]
 pub fn u128_from_hex_le(value: &str) -> Option<u128> {
-    let mut r: u128 = 0;
-    let mut p: usize = 0;
-    let mut found = false;
-    for c in value.chars().take(32) {
-        if let Some(i) = hex_char_to_u8(&c) {
-            found = true;
-            match p {
-                0 => r = i as u128,
-                1..=31 => {
-                    r <<= 4;
-                    r += i as u128;
-                }
-                _ => break,
-            }
-            p += 1;
-        }
-    }
-    if found {
-        Some(u128::from_le_bytes(r.to_be_bytes()))
-    } else {
-        None
-    }
+    u128::from_hex_le(value)
 }
 
 ///
@@ -1654,253 +2496,541 @@ pub fn u128_from_hex_le(value: &str) -> Option<u128> {
 ///
 #[inline]
 pub fn u128ne_to_hex(i: u128) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE[*f as usize])
-        .collect::<String>();
-    r
+    i.to_hex_ne()
 }
 ///
 /// u64 to native endian lowercase hex
 ///
 #[inline]
 pub fn u128ne_to_hex_low(i: u128) -> String {
-    let r = i
-        .to_ne_bytes()
-        .iter()
-        .map(|f| HEX_TABLE_LOWER[*f as usize])
-        .collect::<String>();
-    r
-}
-
-pub trait Hex {
-    /// to big endian hex
-    fn to_hex_be(self) -> String;
-    /// to big endian lowercase hex
-    fn to_hex_be_low(self) -> String;
-    /// from big endian hex
-    fn from_hex_be(value: &str) -> Option<Self>
-    where
-        Self: Sized;
-    /// to little endian hex
-    fn to_hex_le(self) -> String;
-    /// to little endian lowercase hex
-    fn to_hex_le_low(self) -> String;
-    /// from little endian hex
-    fn from_hex_le(value: &str) -> Option<Self>
-    where
-        Self: Sized;
+    i.to_hex_ne_low()
 }
 
-impl Hex for i16 {
-    fn to_hex_be(self) -> String {
-        i16be_to_hex(self)
-    }
-
-    fn to_hex_be_low(self) -> String {
-        i16be_to_hex_low(self)
-    }
-
-    fn from_hex_be(value: &str) -> Option<Self> {
-        i16_from_hex_be(value)
-    }
-
-    fn to_hex_le(self) -> String {
-        i16le_to_hex(self)
-    }
-
-    fn to_hex_le_low(self) -> String {
-        i16le_to_hex_low(self)
-    }
+///
+/// how [`Hex::to_hex_fmt`] writes a number, and what [`Hex::from_hex_fmt`] reads: the digits in
+/// either full width or without leading zeros, an optional `0x` prefix, an optional sign, and
+/// digits grouped from the right with a separator.
+///
+/// without the sign, negative values are written as their two's complement bits. floats are always
+/// written as their unsigned IEEE 754 bits.
+///
+/// parsing reads either case, the prefix if present when the format has one, a `-` when the
+/// format is signed, and separators between digits when the format groups them. any number of
+/// digits is read, as long as the value fits.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// assert_eq!((-31i16).to_hex_fmt(&HexFormat::FULL), "FFE1");
+/// assert_eq!(0x1Fu32.to_hex_fmt(&HexFormat::MINIMAL), "1F");
+/// assert_eq!(0x1Fu32.to_hex_fmt(&HexFormat::PREFIXED), "0x1F");
+/// assert_eq!((-31i16).to_hex_fmt(&HexFormat::SIGNED), "-0x1F");
+/// assert_eq!(0xDEADBEEFu32.to_hex_fmt(&HexFormat::GROUPED), "DEAD_BEEF");
+///
+/// let fmt = HexFormat::SIGNED.with_lowercase(true).with_group(4);
+/// assert_eq!((-0x12345i64).to_hex_fmt(&fmt), "-0x1_2345");
+/// assert_eq!(i64::from_hex_fmt("-0x1_2345", &fmt), Some(-0x12345));
+///
+/// assert_eq!(i16::from_hex_fmt("FFE1", &HexFormat::FULL), Some(-31));
+/// assert_eq!(i16::from_hex_fmt("-0x1f", &HexFormat::SIGNED), Some(-31));
+/// assert_eq!(i16::from_hex_fmt("0x8000", &HexFormat::SIGNED), None);
+/// assert_eq!(u8::from_hex_fmt("100", &HexFormat::MINIMAL), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexFormat {
+    lower: bool,
+    minimal: bool,
+    prefix: bool,
+    signed: bool,
+    group: usize,
+    separator: char,
+}
+
+impl HexFormat {
+    /// every digit of the type, uppercase, as [`Hex::to_hex_be`]
+    pub const FULL: HexFormat = HexFormat {
+        lower: false,
+        minimal: false,
+        prefix: false,
+        signed: false,
+        group: 0,
+        separator: '_',
+    };
+    /// no leading zeros, as `1F`
+    pub const MINIMAL: HexFormat = HexFormat::FULL.with_minimal(true);
+    /// no leading zeros after `0x`, as `0x1F`
+    pub const PREFIXED: HexFormat = HexFormat::MINIMAL.with_prefix(true);
+    /// sign and magnitude with no leading zeros after `0x`, as `-0x1F`
+    pub const SIGNED: HexFormat = HexFormat::PREFIXED.with_signed(true);
+    /// every digit of the type in groups of 4, as `DEAD_BEEF`
+    pub const GROUPED: HexFormat = HexFormat::FULL.with_group(4);
 
-    fn from_hex_le(value: &str) -> Option<Self> {
-        i16_from_hex_le(value)
+    /// sets whether digits are lowercase. the prefix is always `0x`.
+    pub const fn with_lowercase(mut self, lower: bool) -> Self {
+        self.lower = lower;
+        self
     }
-}
 
-impl Hex for u16 {
-    fn to_hex_be(self) -> String {
-        u16be_to_hex(self)
+    /// sets whether leading zeros are left out
+    pub const fn with_minimal(mut self, minimal: bool) -> Self {
+        self.minimal = minimal;
+        self
     }
 
-    fn to_hex_be_low(self) -> String {
-        u16be_to_hex_low(self)
+    /// sets whether `0x` is written before the digits
+    pub const fn with_prefix(mut self, prefix: bool) -> Self {
+        self.prefix = prefix;
+        self
     }
 
-    fn from_hex_be(value: &str) -> Option<Self> {
-        u16_from_hex_be(value)
+    /// sets whether negative values of signed types are written as `-` and their magnitude
+    pub const fn with_signed(mut self, signed: bool) -> Self {
+        self.signed = signed;
+        self
     }
 
-    fn to_hex_le(self) -> String {
-        u16le_to_hex(self)
+    /// sets the number of digits per group, counted from the right, 0 for none
+    pub const fn with_group(mut self, group: usize) -> Self {
+        self.group = group;
+        self
     }
 
-    fn to_hex_le_low(self) -> String {
-        u16le_to_hex_low(self)
+    /// sets the char written between groups, `_` by default
+    pub const fn with_separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
     }
 
-    fn from_hex_le(value: &str) -> Option<Self> {
-        u16_from_hex_le(value)
+    #[inline]
+    pub const fn is_lowercase(&self) -> bool {
+        self.lower
     }
-}
 
-impl Hex for i32 {
-    fn to_hex_be(self) -> String {
-        i32be_to_hex(self)
+    #[inline]
+    pub const fn is_minimal(&self) -> bool {
+        self.minimal
     }
 
-    fn to_hex_be_low(self) -> String {
-        i32be_to_hex_low(self)
+    #[inline]
+    pub const fn has_prefix(&self) -> bool {
+        self.prefix
     }
 
-    fn from_hex_be(value: &str) -> Option<Self> {
-        i32_from_hex_be(value)
+    #[inline]
+    pub const fn is_signed(&self) -> bool {
+        self.signed
     }
 
-    fn to_hex_le(self) -> String {
-        i32le_to_hex(self)
+    /// the separator and the number of digits per group, or None if digits are not grouped
+    #[inline]
+    pub const fn group(&self) -> Option<(char, usize)> {
+        if self.group == 0 {
+            None
+        } else {
+            Some((self.separator, self.group))
+        }
     }
 
-    fn to_hex_le_low(self) -> String {
-        i32le_to_hex_low(self)
+    // writes n, a value of `bits` bits, or the magnitude of a negative value
+    fn format(&self, n: u128, bits: u32, negative: bool) -> String {
+        let width = if self.minimal {
+            ((u128::BITS - n.leading_zeros()).div_ceil(4) as usize).max(1)
+        } else {
+            bits as usize / 4
+        };
+        let mut s = String::with_capacity(width * 2 + 3);
+        if negative {
+            s.push('-');
+        }
+        if self.prefix {
+            s.push_str("0x");
+        }
+        for i in (0..width).rev() {
+            let d = ((n >> (i * 4)) & 0xF) as u8;
+            let c = if self.lower {
+                u8_to_hex_low_str(&d)
+            } else {
+                u8_to_hex_str(&d)
+            };
+            s.push_str(&c[1..]);
+            if self.group > 0 && i > 0 && i.is_multiple_of(self.group) {
+                s.push(self.separator);
+            }
+        }
+        s
     }
 
-    fn from_hex_le(value: &str) -> Option<Self> {
-        i32_from_hex_le(value)
+    // reads a magnitude of at most max, or of at most max_neg after a `-`, and whether it was
+    // negative. a `-` is only read when the format is signed and max_neg is not 0.
+    fn parse(&self, value: &str, max: u128, max_neg: u128) -> Result<(u128, bool), HexDecodeError> {
+        let mut rest = value;
+        let negative = self.signed && max_neg > 0 && rest.starts_with('-');
+        if negative {
+            rest = &rest[1..];
+        }
+        if self.prefix
+            && let Some(r) = rest.strip_prefix("0x").or_else(|| rest.strip_prefix("0X"))
+        {
+            rest = r;
+        }
+        let limit = if negative { max_neg } else { max };
+        let start = value.len() - rest.len();
+        let mut r = 0u128;
+        let mut digits = 0usize;
+        let mut chars = rest.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let index = start + i;
+            match hex_char_to_u8(&c) {
+                Some(d) => {
+                    // checked before the shift, which would lose the high bits of a u128
+                    if r > limit >> 4 || (r << 4) | d as u128 > limit {
+                        return Err(HexDecodeError::new(HexDecodeErrorKind::Overflow, index));
+                    }
+                    r = (r << 4) | d as u128;
+                    digits += 1;
+                }
+                // a separator only sits between two digits
+                None if self.group > 0
+                    && c == self.separator
+                    && digits > 0
+                    && chars.peek().is_some_and(|(_, n)| n.is_ascii_hexdigit()) => {}
+                None => return Err(HexDecodeError::new(HexDecodeErrorKind::InvalidChar, index)),
+            }
+        }
+        if digits == 0 {
+            return Err(HexDecodeError::new(HexDecodeErrorKind::Empty, value.len()));
+        }
+        Ok((r, negative))
     }
 }
 
-impl Hex for u32 {
-    fn to_hex_be(self) -> String {
-        u32be_to_hex(self)
+impl Default for HexFormat {
+    fn default() -> Self {
+        Self::FULL
     }
+}
 
-    fn to_hex_be_low(self) -> String {
-        u32be_to_hex_low(self)
-    }
+///
+/// hex conversion of the bytes of a primitive, in big, little or native endian order. signed
+/// integers are written as their two's complement bits, floats as their IEEE 754 bits, and
+/// `NonZero*` values as the integers they hold.
+///
+/// parsing with `from_hex_*` reads the hex digits among the first two chars per byte, skipping
+/// other chars. a shorter value fills the low digits of big endian, and the leading digits of
+/// little endian. `try_from_hex_*` reads the same values, but fails on a char that is not a hex
+/// digit, a value too big for the type, or no digits at all. leading zeros of big endian and
+/// trailing zeros of little endian are read past, as they do not add to the value.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// # use std::num::NonZeroU16;
+/// assert_eq!(0xABu8.to_hex_be(), "AB");
+/// assert_eq!((-2i8).to_hex_be(), "FE");
+/// assert_eq!(0x1234usize.to_hex_le_low()[..4], *"3412");
+/// assert_eq!(1.0f32.to_hex_be(), "3F800000");
+/// assert_eq!(f64::from_hex_be("400921FB54442D18"), Some(std::f64::consts::PI));
+/// assert_eq!(NonZeroU16::from_hex_be("0102"), NonZeroU16::new(0x102));
+/// assert_eq!(NonZeroU16::from_hex_be("0000"), None);
+///
+/// let x = 0x01020304u32;
+/// assert_eq!(u32::from_hex_ne(&x.to_hex_ne()), Some(x));
+///
+/// assert_eq!(u16::from_hex_be("12345"), Some(0x1234));
+/// let e = u16::try_from_hex_be("12345").unwrap_err();
+/// assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::Overflow, 4));
+/// let e = u16::try_from_hex_le("1G2").unwrap_err();
+/// assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 1));
+/// assert_eq!(u16::try_from_hex_le("3412"), Ok(0x1234));
+/// assert_eq!(u16::try_from_hex_be("00001234"), Ok(0x1234));
+/// ```
+pub trait Hex {
+    /// to big endian hex
+    fn to_hex_be(self) -> String;
+    /// to big endian lowercase hex
+    fn to_hex_be_low(self) -> String;
+    /// from big endian hex
+    fn from_hex_be(value: &str) -> Option<Self>
+    where
+        Self: Sized;
+    /// to little endian hex
+    fn to_hex_le(self) -> String;
+    /// to little endian lowercase hex
+    fn to_hex_le_low(self) -> String;
+    /// from little endian hex
+    fn from_hex_le(value: &str) -> Option<Self>
+    where
+        Self: Sized;
+    /// from big endian hex, failing on a bad char, a value too big or none
+    fn try_from_hex_be(value: &str) -> Result<Self, HexDecodeError>
+    where
+        Self: Sized;
+    /// from little endian hex, failing on a bad char, a value too big or none
+    fn try_from_hex_le(value: &str) -> Result<Self, HexDecodeError>
+    where
+        Self: Sized;
+    /// to hex written as a number in the given format
+    fn to_hex_fmt(self, format: &HexFormat) -> String;
+    /// from hex written as a number in the given format
+    fn from_hex_fmt(value: &str, format: &HexFormat) -> Option<Self>
+    where
+        Self: Sized;
 
-    fn from_hex_be(value: &str) -> Option<Self> {
-        u32_from_hex_be(value)
+    /// to native endian hex
+    fn to_hex_ne(self) -> String
+    where
+        Self: Sized,
+    {
+        if cfg!(target_endian = "big") {
+            self.to_hex_be()
+        } else {
+            self.to_hex_le()
+        }
     }
 
-    fn to_hex_le(self) -> String {
-        u32le_to_hex(self)
+    /// to native endian lowercase hex
+    fn to_hex_ne_low(self) -> String
+    where
+        Self: Sized,
+    {
+        if cfg!(target_endian = "big") {
+            self.to_hex_be_low()
+        } else {
+            self.to_hex_le_low()
+        }
     }
 
-    fn to_hex_le_low(self) -> String {
-        u32le_to_hex_low(self)
+    /// from native endian hex
+    fn from_hex_ne(value: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if cfg!(target_endian = "big") {
+            Self::from_hex_be(value)
+        } else {
+            Self::from_hex_le(value)
+        }
     }
 
-    fn from_hex_le(value: &str) -> Option<Self> {
-        u32_from_hex_le(value)
+    /// from native endian hex, failing on a bad char, a value too big or none
+    fn try_from_hex_ne(value: &str) -> Result<Self, HexDecodeError>
+    where
+        Self: Sized,
+    {
+        if cfg!(target_endian = "big") {
+            Self::try_from_hex_be(value)
+        } else {
+            Self::try_from_hex_le(value)
+        }
     }
 }
 
-impl Hex for i64 {
-    fn to_hex_be(self) -> String {
-        i64be_to_hex(self)
+// the hex digits among the first `digits` chars, most significant first, or None if there are none
+fn hex_digits_to_u128(value: &str, digits: usize) -> Option<u128> {
+    let mut r = 0u128;
+    let mut found = false;
+    for c in value.chars().take(digits) {
+        if let Some(i) = hex_char_to_u8(&c) {
+            found = true;
+            r = (r << 4) | i as u128;
+        }
     }
+    if found { Some(r) } else { None }
+}
 
-    fn to_hex_be_low(self) -> String {
-        i64be_to_hex_low(self)
+// the value of at most `digits` hex digits, most significant first, not counting the leading
+// zeros of big endian or the trailing zeros of little endian (le)
+fn try_hex_digits_to_u128(value: &str, digits: usize, le: bool) -> Result<u128, HexDecodeError> {
+    let mut r = 0u128;
+    let mut n = 0;
+    let mut found = false;
+    for (i, c) in value.char_indices() {
+        let Some(d) = hex_char_to_u8(&c) else {
+            return Err(HexDecodeError::new(HexDecodeErrorKind::InvalidChar, i));
+        };
+        found = true;
+        if !le && n == 0 && d == 0 {
+            continue;
+        }
+        if n == digits {
+            if le && d == 0 {
+                continue;
+            }
+            return Err(HexDecodeError::new(HexDecodeErrorKind::Overflow, i));
+        }
+        r = (r << 4) | d as u128;
+        n += 1;
     }
-
-    fn from_hex_be(value: &str) -> Option<Self> {
-        i64_from_hex_be(value)
+    if !found {
+        return Err(HexDecodeError::new(HexDecodeErrorKind::Empty, 0));
     }
+    Ok(r)
+}
 
-    fn to_hex_le(self) -> String {
-        i64le_to_hex(self)
+// hex of bytes in the order given
+#[inline]
+fn bytes_to_hex(bytes: &[u8], lower: bool) -> String {
+    if lower {
+        u8_array_to_hex_low(bytes)
+    } else {
+        u8_array_to_hex(bytes)
     }
+}
 
-    fn to_hex_le_low(self) -> String {
-        i64le_to_hex_low(self)
-    }
+// integers, with the unsigned type of the same width
+macro_rules! impl_hex_int {
+    ($($t:ty => $u:ty),* $(,)?) => {
+        $(
+            impl Hex for $t {
+                fn to_hex_be(self) -> String {
+                    bytes_to_hex(&self.to_be_bytes(), false)
+                }
 
-    fn from_hex_le(value: &str) -> Option<Self> {
-        i64_from_hex_le(value)
-    }
-}
+                fn to_hex_be_low(self) -> String {
+                    bytes_to_hex(&self.to_be_bytes(), true)
+                }
 
-impl Hex for u64 {
-    fn to_hex_be(self) -> String {
-        u64be_to_hex(self)
-    }
+                fn from_hex_be(value: &str) -> Option<Self> {
+                    hex_digits_to_u128(value, size_of::<$t>() * 2).map(|n| n as $u as $t)
+                }
 
-    fn to_hex_be_low(self) -> String {
-        u64be_to_hex_low(self)
-    }
+                fn to_hex_le(self) -> String {
+                    bytes_to_hex(&self.to_le_bytes(), false)
+                }
 
-    fn from_hex_be(value: &str) -> Option<Self> {
-        u64_from_hex_be(value)
-    }
+                fn to_hex_le_low(self) -> String {
+                    bytes_to_hex(&self.to_le_bytes(), true)
+                }
 
-    fn to_hex_le(self) -> String {
-        u64le_to_hex(self)
-    }
+                fn from_hex_le(value: &str) -> Option<Self> {
+                    hex_digits_to_u128(value, size_of::<$t>() * 2)
+                        .map(|n| (n as $u).swap_bytes() as $t)
+                }
 
-    fn to_hex_le_low(self) -> String {
-        u64le_to_hex_low(self)
-    }
+                fn try_from_hex_be(value: &str) -> Result<Self, HexDecodeError> {
+                    try_hex_digits_to_u128(value, size_of::<$t>() * 2, false)
+                        .map(|n| n as $u as $t)
+                }
 
-    fn from_hex_le(value: &str) -> Option<Self> {
-        u64_from_hex_le(value)
-    }
-}
+                fn try_from_hex_le(value: &str) -> Result<Self, HexDecodeError> {
+                    try_hex_digits_to_u128(value, size_of::<$t>() * 2, true)
+                        .map(|n| (n as $u).swap_bytes() as $t)
+                }
 
-impl Hex for i128 {
-    fn to_hex_be(self) -> String {
-        i128be_to_hex(self)
-    }
+                fn to_hex_fmt(self, format: &HexFormat) -> String {
+                    let bits = self as $u;
+                    if format.signed && self < (0 as $t) {
+                        format.format(bits.wrapping_neg() as u128, <$u>::BITS, true)
+                    } else {
+                        format.format(bits as u128, <$u>::BITS, false)
+                    }
+                }
 
-    fn to_hex_be_low(self) -> String {
-        i128be_to_hex_low(self)
-    }
+                fn from_hex_fmt(value: &str, format: &HexFormat) -> Option<Self> {
+                    // signed types read as sign and magnitude when the format is signed
+                    let (max, max_neg) = if format.signed && <$t>::MIN != 0 {
+                        (<$t>::MAX as u128, <$t>::MIN as $u as u128)
+                    } else {
+                        (<$u>::MAX as u128, 0)
+                    };
+                    let (n, negative) = format.parse(value, max, max_neg).ok()?;
+                    if negative {
+                        Some((n as $u).wrapping_neg() as $t)
+                    } else {
+                        Some(n as $u as $t)
+                    }
+                }
+            }
+        )*
+    };
+}
 
-    fn from_hex_be(value: &str) -> Option<Self> {
-        i128_from_hex_be(value)
-    }
+impl_hex_int!(
+    i8 => u8,
+    u8 => u8,
+    i16 => u16,
+    u16 => u16,
+    i32 => u32,
+    u32 => u32,
+    i64 => u64,
+    u64 => u64,
+    i128 => u128,
+    u128 => u128,
+    isize => usize,
+    usize => usize,
+);
+
+// types stored as another type that implements Hex, converted with `into` and `from`
+macro_rules! impl_hex_via {
+    ($($t:ty => $u:ty, |$x:ident| $into:expr, $from:expr);* $(;)?) => {
+        $(
+            impl Hex for $t {
+                fn to_hex_be(self) -> String {
+                    let $x = self;
+                    ($into).to_hex_be()
+                }
 
-    fn to_hex_le(self) -> String {
-        i128le_to_hex(self)
-    }
+                fn to_hex_be_low(self) -> String {
+                    let $x = self;
+                    ($into).to_hex_be_low()
+                }
 
-    fn to_hex_le_low(self) -> String {
-        i128le_to_hex_low(self)
-    }
+                fn from_hex_be(value: &str) -> Option<Self> {
+                    <$u>::from_hex_be(value).and_then($from)
+                }
 
-    fn from_hex_le(value: &str) -> Option<Self> {
-        i128_from_hex_le(value)
-    }
-}
+                fn to_hex_le(self) -> String {
+                    let $x = self;
+                    ($into).to_hex_le()
+                }
 
-impl Hex for u128 {
-    fn to_hex_be(self) -> String {
-        u128be_to_hex(self)
-    }
+                fn to_hex_le_low(self) -> String {
+                    let $x = self;
+                    ($into).to_hex_le_low()
+                }
 
-    fn to_hex_be_low(self) -> String {
-        u128be_to_hex_low(self)
-    }
+                fn from_hex_le(value: &str) -> Option<Self> {
+                    <$u>::from_hex_le(value).and_then($from)
+                }
 
-    fn from_hex_be(value: &str) -> Option<Self> {
-        u128_from_hex_be(value)
-    }
+                fn try_from_hex_be(value: &str) -> Result<Self, HexDecodeError> {
+                    let n = <$u>::try_from_hex_be(value)?;
+                    ($from)(n).ok_or(HexDecodeError::new(HexDecodeErrorKind::Zero, 0))
+                }
 
-    fn to_hex_le(self) -> String {
-        u128le_to_hex(self)
-    }
+                fn try_from_hex_le(value: &str) -> Result<Self, HexDecodeError> {
+                    let n = <$u>::try_from_hex_le(value)?;
+                    ($from)(n).ok_or(HexDecodeError::new(HexDecodeErrorKind::Zero, 0))
+                }
 
-    fn to_hex_le_low(self) -> String {
-        u128le_to_hex_low(self)
-    }
+                fn to_hex_fmt(self, format: &HexFormat) -> String {
+                    let $x = self;
+                    ($into).to_hex_fmt(format)
+                }
 
-    fn from_hex_le(value: &str) -> Option<Self> {
-        u128_from_hex_le(value)
-    }
+                fn from_hex_fmt(value: &str, format: &HexFormat) -> Option<Self> {
+                    <$u>::from_hex_fmt(value, format).and_then($from)
+                }
+            }
+        )*
+    };
 }
 
+impl_hex_via!(
+    f32 => u32, |x| x.to_bits(), |n| Some(f32::from_bits(n));
+    f64 => u64, |x| x.to_bits(), |n| Some(f64::from_bits(n));
+    NonZeroI8 => i8, |x| x.get(), NonZeroI8::new;
+    NonZeroU8 => u8, |x| x.get(), NonZeroU8::new;
+    NonZeroI16 => i16, |x| x.get(), NonZeroI16::new;
+    NonZeroU16 => u16, |x| x.get(), NonZeroU16::new;
+    NonZeroI32 => i32, |x| x.get(), NonZeroI32::new;
+    NonZeroU32 => u32, |x| x.get(), NonZeroU32::new;
+    NonZeroI64 => i64, |x| x.get(), NonZeroI64::new;
+    NonZeroU64 => u64, |x| x.get(), NonZeroU64::new;
+    NonZeroI128 => i128, |x| x.get(), NonZeroI128::new;
+    NonZeroU128 => u128, |x| x.get(), NonZeroU128::new;
+    NonZeroIsize => isize, |x| x.get(), NonZeroIsize::new;
+    NonZeroUsize => usize, |x| x.get(), NonZeroUsize::new;
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2221,6 +3351,601 @@ mod tests {
         assert_eq!(x, y.unwrap());
     }
 
+    #[test]
+    fn test_hex_long() {
+        let data = (0..1000u32)
+            .map(|x| (x * 7 + x / 5) as u8)
+            .collect::<Vec<u8>>();
+        let hex = u8_array_to_hex(&data);
+        let hex_low = u8_array_to_hex_low(&data);
+        assert_eq!(hex, data.iter().map(u8_to_hex_str).collect::<String>());
+        assert_eq!(
+            hex_low,
+            data.iter().map(u8_to_hex_low_str).collect::<String>()
+        );
+        assert_eq!(hex_str_to_u8_vec(&hex), Some(data.clone()));
+        assert_eq!(hex_str_to_u8_vec(&hex_low), Some(data.clone()));
+
+        // odd length keeps the leading lone nibble
+        let mut odd = String::from("A");
+        odd.push_str(&hex);
+        let v = hex_str_to_u8_vec(&odd).unwrap();
+        assert_eq!(v[0], 0x0A);
+        assert_eq!(&v[1..], data.as_slice());
+
+        for pos in [0usize, 31, 32, 500, 1999] {
+            let mut bad = hex.clone().into_bytes();
+            bad[pos] = b'G';
+            assert_eq!(hex_str_to_u8_vec(&String::from_utf8(bad).unwrap()), None);
+        }
+        let mut s = hex.clone();
+        s.insert(100, '\u{e9}');
+        assert_eq!(hex_str_to_u8_vec(&s), None);
+    }
+
+    #[test]
+    fn test_hex_ct() {
+        let data = (0..=255u8).collect::<Vec<u8>>();
+        let s = u8_array_to_hex(&data);
+        assert_eq!(hex_str_to_u8_vec_ct(&s).unwrap(), data);
+        assert_eq!(hex_str_to_u8_vec_ct(&s.to_lowercase()).unwrap(), data);
+        for b in 0..=255u8 {
+            let valid = hex_char_to_u8(&(b as char)).is_some();
+            assert_eq!(
+                hex_str_to_u8_vec_ct(&format!("A{}", b as char)).is_some(),
+                valid
+            );
+            assert_eq!(
+                hex_str_to_u8_vec_ct(&format!("{}0", b as char)).is_some(),
+                valid
+            );
+        }
+        assert_eq!(hex_str_to_u8_vec_ct("A"), Some(vec![0x0A]));
+        assert_eq!(hex_str_to_u8_vec_ct(""), Some(vec![]));
+        assert_eq!(hex_str_to_u8_vec_ct("\u{e9}"), None);
+    }
+
+    #[test]
+    fn test_hex_strict() {
+        let data = (0..=255u8).collect::<Vec<u8>>();
+        let s = u8_array_to_hex_low(&data);
+        assert_eq!(hex_str_to_u8_vec_strict(&s), Ok(data.clone()));
+        for n in 0..s.len() {
+            // a bad char in every position, past the SIMD prefix or not
+            let mut bad = s.clone();
+            bad.replace_range(n..n + 1, "g");
+            let e = HexDecodeError::new(HexDecodeErrorKind::InvalidChar, n);
+            assert_eq!(hex_str_to_u8_vec_strict(&bad), Err(e));
+            let bad = bad.as_str();
+            let mut it = bad.iter_hex_to_byte_strict();
+            assert_eq!(it.by_ref().count(), n / 2);
+            assert_eq!(it.error(), Some(e));
+        }
+        let e = HexDecodeError::new(HexDecodeErrorKind::OddLength, s.len() - 1);
+        assert_eq!(hex_str_to_u8_vec_strict(&s[..s.len() - 1]), Err(e));
+        assert_eq!(hex_str_to_u8_vec_strict(""), Ok(vec![]));
+        let e = HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 2);
+        assert_eq!(hex_str_to_u8_vec_strict("AB\u{e9}"), Err(e));
+        let e = HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 4);
+        assert_eq!(hex_str_to_u8_vec_strict("ABCD\u{e9}"), Err(e));
+
+        // lenient versions are unchanged
+        assert_eq!(hex_str_to_u8("AZ"), Some(0x0A));
+        assert_eq!(
+            "ABZ".iter_hex_to_byte().collect::<Vec<u8>>(),
+            vec![0x0A, 0xB]
+        );
+        assert_eq!(
+            "ABCZ".iter_hex_to_byte().collect::<Vec<u8>>(),
+            vec![0xAB, 0xC]
+        );
+
+        let s = format!("{}", HexDecodeError::new(HexDecodeErrorKind::OddLength, 5));
+        assert_eq!(s.as_str(), "odd number of hex digits at byte 5");
+    }
+
+    #[test]
+    fn test_hex_config() {
+        let data = (0..=255u8).collect::<Vec<u8>>();
+        for cfg in [
+            HexConfig::UPPER,
+            HexConfig::COLON,
+            HexConfig::SPACED,
+            HexConfig::ESCAPED,
+            HexConfig::PREFIXED,
+            HexConfig::UPPER.with_group("_", 4),
+            HexConfig::LOWER.with_prefix("#").with_group(", ", 3),
+        ] {
+            for n in [0, 1, 2, 3, 4, 5, 7, 8, 9, 256] {
+                let v = &data[..n];
+                let s = cfg.encode(v);
+                assert_eq!(s.len(), cfg.encoded_len(n));
+                assert_eq!(cfg.iter_encode(v).collect::<String>(), s);
+                assert_eq!(cfg.try_decode(&s).unwrap(), v);
+                assert_eq!(cfg.iter_decode(&s).collect::<Vec<u8>>(), v);
+            }
+        }
+
+        let v = vec![0xDE, 0xAD, 0xBE, 0xEF];
+        let cfg = HexConfig::PREFIXED.with_separators(": \\x");
+        for s in [
+            "0xDEADBEEF",
+            "de:ad:be:ef",
+            "de ad be ef",
+            "#deadbeef",
+            "0Xde:ad be:ef",
+        ] {
+            assert_eq!(cfg.decode(s), Some(v.clone()));
+        }
+        assert_eq!(cfg.decode("\\xde\\xad"), Some(vec![0xDE, 0xAD]));
+        assert_eq!(HexConfig::COLON.decode(":de::ad:"), Some(vec![0xDE, 0xAD]));
+
+        // the prefix is only skipped when stripping, and only once
+        assert_eq!(HexConfig::UPPER.decode("0xAB"), None);
+        let e = HexConfig::PREFIXED.try_decode("0x0xAB").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 3));
+        let e = HexConfig::PREFIXED.try_decode("0xABC").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::OddLength, 5));
+        assert_eq!(HexConfig::PREFIXED.decode("0xABC"), Some(vec![0x0A, 0xBC]));
+        assert_eq!(HexConfig::PREFIXED.with_strict(true).decode("0xABC"), None);
+        assert_eq!(
+            HexConfig::PREFIXED
+                .with_prefix_stripping(false)
+                .decode("ABC"),
+            Some(vec![0x0A, 0xBC])
+        );
+
+        // indices count the prefix and separators
+        let e = HexConfig::COLON.try_decode("de:ad:bx").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 7));
+        let e = HexConfig::COLON.try_decode("de-ad").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 2));
+        let e = HexConfig::SPACED.try_decode("de a").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::OddLength, 4));
+
+        // the group separator is skipped whichever builder comes last
+        let a = HexConfig::UPPER.with_group("-", 2).with_separators(" ");
+        let b = HexConfig::UPPER.with_separators(" ").with_group("-", 2);
+        assert_eq!(a, b);
+        assert_eq!(a.decode(&a.encode(b"Many")), Some(b"Many".to_vec()));
+        assert_eq!(a.try_decode("4D61 6E-79"), Ok(b"Many".to_vec()));
+
+        // hex digits never separate
+        let cfg = HexConfig::UPPER.with_separators("a:");
+        assert!(cfg.is_separator(':'));
+        assert!(!cfg.is_separator('a'));
+        assert!(!cfg.is_separator('\u{e9}'));
+        assert_eq!(HexConfig::COLON.group(), Some((":", 1)));
+        assert_eq!(HexConfig::UPPER.group(), None);
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        let data = (0..=255u8).rev().collect::<Vec<u8>>();
+        for style in [HexDumpStyle::Xxd, HexDumpStyle::Canonical] {
+            for (width, group) in [(16, 2), (16, 8), (7, 3), (1, 0), (32, 4), (10, 0)] {
+                for ascii in [true, false] {
+                    let cfg = HexDumpConfig::XXD
+                        .with_style(style)
+                        .with_width(width)
+                        .with_group(group)
+                        .with_ascii(ascii)
+                        .with_lowercase(width % 2 == 0);
+                    for n in [0, 1, 5, 16, 17, 256] {
+                        let s = cfg.dump(&data[..n]).to_string();
+                        assert_eq!(hex_dump_to_bytes(&s).unwrap(), &data[..n]);
+                        let lines = n.div_ceil(width)
+                            + (style == HexDumpStyle::Canonical && n > 0) as usize;
+                        assert_eq!(s.lines().count(), lines);
+                    }
+                }
+            }
+        }
+
+        // xxd -g 4 -u -o 256
+        let s = "Many hands make light work.".hex_dump_with(
+            &HexDumpConfig::XXD
+                .with_group(4)
+                .with_lowercase(false)
+                .with_offset(256),
+        );
+        let xxd = "00000100: 4D616E79 2068616E 6473206D 616B6520  Many hands make \n\
+                   00000110: 6C696768 7420776F 726B2E             light work.\n";
+        assert_eq!(s.to_string().as_str(), xxd);
+        let xxd = xxd.replace('\n', "\r\n");
+        assert_eq!(
+            hex_dump_to_bytes(&xxd).unwrap(),
+            b"Many hands make light work."
+        );
+        assert_eq!(hex_dump_to_bytes(""), Ok(vec![]));
+        assert_eq!("".hex_dump().to_string(), "");
+        assert_eq!("".hex_dump_with(&HexDumpConfig::CANONICAL).to_string(), "");
+        assert_eq!(HexDumpConfig::XXD.with_width(0).width(), 1);
+
+        let e = hex_dump_to_bytes("00000000: 486  H.\n").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::OddLength, 12));
+        let e = hex_dump_to_bytes("0000000g: 48\n").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 7));
+        let e = hex_dump_to_bytes("00000000  48\n*\n").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 13));
+        let e = hex_dump_to_bytes(": 48\n").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 0));
+
+        // plain hex, and lines of only digits among lines with offsets
+        assert_eq!(hex_dump_to_bytes("48656c6c6f\n"), Ok(b"Hello".to_vec()));
+        assert_eq!(hex_dump_to_bytes("4865\r\n6c6C6f"), Ok(b"Hello".to_vec()));
+        let e = hex_dump_to_bytes("4865\n6c6\n").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::OddLength, 8));
+        let dump = "00000000  48 65                                             |He|\n00000002\n\n";
+        assert_eq!(hex_dump_to_bytes(dump), Ok(b"He".to_vec()));
+        let e = hex_dump_to_bytes("00000000: 4865  He\n000000026c6c6f\n").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 33));
+        let e = hex_dump_to_bytes("00000000: 4865  He\n00000002\n00000010: 6c  l\n").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 27));
+        let e = hex_dump_to_bytes("00000000: 4865  He\n000000002\n").unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 28));
+        let cfg = HexDumpConfig::CANONICAL.with_offset(1 << 40);
+        let s = b"Hello".hex_dump_with(&cfg).to_string();
+        assert!(s.ends_with("\n10000000005\n"));
+        assert_eq!(hex_dump_to_bytes(&s), Ok(b"Hello".to_vec()));
+    }
+
+    fn check_hex<T: Hex + Copy + PartialEq + std::fmt::Debug>(values: &[T], bytes: usize) {
+        for &x in values {
+            for s in [x.to_hex_be(), x.to_hex_le(), x.to_hex_ne()] {
+                assert_eq!(s.len(), bytes * 2);
+            }
+            assert_eq!(x.to_hex_be().to_lowercase(), x.to_hex_be_low());
+            assert_eq!(x.to_hex_le().to_lowercase(), x.to_hex_le_low());
+            assert_eq!(x.to_hex_ne().to_lowercase(), x.to_hex_ne_low());
+            assert_eq!(T::from_hex_be(&x.to_hex_be()), Some(x));
+            assert_eq!(T::from_hex_le(&x.to_hex_le_low()), Some(x));
+            assert_eq!(T::from_hex_ne(&x.to_hex_ne()), Some(x));
+        }
+    }
+
+    #[test]
+    fn test_hex_generic() {
+        check_hex(&[0u8, 1, 0x7F, 0xFF], 1);
+        check_hex(&[0i8, -1, i8::MIN, i8::MAX], 1);
+        check_hex(&[0usize, 1, usize::MAX], size_of::<usize>());
+        check_hex(&[0isize, -2, isize::MIN], size_of::<isize>());
+        check_hex(&[0.0f32, -1.5, f32::MAX, f32::INFINITY], 4);
+        check_hex(&[0.0f64, 1e300, f64::NEG_INFINITY], 8);
+        check_hex(&[NonZeroU32::MIN, NonZeroU32::MAX], 4);
+        check_hex(&[NonZeroI128::MIN, NonZeroI128::MAX], 16);
+        check_hex(&[NonZeroU8::MAX], 1);
+        check_hex(&[NonZeroIsize::new(-3).unwrap()], size_of::<isize>());
+
+        assert_eq!((-1.5f32).to_hex_be(), "BFC00000");
+        assert_eq!((-1.5f32).to_hex_le(), "0000C0BF");
+        assert!(f32::from_hex_be("7FC00000").unwrap().is_nan());
+        assert_eq!(u8::from_hex_be("F"), Some(0xF));
+        assert_eq!(u8::from_hex_be("ABC"), Some(0xAB));
+        assert_eq!(i8::from_hex_be("80"), Some(i8::MIN));
+        assert_eq!(u8::from_hex_be("xyz"), None);
+        assert_eq!(NonZeroU64::from_hex_le("00"), None);
+        assert_eq!(NonZeroU16::from_hex_le("0001"), NonZeroU16::new(0x100));
+
+        // the free functions match the trait
+        assert_eq!(u32ne_to_hex(0x1234), 0x1234u32.to_hex_ne());
+        assert_eq!(i64ne_to_hex_low(-5), (-5i64).to_hex_ne_low());
+        assert_eq!(u16_from_hex_le("F4F"), Some(0x4F0F));
+        if cfg!(target_endian = "little") {
+            assert_eq!(0x0102u16.to_hex_ne(), "0201");
+        }
+    }
+
+    #[test]
+    fn test_hex_format() {
+        let formats = [
+            HexFormat::FULL,
+            HexFormat::MINIMAL,
+            HexFormat::PREFIXED,
+            HexFormat::SIGNED,
+            HexFormat::GROUPED,
+            HexFormat::SIGNED
+                .with_group(3)
+                .with_separator(' ')
+                .with_lowercase(true),
+        ];
+        for fmt in formats {
+            for x in [0i32, 1, -1, 0x1F, -0x1F, i32::MIN, i32::MAX, 0x10000] {
+                let s = x.to_hex_fmt(&fmt);
+                assert_eq!(i32::from_hex_fmt(&s, &fmt), Some(x), "{s}");
+                let s = (x as u32).to_hex_fmt(&fmt);
+                assert_eq!(u32::from_hex_fmt(&s, &fmt), Some(x as u32), "{s}");
+            }
+            for x in [i128::MIN, i128::MAX, -1] {
+                assert_eq!(i128::from_hex_fmt(&x.to_hex_fmt(&fmt), &fmt), Some(x));
+            }
+            for x in [i8::MIN, -1, 0, i8::MAX] {
+                assert_eq!(i8::from_hex_fmt(&x.to_hex_fmt(&fmt), &fmt), Some(x));
+            }
+            assert_eq!(
+                f32::from_hex_fmt(&(-2.5f32).to_hex_fmt(&fmt), &fmt),
+                Some(-2.5)
+            );
+        }
+
+        assert_eq!(0u8.to_hex_fmt(&HexFormat::MINIMAL), "0");
+        assert_eq!(0u8.to_hex_fmt(&HexFormat::GROUPED), "00");
+        assert_eq!(0x12345u32.to_hex_fmt(&HexFormat::GROUPED), "0001_2345");
+        assert_eq!(i8::MIN.to_hex_fmt(&HexFormat::SIGNED), "-0x80");
+        assert_eq!(u8::MAX.to_hex_fmt(&HexFormat::SIGNED), "0xFF");
+        assert_eq!((-1i32).to_hex_fmt(&HexFormat::PREFIXED), "0xFFFFFFFF");
+        assert_eq!((-2.0f32).to_hex_fmt(&HexFormat::SIGNED), "0xC0000000");
+        assert_eq!(
+            NonZeroI16::new(-2).unwrap().to_hex_fmt(&HexFormat::SIGNED),
+            "-0x2"
+        );
+
+        // signs, prefixes and separators are only read in formats that have them
+        assert_eq!(u8::from_hex_fmt("-1", &HexFormat::SIGNED), None);
+        assert_eq!(i8::from_hex_fmt("-1", &HexFormat::PREFIXED), None);
+        assert_eq!(u8::from_hex_fmt("0x1", &HexFormat::MINIMAL), None);
+        assert_eq!(u8::from_hex_fmt("1", &HexFormat::PREFIXED), Some(1));
+        assert_eq!(u16::from_hex_fmt("1_2", &HexFormat::MINIMAL), None);
+        assert_eq!(u16::from_hex_fmt("1_2", &HexFormat::GROUPED), Some(0x12));
+        assert_eq!(u16::from_hex_fmt("_12", &HexFormat::GROUPED), None);
+        assert_eq!(u16::from_hex_fmt("12_", &HexFormat::GROUPED), None);
+        assert_eq!(u16::from_hex_fmt("1__2", &HexFormat::GROUPED), None);
+        assert_eq!(u16::from_hex_fmt("0x", &HexFormat::PREFIXED), None);
+        assert_eq!(
+            u16::from_hex_fmt("000000ABCD", &HexFormat::FULL),
+            Some(0xABCD)
+        );
+        assert_eq!(i8::from_hex_fmt("-0x81", &HexFormat::SIGNED), None);
+        assert_eq!(i8::from_hex_fmt("FF", &HexFormat::SIGNED), None);
+        assert_eq!(NonZeroU8::from_hex_fmt("0", &HexFormat::MINIMAL), None);
+
+        // 128 bit values that only overflow past the top bit
+        let max = format!("{:X}", u128::MAX);
+        assert_eq!(u128::from_hex_fmt(&max, &HexFormat::FULL), Some(u128::MAX));
+        let big = format!("1{}", "0".repeat(32));
+        assert_eq!(u128::from_hex_fmt(&big, &HexFormat::FULL), None);
+        assert_eq!(
+            u128::from_hex_fmt(&format!("{max}0"), &HexFormat::FULL),
+            None
+        );
+        assert_eq!(NonZeroU128::from_hex_fmt(&big, &HexFormat::MINIMAL), None);
+        let min = format!("-0x8{}", "0".repeat(31));
+        assert_eq!(
+            i128::from_hex_fmt(&min, &HexFormat::SIGNED),
+            Some(i128::MIN)
+        );
+        assert_eq!(
+            i128::from_hex_fmt(&format!("{min}0"), &HexFormat::SIGNED),
+            None
+        );
+        assert_eq!(i128::from_hex_fmt(&min[1..], &HexFormat::SIGNED), None);
+        let e = HexFormat::FULL.parse(&big, u128::MAX, 0).unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::Overflow, 32));
+
+        let e = HexFormat::SIGNED.parse("-0x1FF", 0xFF, 0x80).unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::Overflow, 5));
+        let e = HexFormat::SIGNED.parse("-0x", 0xFF, 0x80).unwrap_err();
+        assert_eq!(e, HexDecodeError::new(HexDecodeErrorKind::Empty, 3));
+    }
+
+    #[test]
+    fn test_hex_try() {
+        // strict parsing agrees with lenient parsing wherever it succeeds
+        for s in ["1", "12", "123", "1234", "abcd", "FfFf", "0000", "8000"] {
+            assert_eq!(u16::try_from_hex_be(s).ok(), u16::from_hex_be(s), "{s}");
+            assert_eq!(i16::try_from_hex_le(s).ok(), i16::from_hex_le(s), "{s}");
+            assert_eq!(u16::try_from_hex_ne(s).ok(), u16::from_hex_ne(s), "{s}");
+        }
+        for x in [0u64, 1, u64::MAX, 0x0123456789ABCDEF] {
+            assert_eq!(u64::try_from_hex_be(&x.to_hex_be()), Ok(x));
+            assert_eq!(u64::try_from_hex_le(&x.to_hex_le_low()), Ok(x));
+        }
+        assert_eq!(f64::try_from_hex_be(&1.5f64.to_hex_be()), Ok(1.5));
+        assert_eq!(i128::try_from_hex_be(&"F".repeat(32)), Ok(-1));
+
+        fn err<T>(kind: HexDecodeErrorKind, index: usize) -> Result<T, HexDecodeError> {
+            Err(HexDecodeError::new(kind, index))
+        }
+        assert_eq!(
+            u16::try_from_hex_be("12345"),
+            err(HexDecodeErrorKind::Overflow, 4)
+        );
+        assert_eq!(
+            u8::try_from_hex_le("001"),
+            err(HexDecodeErrorKind::Overflow, 2)
+        );
+
+        // zeros that do not add to the value are read past
+        assert_eq!(u16::try_from_hex_be("00001234"), Ok(0x1234));
+        assert_eq!(u16::try_from_hex_le("34120000"), Ok(0x1234));
+        assert_eq!(u8::try_from_hex_le("000"), Ok(0));
+        assert_eq!(u8::try_from_hex_be("000"), Ok(0));
+        assert_eq!(i128::try_from_hex_be(&"0".repeat(40)), Ok(0));
+        assert_eq!(
+            u16::try_from_hex_be("0012345"),
+            err(HexDecodeErrorKind::Overflow, 6)
+        );
+        assert_eq!(
+            u16::try_from_hex_le("341201"),
+            err(HexDecodeErrorKind::Overflow, 5)
+        );
+        assert_eq!(
+            u16::try_from_hex_be("1G2"),
+            err(HexDecodeErrorKind::InvalidChar, 1)
+        );
+        assert_eq!(
+            u16::try_from_hex_be("12 "),
+            err(HexDecodeErrorKind::InvalidChar, 2)
+        );
+        assert_eq!(
+            u16::try_from_hex_be("0x12"),
+            err(HexDecodeErrorKind::InvalidChar, 1)
+        );
+        assert_eq!(
+            u32::try_from_hex_be("\u{e9}1"),
+            err(HexDecodeErrorKind::InvalidChar, 0)
+        );
+        assert_eq!(
+            u32::try_from_hex_le("1\u{e9}"),
+            err(HexDecodeErrorKind::InvalidChar, 1)
+        );
+        assert_eq!(u32::try_from_hex_be(""), err(HexDecodeErrorKind::Empty, 0));
+        assert_eq!(
+            NonZeroU32::try_from_hex_be("0"),
+            err(HexDecodeErrorKind::Zero, 0)
+        );
+        assert_eq!(
+            NonZeroU32::try_from_hex_le("01"),
+            Ok(NonZeroU32::new(1 << 24).unwrap())
+        );
+        assert_eq!(
+            NonZeroU8::try_from_hex_be("100"),
+            err(HexDecodeErrorKind::Overflow, 2)
+        );
+
+        let s = format!("{}", HexDecodeError::new(HexDecodeErrorKind::Zero, 0));
+        assert_eq!(s.as_str(), "zero value at byte 0");
+    }
+
+    // reader that hands out at most `step` bytes per read
+    struct StepReader<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl std::io::Read for StepReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.step.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    // writer that fails its first write
+    #[derive(Default)]
+    struct FailOnce {
+        failed: bool,
+        out: Vec<u8>,
+    }
+
+    impl std::io::Write for FailOnce {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if !self.failed {
+                self.failed = true;
+                return Err(std::io::Error::other("write failed"));
+            }
+            self.out.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_hex_stream() {
+        use std::io::{Read, Write};
+        let data = (0..3000u32)
+            .map(|x| (x * 7 + x / 3) as u8)
+            .collect::<Vec<u8>>();
+        let configs = [
+            HexConfig::UPPER,
+            HexConfig::ESCAPED,
+            HexConfig::PREFIXED.with_line_wrap(32, "\r\n"),
+            HexConfig::COLON.with_line_wrap(6, "\n"),
+            HexConfig::SPACED
+                .with_group(" ", 4)
+                .with_line_wrap(16, "\n"),
+            HexConfig::COLON
+                .with_line_wrap(5, "\r\n")
+                .with_separators(" "),
+        ];
+        for cfg in configs {
+            for n in [0, 1, 7, 3000] {
+                let s = cfg.encode(&data[..n]);
+                assert_eq!(s.len(), cfg.encoded_len(n));
+                assert_eq!(cfg.iter_encode(&data[..n]).collect::<String>(), s);
+                assert_eq!(cfg.decode(&s).unwrap(), &data[..n]);
+                for step in [1usize, 2, 3, 1000, 5000] {
+                    let mut w = HexWriter::new_with(Vec::new(), &cfg);
+                    for chunk in data[..n].chunks(step) {
+                        w.write_all(chunk).unwrap();
+                    }
+                    assert_eq!(w.finish().unwrap(), s.as_bytes());
+
+                    let src = StepReader {
+                        data: s.as_bytes(),
+                        step,
+                    };
+                    let mut r = HexReader::new_with(src, &cfg.with_strict(true));
+                    let mut v = Vec::new();
+                    r.read_to_end(&mut v).unwrap();
+                    assert_eq!(v, &data[..n]);
+                    assert_eq!(r.error(), None);
+                }
+            }
+        }
+        // the line ending is skipped whichever builder comes last
+        assert_eq!(
+            HexConfig::LOWER
+                .with_line_wrap(5, "\r\n")
+                .with_separators(" "),
+            HexConfig::LOWER
+                .with_separators(" ")
+                .with_line_wrap(5, "\r\n")
+        );
+
+        let mut w = HexWriter::new_low(Vec::new());
+        w.write_all(&[0xAB, 0xCD]).unwrap();
+        assert_eq!(w.get_ref().as_slice(), b"abcd");
+        assert_eq!(w.finish().unwrap(), b"abcd");
+        assert_eq!(HexWriter::new(Vec::new()).finish().unwrap(), b"");
+
+        // a failed write leaves the separators where they were
+        let mut w = HexWriter::new_with(FailOnce::default(), &HexConfig::COLON);
+        assert!(w.write_all(b"ab").is_err());
+        w.write_all(b"Many").unwrap();
+        assert_eq!(w.finish().unwrap().out, b"4d:61:6e:79");
+
+        // lenient readers end at the problem, strict ones fail the read
+        for (src, kind, index, good) in [
+            ("ABC", HexDecodeErrorKind::OddLength, 2, 1),
+            ("AB:CD", HexDecodeErrorKind::InvalidChar, 2, 1),
+            ("AB\u{e9}", HexDecodeErrorKind::InvalidChar, 2, 1),
+            ("0xAB", HexDecodeErrorKind::InvalidChar, 1, 0),
+        ] {
+            let e = HexDecodeError::new(kind, index);
+            let mut r = HexReader::new(src.as_bytes());
+            let mut v = Vec::new();
+            r.read_to_end(&mut v).unwrap();
+            assert_eq!(v.len(), good);
+            assert_eq!(r.error(), Some(e));
+
+            let mut r = HexReader::new_with(src.as_bytes(), &HexConfig::UPPER.with_strict(true));
+            let err = r.read_to_end(&mut Vec::new()).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            assert_eq!(r.error(), Some(e));
+        }
+
+        // the prefix may be split across reads, and is skipped once
+        let src = StepReader {
+            data: b"0x0xAB",
+            step: 1,
+        };
+        let mut r = HexReader::new_with(src, &HexConfig::PREFIXED);
+        let mut v = Vec::new();
+        r.read_to_end(&mut v).unwrap();
+        assert!(v.is_empty());
+        let e = HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 3);
+        assert_eq!(r.error(), Some(e));
+        let mut r = HexReader::new_with("#\u{e9}".as_bytes(), &HexConfig::PREFIXED);
+        assert_eq!(r.read(&mut [0; 4]).unwrap(), 0);
+        let e = HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 1);
+        assert_eq!(r.error(), Some(e));
+    }
+
     #[test]
     fn test_hex_docs() {
         let x = 0x1F2i64;
@@ -0,0 +1,778 @@
+//! reading and writing Intel HEX and Motorola S-record firmware files, to and from an
+//! address-sparse [`MemoryImage`]
+//!
+//! # Quick Start
+//!
+//!```
+//! use cj_common::prelude::*;
+//!
+//! let mut image = MemoryImage::new();
+//! image.insert(0x0800_0000, b"Many hands").unwrap();
+//! image.insert(0x0800_0100, b"make light work.").unwrap();
+//! image.entry = Some(0x0800_0000);
+//!
+//! let s = image.to_ihex();
+//! assert!(s.starts_with(":020000040800F2\n:0A0000004D616E792068616E6473"));
+//! assert!(s.ends_with(":0400000508000000EF\n:00000001FF\n"));
+//! assert_eq!(parse_ihex(&s), Ok(image.clone()));
+//!
+//! let s = image.to_srec();
+//! assert_eq!(parse_srec(&s), Ok(image.clone()));
+//!
+//! // writing over data already in the image is an error
+//! let e = image.insert(0x0800_0108, b"??").unwrap_err();
+//! assert_eq!(e.kind, HexFileErrorKind::Overlap(0x0800_0108));
+//! assert_eq!(image.gaps(), vec![(0x0800_000A, 0x0800_0100)]);
+//! ```
+
+use crate::cj_binary::hex::{HexDecodeError, hex_str_to_u8_vec_strict, u8_array_to_hex};
+
+/// a run of bytes at consecutive addresses
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MemorySegment {
+    pub address: u32,
+    pub data: Vec<u8>,
+}
+
+impl MemorySegment {
+    /// the address just past the last byte
+    #[inline]
+    pub fn end(&self) -> u64 {
+        self.address as u64 + self.data.len() as u64
+    }
+}
+
+///
+/// the contents of a firmware file: bytes at addresses in a 32 bit space, which need not be
+/// contiguous, along with the execution start address and the S-record header.
+///
+/// the bytes are held as segments sorted by address, with no two overlapping or touching.
+/// [`MemoryImage::insert`] merges new bytes into them, failing if any address is already set.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// let mut image = MemoryImage::new();
+/// image.insert(0x100, &[1, 2, 3]).unwrap();
+/// image.insert(0x103, &[4]).unwrap();
+/// image.insert(0x0F0, &[0]).unwrap();
+/// assert_eq!(image.segments().len(), 2);
+/// assert_eq!(image.read(0x100, 4), Some(&[1u8, 2, 3, 4][..]));
+/// assert_eq!(image.get(0x0F1), None);
+/// assert_eq!(image.gaps(), vec![(0x0F1, 0x100)]);
+/// assert_eq!(image.bounds(), Some((0x0F0, 0x104)));
+/// assert_eq!(image.len(), 5);
+///
+/// let (base, v) = image.to_contiguous(0xFF);
+/// assert_eq!(base, 0x0F0);
+/// assert_eq!(v.len(), 0x14);
+/// assert_eq!(v[1], 0xFF);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MemoryImage {
+    segments: Vec<MemorySegment>,
+    /// execution start address, from Intel HEX record 03 or 05, or S-record S7, S8 or S9
+    pub entry: Option<u32>,
+    /// data of the S-record S0 header, often a file or module name
+    pub header: Vec<u8>,
+}
+
+impl MemoryImage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the segments of bytes, sorted by address
+    #[inline]
+    pub fn segments(&self) -> &[MemorySegment] {
+        &self.segments
+    }
+
+    /// the number of bytes set
+    pub fn len(&self) -> usize {
+        self.segments.iter().map(|s| s.data.len()).sum()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// the first address set and the address just past the last, or None if the image is empty
+    pub fn bounds(&self) -> Option<(u32, u64)> {
+        let first = self.segments.first()?;
+        let last = self.segments.last()?;
+        Some((first.address, last.end()))
+    }
+
+    /// the gaps between segments, each as its first address and the address just past its last
+    pub fn gaps(&self) -> Vec<(u32, u32)> {
+        self.segments
+            .windows(2)
+            .map(|w| (w[0].end() as u32, w[1].address))
+            .collect()
+    }
+
+    // index of the last segment starting at or before address
+    fn segment_before(&self, address: u32) -> Option<usize> {
+        self.segments
+            .partition_point(|s| s.address <= address)
+            .checked_sub(1)
+    }
+
+    /// the byte at an address, or None if it is not set
+    pub fn get(&self, address: u32) -> Option<u8> {
+        let s = &self.segments[self.segment_before(address)?];
+        s.data.get((address - s.address) as usize).copied()
+    }
+
+    /// `len` bytes from an address, or None unless all of them are set
+    pub fn read(&self, address: u32, len: usize) -> Option<&[u8]> {
+        let s = &self.segments[self.segment_before(address)?];
+        let start = (address - s.address) as usize;
+        s.data.get(start..start.checked_add(len)?)
+    }
+
+    /// sets bytes from an address on, merging them with the segments they touch. fails with
+    /// [`HexFileErrorKind::Overlap`] and the first address already set, or with
+    /// [`HexFileErrorKind::AddressOverflow`] if the bytes run past the 32 bit address space,
+    /// leaving the image unchanged. errors have a line of 0.
+    pub fn insert(&mut self, address: u32, data: &[u8]) -> Result<(), HexFileError> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let end = address as u64 + data.len() as u64;
+        if end > 1 << 32 {
+            return Err(HexFileError::new(HexFileErrorKind::AddressOverflow, 0));
+        }
+        // files are usually in address order, so most data goes on the end
+        let i = self.segments.partition_point(|s| s.address < address);
+        if let Some(prev) = i.checked_sub(1).map(|p| &self.segments[p])
+            && prev.end() > address as u64
+        {
+            return Err(HexFileError::new(HexFileErrorKind::Overlap(address), 0));
+        }
+        if let Some(next) = self.segments.get(i)
+            && (next.address as u64) < end
+        {
+            return Err(HexFileError::new(
+                HexFileErrorKind::Overlap(next.address),
+                0,
+            ));
+        }
+
+        let joins_prev = i > 0 && self.segments[i - 1].end() == address as u64;
+        let joins_next = self
+            .segments
+            .get(i)
+            .is_some_and(|n| n.address as u64 == end);
+        match (joins_prev, joins_next) {
+            (true, true) => {
+                let next = self.segments.remove(i);
+                let prev = &mut self.segments[i - 1].data;
+                prev.extend_from_slice(data);
+                prev.extend_from_slice(&next.data);
+            }
+            (true, false) => self.segments[i - 1].data.extend_from_slice(data),
+            (false, true) => {
+                let next = &mut self.segments[i];
+                next.address = address;
+                next.data.splice(0..0, data.iter().copied());
+            }
+            (false, false) => self.segments.insert(
+                i,
+                MemorySegment {
+                    address,
+                    data: data.to_vec(),
+                },
+            ),
+        }
+        Ok(())
+    }
+
+    /// the bytes from the first address set to the last, with gaps filled with `fill`, and the
+    /// first address. an image with far apart segments makes a large vec.
+    pub fn to_contiguous(&self, fill: u8) -> (u32, Vec<u8>) {
+        let Some((first, end)) = self.bounds() else {
+            return (0, Vec::new());
+        };
+        let mut v = vec![fill; (end - first as u64) as usize];
+        for s in self.segments.iter() {
+            let at = (s.address - first) as usize;
+            v[at..at + s.data.len()].copy_from_slice(&s.data);
+        }
+        (first, v)
+    }
+
+    /// the image as Intel HEX, with 16 bytes per data record
+    pub fn to_ihex(&self) -> String {
+        self.to_ihex_with(16)
+    }
+
+    /// the image as Intel HEX, with up to `record_len` bytes, from 1 to 255, per data record.
+    ///
+    /// addresses above 64 KiB are written with extended linear address records (04), and the
+    /// entry address with a start linear address record (05). lines end with `\n`.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut image = MemoryImage::new();
+    /// image.insert(0xFFFE, b"Many").unwrap();
+    /// assert_eq!(
+    ///     image.to_ihex_with(2),
+    ///     ":02FFFE004D6153\n:020000040001F9\n:020000006E7917\n:00000001FF\n"
+    /// );
+    /// ```
+    pub fn to_ihex_with(&self, record_len: usize) -> String {
+        let record_len = record_len.clamp(1, 255);
+        let mut s = String::with_capacity(self.len() * 2 + self.len() / record_len * 12 + 32);
+        let mut upper = 0u32;
+        for seg in self.segments.iter() {
+            let mut address = seg.address;
+            let mut data = seg.data.as_slice();
+            while !data.is_empty() {
+                if address >> 16 != upper {
+                    upper = address >> 16;
+                    ihex_record(&mut s, 0x04, 0, &(upper as u16).to_be_bytes());
+                }
+                // a record does not cross a 64 KiB boundary, where the offset would wrap
+                let room = 0x10000 - (address & 0xFFFF) as usize;
+                let n = data.len().min(record_len).min(room);
+                ihex_record(&mut s, 0x00, address as u16, &data[..n]);
+                address = address.wrapping_add(n as u32);
+                data = &data[n..];
+            }
+        }
+        if let Some(entry) = self.entry {
+            ihex_record(&mut s, 0x05, 0, &entry.to_be_bytes());
+        }
+        ihex_record(&mut s, 0x01, 0, &[]);
+        s
+    }
+
+    /// the image as Motorola S-records, with 16 bytes per data record
+    pub fn to_srec(&self) -> String {
+        self.to_srec_with(16)
+    }
+
+    /// the image as Motorola S-records, with up to `record_len` bytes per data record, from 1 to
+    /// as many as fit in a record.
+    ///
+    /// data records use the shortest address that holds every address in the image and the entry:
+    /// S1 and S9 for 16 bits, S2 and S8 for 24 bits, or S3 and S7 for 32 bits. an S0 header is
+    /// written if the header is not empty, cut to the 252 bytes that fit in one record, and an S5
+    /// or S6 record count follows the data. the terminator holds the entry, or 0 if there is none.
+    /// lines end with `\n`.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let mut image = MemoryImage::new();
+    /// image.header = b"HDR".to_vec();
+    /// image.insert(0x1000, b"Many").unwrap();
+    /// assert_eq!(
+    ///     image.to_srec(),
+    ///     "S00600004844521B\nS10710004D616E7953\nS5030001FB\nS9030000FC\n"
+    /// );
+    /// ```
+    pub fn to_srec_with(&self, record_len: usize) -> String {
+        let top = self.bounds().map_or(0, |(_, end)| end.saturating_sub(1));
+        let top = top.max(self.entry.unwrap_or(0) as u64);
+        let (data_type, address_len) = match top {
+            0..=0xFFFF => (1, 2),
+            0x10000..=0xFF_FFFF => (2, 3),
+            _ => (3, 4),
+        };
+        let record_len = record_len.clamp(1, 254 - address_len);
+        let mut s = String::with_capacity(self.len() * 2 + self.len() / record_len * 16 + 64);
+        if !self.header.is_empty() {
+            let header = &self.header[..self.header.len().min(252)];
+            srec_record(&mut s, 0, 0, 2, header);
+        }
+        let mut count = 0u32;
+        for seg in self.segments.iter() {
+            let mut address = seg.address;
+            for chunk in seg.data.chunks(record_len) {
+                srec_record(&mut s, data_type, address, address_len, chunk);
+                address = address.wrapping_add(chunk.len() as u32);
+                count += 1;
+            }
+        }
+        match count {
+            0..=0xFFFF => srec_record(&mut s, 5, count, 2, &[]),
+            0x10000..=0xFF_FFFF => srec_record(&mut s, 6, count, 3, &[]),
+            _ => {}
+        }
+        srec_record(
+            &mut s,
+            10 - data_type,
+            self.entry.unwrap_or(0),
+            address_len,
+            &[],
+        );
+        s
+    }
+}
+
+// writes one Intel HEX record
+fn ihex_record(s: &mut String, record_type: u8, offset: u16, data: &[u8]) {
+    let mut bytes = Vec::with_capacity(data.len() + 5);
+    bytes.push(data.len() as u8);
+    bytes.extend_from_slice(&offset.to_be_bytes());
+    bytes.push(record_type);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |a, b| a.wrapping_add(*b));
+    bytes.push(sum.wrapping_neg());
+    s.push(':');
+    s.push_str(&u8_array_to_hex(&bytes));
+    s.push('\n');
+}
+
+// writes one S-record, with an address of address_len bytes
+fn srec_record(s: &mut String, record_type: u8, address: u32, address_len: usize, data: &[u8]) {
+    let mut bytes = Vec::with_capacity(data.len() + 6);
+    bytes.push((address_len + data.len() + 1) as u8);
+    bytes.extend_from_slice(&address.to_be_bytes()[4 - address_len..]);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |a, b| a.wrapping_add(*b));
+    bytes.push(!sum);
+    s.push('S');
+    s.push((b'0' + record_type) as char);
+    s.push_str(&u8_array_to_hex(&bytes));
+    s.push('\n');
+}
+
+/// kind of problem found while reading an Intel HEX or S-record file, or inserting into a
+/// [`MemoryImage`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexFileErrorKind {
+    /// a line that does not start with `:` (Intel HEX) or `S` (S-record)
+    InvalidStart,
+    /// the record is not valid hex. the error index is the offset in the line.
+    InvalidHex(HexDecodeError),
+    /// the byte count does not match the length of the record, or the record is the wrong length
+    /// for its type
+    InvalidLength,
+    /// the checksum does not match the record
+    Checksum,
+    /// a record type that does not exist, such as Intel HEX 06 or S4
+    InvalidType,
+    /// data at an address that is already set
+    Overlap(u32),
+    /// data past the end of the 32 bit address space
+    AddressOverflow,
+    /// the S5 or S6 record count does not match the number of data records before it
+    RecordCount,
+    /// the input ended without an Intel HEX end of file record (01)
+    MissingEnd,
+    /// a record after the Intel HEX end of file record or the S-record terminator
+    TrailingData,
+}
+
+/// error returned when reading an Intel HEX or S-record file, with the kind of problem and the
+/// (1 based) line it was found on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexFileError {
+    pub kind: HexFileErrorKind,
+    pub line: usize,
+}
+
+impl HexFileError {
+    pub const fn new(kind: HexFileErrorKind, line: usize) -> Self {
+        Self { kind, line }
+    }
+}
+
+impl std::fmt::Display for HexFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            HexFileErrorKind::InvalidStart => write!(f, "not a record at line {}", self.line),
+            HexFileErrorKind::InvalidHex(e) => write!(f, "{} (line {})", e, self.line),
+            HexFileErrorKind::InvalidLength => {
+                write!(f, "invalid record length at line {}", self.line)
+            }
+            HexFileErrorKind::Checksum => write!(f, "checksum mismatch at line {}", self.line),
+            HexFileErrorKind::InvalidType => {
+                write!(f, "invalid record type at line {}", self.line)
+            }
+            HexFileErrorKind::Overlap(a) => {
+                write!(f, "address {:#010X} already set at line {}", a, self.line)
+            }
+            HexFileErrorKind::AddressOverflow => {
+                write!(f, "address past 4 GiB at line {}", self.line)
+            }
+            HexFileErrorKind::RecordCount => {
+                write!(f, "record count mismatch at line {}", self.line)
+            }
+            HexFileErrorKind::MissingEnd => write!(f, "no end of file record"),
+            HexFileErrorKind::TrailingData => {
+                write!(f, "record after the end at line {}", self.line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HexFileError {}
+
+// the bytes of a record, after its `skip` leading chars, with the byte count checked against the
+// length. count_extra is the number of bytes not included in the count.
+fn record_bytes(
+    line: &str,
+    skip: usize,
+    count_extra: usize,
+    line_no: usize,
+) -> Result<Vec<u8>, HexFileError> {
+    let bytes = hex_str_to_u8_vec_strict(&line[skip..]).map_err(|e| {
+        let e = HexDecodeError::new(e.kind, e.index + skip);
+        HexFileError::new(HexFileErrorKind::InvalidHex(e), line_no)
+    })?;
+    if bytes.len() < 2 || bytes.len() != bytes[0] as usize + count_extra {
+        return Err(HexFileError::new(HexFileErrorKind::InvalidLength, line_no));
+    }
+    Ok(bytes)
+}
+
+///
+/// reads an Intel HEX file into a [`MemoryImage`].
+///
+/// all record types are read: data (00), end of file (01), extended segment address (02), start
+/// segment address (03), extended linear address (04) and start linear address (05). a start
+/// segment address CS:IP is kept as `CS << 16 | IP`. as the spec says, under an extended segment
+/// address the offset of a data record wraps within its 64 KiB segment, while under an extended
+/// linear address the data runs on into the next 64 KiB.
+///
+/// blank lines are skipped, and lines may end with `\r\n`. anything else that is not a record,
+/// a bad checksum, data over data already read, or a missing end of file record is an error.
+/// ```
+/// # use cj_common::prelude::*;
+/// let s = ":020000021000EC\n:0400F0004D616E7977\n:00000001FF\n";
+/// let image = parse_ihex(s).unwrap();
+/// assert_eq!(image.read(0x100F0, 4), Some(&b"Many"[..]));
+///
+/// let e = parse_ihex(":0400F0004D616E7978\n:00000001FF\n").unwrap_err();
+/// assert_eq!(e, HexFileError::new(HexFileErrorKind::Checksum, 1));
+/// ```
+pub fn parse_ihex(s: &str) -> Result<MemoryImage, HexFileError> {
+    let mut image = MemoryImage::new();
+    let mut base = 0u32;
+    // whether base came from an extended segment address (02), whose offsets wrap
+    let mut segmented = false;
+    let mut ended = false;
+    let mut line_no = 0;
+    for (n, line) in s.lines().enumerate() {
+        line_no = n + 1;
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        if ended {
+            return Err(HexFileError::new(HexFileErrorKind::TrailingData, line_no));
+        }
+        if !line.starts_with(':') {
+            return Err(HexFileError::new(HexFileErrorKind::InvalidStart, line_no));
+        }
+        let bytes = record_bytes(line, 1, 5, line_no)?;
+        if bytes.iter().fold(0u8, |a, b| a.wrapping_add(*b)) != 0 {
+            return Err(HexFileError::new(HexFileErrorKind::Checksum, line_no));
+        }
+        let offset = u16::from_be_bytes([bytes[1], bytes[2]]);
+        let data = &bytes[4..bytes.len() - 1];
+        let err = |kind| Err(HexFileError::new(kind, line_no));
+        match (bytes[3], data.len()) {
+            (0x00, _) => {
+                let r = if segmented {
+                    // the part past the end of the segment wraps to its start
+                    let split = data.len().min(0x10000 - offset as usize);
+                    image
+                        .insert(base + offset as u32, &data[..split])
+                        .and_then(|_| image.insert(base, &data[split..]))
+                } else {
+                    image.insert(base + offset as u32, data)
+                };
+                r.map_err(|e| HexFileError::new(e.kind, line_no))?;
+            }
+            (0x01, 0) => ended = true,
+            (0x02, 2) => {
+                base = (u16::from_be_bytes([data[0], data[1]]) as u32) << 4;
+                segmented = true;
+            }
+            (0x04, 2) => {
+                base = (u16::from_be_bytes([data[0], data[1]]) as u32) << 16;
+                segmented = false;
+            }
+            (0x03 | 0x05, 4) => {
+                image.entry = Some(u32::from_be_bytes([data[0], data[1], data[2], data[3]]));
+            }
+            (0x01..=0x05, _) => return err(HexFileErrorKind::InvalidLength),
+            _ => return err(HexFileErrorKind::InvalidType),
+        }
+    }
+    if !ended {
+        return Err(HexFileError::new(HexFileErrorKind::MissingEnd, line_no));
+    }
+    Ok(image)
+}
+
+///
+/// reads a Motorola S-record file into a [`MemoryImage`].
+///
+/// all record types are read: the S0 header, data with 16, 24 or 32 bit addresses (S1, S2, S3),
+/// the S5 or S6 record count, which is checked, and the S7, S8 or S9 terminator holding the
+/// entry address. the terminator is optional.
+///
+/// blank lines are skipped, and lines may end with `\r\n`. anything else that is not a record,
+/// a bad checksum, or data over data already read is an error.
+/// ```
+/// # use cj_common::prelude::*;
+/// let s = "S00600004844521B\nS10710004D616E7953\nS5030001FB\nS9031000EC\n";
+/// let image = parse_srec(s).unwrap();
+/// assert_eq!(image.header.as_slice(), b"HDR");
+/// assert_eq!(image.read(0x1000, 4), Some(&b"Many"[..]));
+/// assert_eq!(image.entry, Some(0x1000));
+///
+/// let e = parse_srec("S10710004D616E7953\nS5030002FA\n").unwrap_err();
+/// assert_eq!(e, HexFileError::new(HexFileErrorKind::RecordCount, 2));
+/// ```
+pub fn parse_srec(s: &str) -> Result<MemoryImage, HexFileError> {
+    let mut image = MemoryImage::new();
+    let mut count = 0u32;
+    let mut ended = false;
+    for (n, line) in s.lines().enumerate() {
+        let line_no = n + 1;
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        if ended {
+            return Err(HexFileError::new(HexFileErrorKind::TrailingData, line_no));
+        }
+        let err = |kind| Err(HexFileError::new(kind, line_no));
+        let record_type = match line.as_bytes() {
+            [b'S', t @ b'0'..=b'9', ..] => t - b'0',
+            [b'S', ..] => return err(HexFileErrorKind::InvalidType),
+            _ => return err(HexFileErrorKind::InvalidStart),
+        };
+        let address_len = match record_type {
+            0 | 1 | 5 | 9 => 2,
+            2 | 6 | 8 => 3,
+            3 | 7 => 4,
+            _ => return err(HexFileErrorKind::InvalidType),
+        };
+        let bytes = record_bytes(line, 2, 1, line_no)?;
+        if bytes.iter().fold(0u8, |a, b| a.wrapping_add(*b)) != 0xFF {
+            return err(HexFileErrorKind::Checksum);
+        }
+        let body = &bytes[1..bytes.len() - 1];
+        if body.len() < address_len {
+            return err(HexFileErrorKind::InvalidLength);
+        }
+        let address = body[..address_len]
+            .iter()
+            .fold(0u32, |a, b| (a << 8) | *b as u32);
+        let data = &body[address_len..];
+        match record_type {
+            0 => image.header = data.to_vec(),
+            1..=3 => {
+                image
+                    .insert(address, data)
+                    .map_err(|e| HexFileError::new(e.kind, line_no))?;
+                count += 1;
+            }
+            _ if !data.is_empty() => return err(HexFileErrorKind::InvalidLength),
+            5 | 6 if address != count => return err(HexFileErrorKind::RecordCount),
+            5 | 6 => {}
+            _ => {
+                image.entry = Some(address);
+                ended = true;
+            }
+        }
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cj_binary::hex::HexDecodeErrorKind;
+
+    #[test]
+    fn test_memory_image() {
+        let mut image = MemoryImage::new();
+        assert_eq!(image.bounds(), None);
+        assert_eq!(image.to_contiguous(0), (0, vec![]));
+        image.insert(10, &[1, 2]).unwrap();
+        image.insert(20, &[5]).unwrap();
+        image.insert(14, &[3]).unwrap();
+        image.insert(0, &[]).unwrap();
+
+        let overlap = |a| Err(HexFileError::new(HexFileErrorKind::Overlap(a), 0));
+        assert_eq!(image.insert(11, &[9]), overlap(11));
+        assert_eq!(image.insert(8, &[9, 9, 9]), overlap(10));
+        assert_eq!(image.insert(13, &[9, 9]), overlap(14));
+        assert_eq!(image.insert(0, &[9; 100]), overlap(10));
+        assert_eq!(image.len(), 4);
+
+        // filling a gap exactly merges both sides
+        image.insert(12, &[2, 2]).unwrap();
+        image.insert(15, &[4, 4, 4, 4, 4]).unwrap();
+        assert_eq!(image.segments().len(), 1);
+        assert_eq!(image.segments()[0].address, 10);
+        assert_eq!(image.read(10, 11).unwrap().len(), 11);
+        assert_eq!(image.read(10, 12), None);
+        assert_eq!(image.read(9, 1), None);
+        image.insert(9, &[0]).unwrap();
+        assert_eq!(image.segments()[0].address, 9);
+        assert_eq!(image.get(9), Some(0));
+        assert_eq!(image.get(21), None);
+        assert!(image.gaps().is_empty());
+
+        image.insert(u32::MAX - 1, &[7, 7]).unwrap();
+        assert_eq!(image.bounds(), Some((9, 1 << 32)));
+        assert_eq!(image.gaps(), vec![(21, u32::MAX - 1)]);
+        assert_eq!(image.read(u32::MAX, 1), Some(&[7u8][..]));
+        let e = HexFileError::new(HexFileErrorKind::AddressOverflow, 0);
+        assert_eq!(image.insert(u32::MAX, &[1, 2]), Err(e));
+        assert_eq!(image.len(), 14);
+    }
+
+    fn sample() -> MemoryImage {
+        let mut image = MemoryImage::new();
+        let data = (0..600u32).map(|x| (x * 7) as u8).collect::<Vec<u8>>();
+        image.insert(0x0000_FF00, &data).unwrap();
+        image.insert(0x0002_0000, &data[..17]).unwrap();
+        image.insert(0x1234_5678, &data[..3]).unwrap();
+        image.entry = Some(0x0800_0000);
+        image.header = b"test image".to_vec();
+        image
+    }
+
+    #[test]
+    fn test_ihex() {
+        let image = sample();
+        for len in [1, 16, 32, 255, 1000] {
+            let s = image.to_ihex_with(len);
+            let mut read = parse_ihex(&s).unwrap();
+            assert_eq!(parse_ihex(&s.replace('\n', "\r\n")), Ok(read.clone()));
+            // Intel HEX has no header
+            assert!(read.header.is_empty());
+            read.header = image.header.clone();
+            assert_eq!(read, image);
+        }
+
+        // a segment address, and an offset that wraps within the segment
+        let s = ":02000002F0000C\n:04FFFE0001020304F5\n:0400000312345678E5\n:00000001FF\n";
+        let image = parse_ihex(s).unwrap();
+        assert_eq!(image.read(0xFFFFE, 2), Some(&[1u8, 2][..]));
+        assert_eq!(image.read(0xF0000, 2), Some(&[3u8, 4][..]));
+        assert_eq!(image.entry, Some(0x12345678));
+
+        // a linear address, where the data runs on into the next 64 KiB
+        let s = ":020000040001F9\n:04FFFE0001020304F5\n:00000001FF\n";
+        let image = parse_ihex(s).unwrap();
+        assert_eq!(image.segments().len(), 1);
+        assert_eq!(image.read(0x1FFFE, 4), Some(&[1u8, 2, 3, 4][..]));
+        let s = ":02000004FFFFFC\n:04FFFE0001020304F5\n:00000001FF\n";
+        let e = HexFileError::new(HexFileErrorKind::AddressOverflow, 2);
+        assert_eq!(parse_ihex(s), Err(e));
+
+        let err = |kind, line| Err(HexFileError::new(kind, line));
+        assert_eq!(parse_ihex(""), err(HexFileErrorKind::MissingEnd, 0));
+        assert_eq!(
+            parse_ihex(":00000001FF\n\n:00000001FF"),
+            err(HexFileErrorKind::TrailingData, 3)
+        );
+        assert_eq!(
+            parse_ihex("\n00000001FF"),
+            err(HexFileErrorKind::InvalidStart, 2)
+        );
+        assert_eq!(
+            parse_ihex(":00000006FA"),
+            err(HexFileErrorKind::InvalidType, 1)
+        );
+        assert_eq!(
+            parse_ihex(":0100000100FE"),
+            err(HexFileErrorKind::InvalidLength, 1)
+        );
+        assert_eq!(
+            parse_ihex(":0200000401F9"),
+            err(HexFileErrorKind::InvalidLength, 1)
+        );
+        assert_eq!(parse_ihex(":0000"), err(HexFileErrorKind::InvalidLength, 1));
+        let e = HexDecodeError::new(HexDecodeErrorKind::InvalidChar, 3);
+        assert_eq!(
+            parse_ihex(":00x00001FF"),
+            err(HexFileErrorKind::InvalidHex(e), 1)
+        );
+        let e = HexDecodeError::new(HexDecodeErrorKind::OddLength, 12);
+        assert_eq!(
+            parse_ihex(":00000001FF0"),
+            err(HexFileErrorKind::InvalidHex(e), 1)
+        );
+        assert_eq!(
+            parse_ihex(":0100000001FE\n:0100000002FD\n:00000001FF"),
+            err(HexFileErrorKind::Overlap(0), 2)
+        );
+    }
+
+    #[test]
+    fn test_srec() {
+        let image = sample();
+        for len in [1, 16, 32, 250, 1000] {
+            let s = image.to_srec_with(len);
+            assert!(s.lines().all(|l| l.starts_with("S0")
+                || l.starts_with("S3")
+                || l.starts_with("S5")
+                || l.starts_with("S7")));
+            assert_eq!(parse_srec(&s), Ok(image.clone()));
+            assert_eq!(parse_srec(&s.replace('\n', "\r\n")), Ok(image.clone()));
+        }
+
+        // the shortest addresses that fit
+        let mut image = MemoryImage::new();
+        image.insert(0xFFFF, &[1]).unwrap();
+        assert!(image.to_srec().starts_with("S1"));
+        image.entry = Some(0x10000);
+        assert!(image.to_srec().starts_with("S2"));
+        assert!(image.to_srec().ends_with("\nS804010000FA\n"));
+        image.entry = None;
+        let s = image.to_srec();
+        assert!(s.ends_with("\nS9030000FC\n"));
+        image.entry = Some(0);
+        assert_eq!(parse_srec(&s), Ok(image));
+        assert_eq!(parse_srec(""), Ok(MemoryImage::new()));
+
+        let err = |kind, line| Err(HexFileError::new(kind, line));
+        assert_eq!(
+            parse_srec("S9030000FC\nS9030000FC"),
+            err(HexFileErrorKind::TrailingData, 2)
+        );
+        assert_eq!(
+            parse_srec("X9030000FC"),
+            err(HexFileErrorKind::InvalidStart, 1)
+        );
+        assert_eq!(
+            parse_srec("S4030000FC"),
+            err(HexFileErrorKind::InvalidType, 1)
+        );
+        assert_eq!(
+            parse_srec("SX030000FC"),
+            err(HexFileErrorKind::InvalidType, 1)
+        );
+        assert_eq!(parse_srec("S9030000FD"), err(HexFileErrorKind::Checksum, 1));
+        assert_eq!(
+            parse_srec("S9040000FC"),
+            err(HexFileErrorKind::InvalidLength, 1)
+        );
+        assert_eq!(
+            parse_srec("S30401020304F1"),
+            err(HexFileErrorKind::InvalidLength, 1)
+        );
+        assert_eq!(
+            parse_srec("S904000001FA"),
+            err(HexFileErrorKind::InvalidLength, 1)
+        );
+        assert_eq!(
+            parse_srec("S30AFFFFFFFF0102030405EA"),
+            err(HexFileErrorKind::AddressOverflow, 1)
+        );
+
+        let s = format!("{}", HexFileError::new(HexFileErrorKind::Overlap(0x1F), 3));
+        assert_eq!(s.as_str(), "address 0x0000001F already set at line 3");
+    }
+}
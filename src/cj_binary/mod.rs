@@ -1,3 +1,13 @@
+pub mod b32;
+pub mod b58;
 pub mod b64;
+pub mod b85;
 pub mod bitbuf;
+pub mod ct;
+pub mod encoding;
 pub mod hex;
+pub mod hexfile;
+pub mod pem;
+pub mod radix;
+mod sha256;
+mod simd;
@@ -0,0 +1,609 @@
+//! structs, methods and traits for encoding integers and byte strings in any radix from 2 to 256,
+//! such as Base62 and Base36 for short IDs
+//!
+//! # Quick Start
+//!
+//!```
+//! use cj_common::prelude::*;
+//!
+//! // integers from i16 to u128
+//! assert_eq!(123456789u64.to_base62(), "8M0kX");
+//! assert_eq!(u64::from_base62("8M0kX"), Some(123456789));
+//! assert_eq!(123456789u64.to_base36(), "21i3v9");
+//!
+//! // fixed width, padded with the zero symbol
+//! assert_eq!(42u32.to_radix_padded(&RadixAlphabet::BASE62, 4), "000g");
+//! assert_eq!(42u32.to_radix_full(&RadixAlphabet::BASE62), "00000g");
+//!
+//! // byte strings, where leading zero bytes become leading zero symbols
+//! let s = bytes_to_base62(&[0, 1, 2, 3]);
+//! assert_eq!(s.as_str(), "0HBL");
+//! assert_eq!(base62_to_bytes(&s), Some(vec![0, 1, 2, 3]));
+//! ```
+
+// Base62 table, in ASCII order so fixed width strings sort like the numbers they hold.
+const RADIX_BASE62_TABLE: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+// Base36 table, decoded case-insensitively.
+const RADIX_BASE36_TABLE: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+// marks an ASCII char that is not part of an alphabet in RadixAlphabet's decode table
+const RADIX_NO_ORD: u16 = 0xFFFF;
+
+///
+/// an alphabet of 2 to 256 unique chars, where the index of each char is its value. the alphabet
+/// also does the encoding and decoding, as there are no other options.
+///
+/// [`RadixAlphabet::BASE2`], [`RadixAlphabet::BASE8`], [`RadixAlphabet::BASE10`],
+/// [`RadixAlphabet::BASE16`], [`RadixAlphabet::BASE36`] and [`RadixAlphabet::BASE62`] are
+/// provided. BASE16 and BASE36 write lowercase and read either case.
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// let a = RadixAlphabet::new("01234567").unwrap();
+/// assert_eq!(a.radix(), 8);
+/// assert_eq!(511u16.to_radix(&a), "777");
+///
+/// // chars do not have to be ASCII
+/// let a = RadixAlphabet::new("○●").unwrap();
+/// assert_eq!(5u8 as u16, u16::from_radix("●○●", &a).unwrap());
+///
+/// // too short, and duplicate chars
+/// assert!(RadixAlphabet::new("0").is_none());
+/// assert!(RadixAlphabet::new("0120").is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RadixAlphabet {
+    encode: [char; 256],
+    // values of ASCII chars, other chars are looked up in encode
+    decode: [u16; 128],
+    radix: u16,
+}
+
+impl RadixAlphabet {
+    /// binary, `0` and `1`
+    pub const BASE2: RadixAlphabet = RadixAlphabet::from_ascii(b"01");
+    /// octal, `0` to `7`
+    pub const BASE8: RadixAlphabet = RadixAlphabet::from_ascii(b"01234567");
+    /// decimal, `0` to `9`
+    pub const BASE10: RadixAlphabet = RadixAlphabet::from_ascii(b"0123456789");
+    /// lowercase hex, reading either case
+    pub const BASE16: RadixAlphabet =
+        RadixAlphabet::from_ascii(b"0123456789abcdef").case_insensitive();
+    /// `0` to `9` then `a` to `z`, reading either case
+    pub const BASE36: RadixAlphabet =
+        RadixAlphabet::from_ascii(RADIX_BASE36_TABLE).case_insensitive();
+    /// `0` to `9`, `A` to `Z` then `a` to `z`
+    pub const BASE62: RadixAlphabet = RadixAlphabet::from_ascii(RADIX_BASE62_TABLE);
+
+    // builds an alphabet from unique ASCII bytes, panicking (at compile time) on a bad table
+    const fn from_ascii(table: &[u8]) -> Self {
+        assert!(
+            table.len() >= 2 && table.len() <= 128,
+            "invalid radix table"
+        );
+        let mut encode = ['\0'; 256];
+        let mut decode = [RADIX_NO_ORD; 128];
+        let mut i = 0;
+        while i < table.len() {
+            let b = table[i];
+            assert!(
+                b.is_ascii_graphic() && decode[b as usize] == RADIX_NO_ORD,
+                "invalid radix table"
+            );
+            encode[i] = b as char;
+            decode[b as usize] = i as u16;
+            i += 1;
+        }
+        Self {
+            encode,
+            decode,
+            radix: table.len() as u16,
+        }
+    }
+
+    // lets the other case of each ASCII letter decode to the same value, where it is unused
+    const fn case_insensitive(mut self) -> Self {
+        let mut b = 0u8;
+        while b < 128 {
+            let o = self.decode[b as usize];
+            if o != RADIX_NO_ORD && b.is_ascii_alphabetic() {
+                let other = (b ^ 0x20) as usize;
+                if self.decode[other] == RADIX_NO_ORD {
+                    self.decode[other] = o;
+                }
+            }
+            b += 1;
+        }
+        self
+    }
+
+    /// builds an alphabet from 2 to 256 unique, non-whitespace chars, or None if the chars
+    /// do not make one
+    pub fn new(symbols: &str) -> Option<Self> {
+        let mut encode = ['\0'; 256];
+        let mut decode = [RADIX_NO_ORD; 128];
+        let mut radix = 0usize;
+        for c in symbols.chars() {
+            if radix == 256 || c.is_whitespace() || c.is_control() || encode[..radix].contains(&c) {
+                return None;
+            }
+            if c.is_ascii() {
+                decode[c as usize] = radix as u16;
+            }
+            encode[radix] = c;
+            radix += 1;
+        }
+        if radix < 2 {
+            return None;
+        }
+        Some(Self {
+            encode,
+            decode,
+            radix: radix as u16,
+        })
+    }
+
+    /// the number of chars in the alphabet
+    #[inline(always)]
+    pub const fn radix(&self) -> usize {
+        self.radix as usize
+    }
+
+    /// the char for a value below the radix
+    #[inline(always)]
+    pub const fn symbol(&self, ord: usize) -> char {
+        self.encode[ord % self.radix as usize]
+    }
+
+    /// the value of a char, or None if it is not part of the alphabet
+    #[inline]
+    pub fn char_to_ord(&self, c: char) -> Option<usize> {
+        if c.is_ascii() {
+            return match self.decode[c as usize] {
+                RADIX_NO_ORD => None,
+                o => Some(o as usize),
+            };
+        }
+        self.encode[..self.radix()].iter().position(|s| *s == c)
+    }
+
+    /// the number of chars needed for the largest value of `bits` bits, which is the width used
+    /// by [`Radix::to_radix_full`]
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(RadixAlphabet::BASE62.max_digits(64), 11);
+    /// assert_eq!(RadixAlphabet::BASE10.max_digits(32), 10);
+    /// assert_eq!(RadixAlphabet::BASE2.max_digits(8), 8);
+    /// ```
+    pub const fn max_digits(&self, bits: u32) -> usize {
+        if bits == 0 {
+            return 0;
+        }
+        let r = self.radix as u128;
+        let mut n = if bits >= 128 {
+            u128::MAX
+        } else {
+            (1u128 << bits) - 1
+        };
+        let mut digits = 0;
+        while n > 0 {
+            n /= r;
+            digits += 1;
+        }
+        digits
+    }
+
+    /// converts a slice of bytes, read as one big-endian number, into a String. each leading zero
+    /// byte becomes a leading zero symbol, so they survive a round trip.
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(RadixAlphabet::BASE10.encode(&[1, 0]), "256");
+    /// assert_eq!(RadixAlphabet::BASE10.encode(&[0, 0, 255]), "00255");
+    /// assert_eq!(RadixAlphabet::BASE62.encode(b""), "");
+    /// ```
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let r = self.radix as u32;
+        let zeros = bytes.iter().take_while(|b| **b == 0).count();
+        // the rest of the bytes as a big number, in base r digits, least significant first
+        let mut digits: Vec<u32> = Vec::with_capacity((bytes.len() - zeros) * 8 + 1);
+        for b in &bytes[zeros..] {
+            let mut carry = *b as u32;
+            for d in digits.iter_mut() {
+                let x = (*d << 8) | carry;
+                *d = x % r;
+                carry = x / r;
+            }
+            while carry > 0 {
+                digits.push(carry % r);
+                carry /= r;
+            }
+        }
+
+        let mut s = String::with_capacity(zeros + digits.len());
+        s.extend(std::iter::repeat_n(self.encode[0], zeros));
+        s.extend(digits.iter().rev().map(|d| self.encode[*d as usize]));
+        s
+    }
+
+    /// converts a str encoded with [`RadixAlphabet::encode`] back into a vec of bytes, or None if
+    /// any char is not part of the alphabet
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!(RadixAlphabet::BASE10.decode("00255"), Some(vec![0, 0, 255]));
+    /// assert_eq!(RadixAlphabet::BASE36.decode("A0"), RadixAlphabet::BASE36.decode("a0"));
+    /// assert_eq!(RadixAlphabet::BASE10.decode("12a"), None);
+    /// ```
+    pub fn decode(&self, data: &str) -> Option<Vec<u8>> {
+        let r = self.radix as u32;
+        let mut zeros = 0;
+        let mut leading = true;
+        // the number in base 256 digits, least significant first
+        let mut bytes: Vec<u8> = Vec::with_capacity(data.len());
+        for c in data.chars() {
+            let o = self.char_to_ord(c)? as u32;
+            if leading && o == 0 {
+                zeros += 1;
+                continue;
+            }
+            leading = false;
+            let mut carry = o;
+            for b in bytes.iter_mut() {
+                let x = (*b as u32) * r + carry;
+                *b = x as u8;
+                carry = x >> 8;
+            }
+            while carry > 0 {
+                bytes.push(carry as u8);
+                carry >>= 8;
+            }
+        }
+        bytes.extend(std::iter::repeat_n(0, zeros));
+        bytes.reverse();
+        Some(bytes)
+    }
+
+    /// converts a str encoded with [`RadixAlphabet::encode`] back into a vec of bytes, failing with
+    /// the location of the first char that is not part of the alphabet
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// let e = RadixAlphabet::BASE10.try_decode("12a").unwrap_err();
+    /// assert_eq!(e, RadixDecodeError { index: 2 });
+    /// ```
+    pub fn try_decode(&self, data: &str) -> Result<Vec<u8>, RadixDecodeError> {
+        self.decode(data).ok_or_else(|| {
+            let index = data
+                .char_indices()
+                .find(|(_, c)| self.char_to_ord(*c).is_none())
+                .map_or(data.len(), |(i, _)| i);
+            RadixDecodeError { index }
+        })
+    }
+
+    // writes n with at least width digits
+    fn encode_u128(&self, mut n: u128, width: usize) -> String {
+        let r = self.radix as u128;
+        let mut digits = Vec::with_capacity(width.max(16));
+        while n > 0 {
+            digits.push(self.encode[(n % r) as usize]);
+            n /= r;
+        }
+        if digits.len() < width {
+            digits.resize(width, self.encode[0]);
+        }
+        if digits.is_empty() {
+            digits.push(self.encode[0]);
+        }
+        digits.iter().rev().collect()
+    }
+
+    // reads a number no larger than max, or None if it is empty, too large or has a char outside
+    // the alphabet
+    fn decode_u128(&self, data: &str, max: u128) -> Option<u128> {
+        let r = self.radix as u128;
+        let mut n = 0u128;
+        let mut any = false;
+        for c in data.chars() {
+            let o = self.char_to_ord(c)? as u128;
+            n = n.checked_mul(r)?.checked_add(o)?;
+            any = true;
+        }
+        if !any || n > max {
+            return None;
+        }
+        Some(n)
+    }
+}
+
+impl Default for RadixAlphabet {
+    fn default() -> Self {
+        Self::BASE62
+    }
+}
+
+/// error returned by [`RadixAlphabet::try_decode`], with the byte offset of the first char that is
+/// not part of the alphabet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RadixDecodeError {
+    pub index: usize,
+}
+
+impl std::fmt::Display for RadixDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid radix symbol at byte {}", self.index)
+    }
+}
+
+impl std::error::Error for RadixDecodeError {}
+
+///
+/// converts a slice of bytes into base62, keeping leading zero bytes as leading `0`s
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_base62;
+///
+/// assert_eq!(bytes_to_base62(b"Hello").as_str(), "5TP3P3v");
+/// ```
+pub fn bytes_to_base62(bytes: &[u8]) -> String {
+    RadixAlphabet::BASE62.encode(bytes)
+}
+
+///
+/// converts a base62 str into a vec of bytes, or None if any char is not base62
+///
+/// ```
+///  # use crate::cj_common::prelude::base62_to_bytes;
+///
+/// assert_eq!(base62_to_bytes("5TP3P3v"), Some(b"Hello".to_vec()));
+/// ```
+pub fn base62_to_bytes(data: &str) -> Option<Vec<u8>> {
+    RadixAlphabet::BASE62.decode(data)
+}
+
+///
+/// converts a slice of bytes into lowercase base36, keeping leading zero bytes as leading `0`s
+///
+/// ```
+///  # use crate::cj_common::prelude::bytes_to_base36;
+///
+/// assert_eq!(bytes_to_base36(b"Hello").as_str(), "3yud78mn");
+/// ```
+pub fn bytes_to_base36(bytes: &[u8]) -> String {
+    RadixAlphabet::BASE36.encode(bytes)
+}
+
+///
+/// converts a base36 str, in either case, into a vec of bytes, or None if any char is not base36
+///
+/// ```
+///  # use crate::cj_common::prelude::base36_to_bytes;
+///
+/// assert_eq!(base36_to_bytes("3YUD78MN"), Some(b"Hello".to_vec()));
+/// ```
+pub fn base36_to_bytes(data: &str) -> Option<Vec<u8>> {
+    RadixAlphabet::BASE36.decode(data)
+}
+
+pub trait CjToRadix {
+    fn to_base62_string(&self) -> String;
+    fn to_base36_string(&self) -> String;
+    fn to_radix_string(&self, alphabet: &RadixAlphabet) -> String;
+}
+
+impl<T: AsRef<[u8]> + ?Sized> CjToRadix for T {
+    /// converts anything holding bytes (`&str`, `String`, `Vec<u8>`, `[u8; N]`, ...) to base62
+    /// ```
+    /// # use cj_common::prelude::CjToRadix;
+    /// assert_eq!("Hello".to_base62_string().as_str(), "5TP3P3v");
+    /// ```
+    fn to_base62_string(&self) -> String {
+        bytes_to_base62(self.as_ref())
+    }
+    /// converts anything holding bytes to lowercase base36
+    /// ```
+    /// # use cj_common::prelude::CjToRadix;
+    /// assert_eq!(vec![0u8, 255].to_base36_string().as_str(), "073");
+    /// ```
+    fn to_base36_string(&self) -> String {
+        bytes_to_base36(self.as_ref())
+    }
+    /// converts anything holding bytes to a String using the given alphabet
+    /// ```
+    /// # use cj_common::prelude::*;
+    /// assert_eq!([1u8, 0].to_radix_string(&RadixAlphabet::BASE2).as_str(), "100000000");
+    /// ```
+    fn to_radix_string(&self, alphabet: &RadixAlphabet) -> String {
+        alphabet.encode(self.as_ref())
+    }
+}
+
+///
+/// radix conversion for the integers from i16 to u128. signed values are written as their two's
+/// complement bits, the same as [`crate::cj_binary::hex::Hex`].
+///
+/// ```
+/// # use cj_common::prelude::*;
+/// assert_eq!(255u8 as u16, u16::from_base36("73").unwrap());
+/// assert_eq!((-1i16).to_base62(), "H31");
+/// assert_eq!(i16::from_base62("H31"), Some(-1));
+///
+/// // too large for the type
+/// assert_eq!(u16::from_base62("H32"), None);
+/// ```
+pub trait Radix: Sized {
+    /// to the fewest chars of the given alphabet
+    fn to_radix(self, alphabet: &RadixAlphabet) -> String;
+    /// to at least `width` chars of the given alphabet, left padded with its zero symbol.
+    /// values needing more chars are not truncated.
+    fn to_radix_padded(self, alphabet: &RadixAlphabet, width: usize) -> String;
+    /// to as many chars of the given alphabet as the largest value of the type needs, so every
+    /// value of the type has the same width
+    fn to_radix_full(self, alphabet: &RadixAlphabet) -> String;
+    /// from chars of the given alphabet, with or without padding. None if the str is empty, has a
+    /// char outside the alphabet or the value does not fit the type.
+    fn from_radix(value: &str, alphabet: &RadixAlphabet) -> Option<Self>;
+    /// to the fewest base62 chars
+    fn to_base62(self) -> String {
+        self.to_radix(&RadixAlphabet::BASE62)
+    }
+    /// from base62
+    fn from_base62(value: &str) -> Option<Self> {
+        Self::from_radix(value, &RadixAlphabet::BASE62)
+    }
+    /// to the fewest lowercase base36 chars
+    fn to_base36(self) -> String {
+        self.to_radix(&RadixAlphabet::BASE36)
+    }
+    /// from base36, in either case
+    fn from_base36(value: &str) -> Option<Self> {
+        Self::from_radix(value, &RadixAlphabet::BASE36)
+    }
+}
+
+macro_rules! impl_radix {
+    ($($t:ty => $u:ty),* $(,)?) => {
+        $(
+            impl Radix for $t {
+                fn to_radix(self, alphabet: &RadixAlphabet) -> String {
+                    alphabet.encode_u128(self as $u as u128, 1)
+                }
+
+                fn to_radix_padded(self, alphabet: &RadixAlphabet, width: usize) -> String {
+                    alphabet.encode_u128(self as $u as u128, width)
+                }
+
+                fn to_radix_full(self, alphabet: &RadixAlphabet) -> String {
+                    let width = alphabet.max_digits(<$u>::BITS);
+                    alphabet.encode_u128(self as $u as u128, width)
+                }
+
+                fn from_radix(value: &str, alphabet: &RadixAlphabet) -> Option<Self> {
+                    alphabet
+                        .decode_u128(value, <$u>::MAX as u128)
+                        .map(|n| n as $u as $t)
+                }
+            }
+        )*
+    };
+}
+
+impl_radix!(
+    i16 => u16,
+    u16 => u16,
+    i32 => u32,
+    u32 => u32,
+    i64 => u64,
+    u64 => u64,
+    i128 => u128,
+    u128 => u128,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integers() {
+        let alphabets = [
+            RadixAlphabet::BASE2,
+            RadixAlphabet::BASE8,
+            RadixAlphabet::BASE10,
+            RadixAlphabet::BASE16,
+            RadixAlphabet::BASE36,
+            RadixAlphabet::BASE62,
+            RadixAlphabet::new(
+                &(0..256u32)
+                    .map(|x| char::from_u32(0x100 + x).unwrap())
+                    .collect::<String>(),
+            )
+            .unwrap(),
+        ];
+        for a in &alphabets {
+            for n in [0u64, 1, 61, 62, 12345678901234, u64::MAX] {
+                let s = n.to_radix(a);
+                assert_eq!(u64::from_radix(&s, a), Some(n));
+                let s = n.to_radix_full(a);
+                assert_eq!(s.chars().count(), a.max_digits(64));
+                assert_eq!(u64::from_radix(&s, a), Some(n));
+            }
+            for n in [i128::MIN, -1, 0, 1, i128::MAX] {
+                assert_eq!(i128::from_radix(&n.to_radix(a), a), Some(n));
+            }
+            assert_eq!(u128::from_radix(&u128::MAX.to_radix(a), a), Some(u128::MAX));
+        }
+
+        assert_eq!(0u64.to_base62(), "0");
+        assert_eq!(u64::MAX.to_base62(), "LygHa16AHYF");
+        assert_eq!(
+            u64::MAX.to_radix(&RadixAlphabet::BASE10),
+            u64::MAX.to_string()
+        );
+        assert_eq!(
+            u128::MAX.to_radix(&RadixAlphabet::BASE10),
+            u128::MAX.to_string()
+        );
+        assert_eq!(0xDEADu16.to_radix(&RadixAlphabet::BASE16), "dead");
+        assert_eq!(
+            u16::from_radix("DEAD", &RadixAlphabet::BASE16),
+            Some(0xDEAD)
+        );
+        assert_eq!(7u32.to_radix_padded(&RadixAlphabet::BASE2, 6), "000111");
+        assert_eq!(255u32.to_radix_padded(&RadixAlphabet::BASE2, 4), "11111111");
+        assert_eq!(0i32.to_radix_full(&RadixAlphabet::BASE16), "00000000");
+
+        // ordering is kept by fixed width base62
+        let mut v = [5u64, 61, 62, 3843, 3844, 1 << 40];
+        let mut s = v.map(|n| n.to_radix_full(&RadixAlphabet::BASE62));
+        v.sort();
+        s.sort();
+        assert_eq!(s.map(|s| u64::from_base62(&s).unwrap()), v);
+
+        assert_eq!(u64::from_base62(""), None);
+        assert_eq!(u64::from_base62("a-b"), None);
+        assert_eq!(u64::from_base62("LygHa16AHYG"), None);
+        assert_eq!(u128::from_base62(&"z".repeat(30)), None);
+        assert_eq!(i16::from_base36("1ekf"), Some(-1));
+        assert_eq!(i16::from_base36("1ekg"), None);
+    }
+
+    #[test]
+    fn test_bytes() {
+        let data = (0..40u32).map(|x| (x * 97 + 11) as u8).collect::<Vec<u8>>();
+        let alphabets = [
+            RadixAlphabet::BASE2,
+            RadixAlphabet::BASE10,
+            RadixAlphabet::BASE36,
+            RadixAlphabet::BASE62,
+            RadixAlphabet::new(
+                &(0..256u32)
+                    .map(|x| char::from_u32(0x100 + x).unwrap())
+                    .collect::<String>(),
+            )
+            .unwrap(),
+        ];
+        for a in &alphabets {
+            for n in 0..data.len() {
+                let s = a.encode(&data[..n]);
+                assert_eq!(a.decode(&s).unwrap(), &data[..n]);
+            }
+            let s = a.encode(&[0, 0, 1, 0]);
+            assert_eq!(a.decode(&s).unwrap(), [0, 0, 1, 0]);
+        }
+        // base 256 over U+0100.. is one char per byte
+        assert_eq!(alphabets[4].encode(&data).chars().count(), data.len());
+
+        assert_eq!(bytes_to_base62(&[0, 0]), "00");
+        assert_eq!(base62_to_bytes("00"), Some(vec![0, 0]));
+        assert_eq!(base62_to_bytes(""), Some(vec![]));
+        assert_eq!(base62_to_bytes("5TP3P3v!"), None);
+        assert_eq!(bytes_to_base36(&[0xFF; 8]), "3w5e11264sgsf");
+        assert_eq!(base36_to_bytes("3W5E11264SGSF"), Some(vec![0xFF; 8]));
+
+        assert!(RadixAlphabet::new(&"ab".repeat(2)).is_none());
+        assert!(RadixAlphabet::new("a b").is_none());
+        let many = (0..257u32)
+            .map(|x| char::from_u32(0x100 + x).unwrap())
+            .collect::<String>();
+        assert!(RadixAlphabet::new(&many).is_none());
+    }
+}
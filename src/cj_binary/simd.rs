@@ -0,0 +1,878 @@
+//! SIMD fast paths for the b64 and hex codecs, picked at runtime from what the cpu supports.
+//!
+//! each function handles the run of whole blocks at the start of its input that it can, appends the
+//! result to `out` and returns how much of the input it used. the scalar code does the rest, so any
+//! input the fast path does not like (padding, whitespace, invalid chars) is left to it, and the
+//! output is the same either way.
+
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+// A-Z, a-z, 0-9. the x86 Base64 paths work out symbols by range, so only alphabets starting with
+// these 62 symbols (standard, url safe and friends) can use them.
+#[cfg(target_arch = "x86_64")]
+const B64_RANGE_PREFIX: &[u8; 62] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// instruction set used for the fast paths. only [`SimdLevel::detect`] and
+/// [`SimdLevel::supported`] may make one, as the level is trusted to be supported by the cpu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SimdLevel {
+    Scalar,
+    #[cfg(target_arch = "x86_64")]
+    Sse41,
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+    #[cfg(target_arch = "aarch64")]
+    Neon,
+}
+
+impl SimdLevel {
+    /// best level the cpu supports. std caches the feature checks, so this is cheap.
+    #[inline]
+    pub(crate) fn detect() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") {
+                return SimdLevel::Avx2;
+            }
+            if is_x86_feature_detected!("sse4.1") && is_x86_feature_detected!("ssse3") {
+                return SimdLevel::Sse41;
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") {
+                return SimdLevel::Neon;
+            }
+        }
+        SimdLevel::Scalar
+    }
+
+    /// every level the cpu supports, scalar included
+    #[cfg(test)]
+    pub(crate) fn supported() -> Vec<Self> {
+        #[allow(unused_mut)]
+        let mut v = vec![SimdLevel::Scalar];
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("sse4.1") && is_x86_feature_detected!("ssse3") {
+                v.push(SimdLevel::Sse41);
+            }
+            if is_x86_feature_detected!("avx2") {
+                v.push(SimdLevel::Avx2);
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") {
+                v.push(SimdLevel::Neon);
+            }
+        }
+        v
+    }
+}
+
+// symbols 62 and 63 of an alphabet the x86 paths can handle
+#[cfg(target_arch = "x86_64")]
+#[inline]
+fn b64_range_tail(encode: &[u8; 64]) -> Option<(u8, u8)> {
+    if encode[..62] == B64_RANGE_PREFIX[..] {
+        Some((encode[62], encode[63]))
+    } else {
+        None
+    }
+}
+
+/// Base64 encodes whole 3 byte quanta from the start of input, without padding or line endings.
+/// returns the number of bytes used, always a multiple of 3.
+#[inline]
+pub(crate) fn b64_encode_prefix(encode: &[u8; 64], input: &[u8], out: &mut Vec<u8>) -> usize {
+    b64_encode_prefix_with(SimdLevel::detect(), encode, input, out)
+}
+
+// not every arch uses every argument
+#[allow(unused_variables)]
+pub(crate) fn b64_encode_prefix_with(
+    level: SimdLevel,
+    encode: &[u8; 64],
+    input: &[u8],
+    out: &mut Vec<u8>,
+) -> usize {
+    match level {
+        SimdLevel::Scalar => 0,
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Sse41 => match b64_range_tail(encode) {
+            // SAFETY: the level says the cpu has the features
+            Some((c62, c63)) => unsafe { b64_encode_sse41(c62, c63, input, out) },
+            None => 0,
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => match b64_range_tail(encode) {
+            // SAFETY: the level says the cpu has the features
+            Some((c62, c63)) => unsafe { b64_encode_avx2(c62, c63, input, out) },
+            None => 0,
+        },
+        // SAFETY: the level says the cpu has the features
+        #[cfg(target_arch = "aarch64")]
+        SimdLevel::Neon => unsafe { b64_encode_neon(encode, input, out) },
+    }
+}
+
+/// Base64 decodes whole 4 symbol quanta from the start of input, stopping at the first block with
+/// anything other than alphabet symbols in it. returns the number of bytes used, always a multiple of 4.
+#[inline]
+pub(crate) fn b64_decode_prefix(
+    encode: &[u8; 64],
+    decode: &[u8; 256],
+    input: &[u8],
+    out: &mut Vec<u8>,
+) -> usize {
+    b64_decode_prefix_with(SimdLevel::detect(), encode, decode, input, out)
+}
+
+// not every arch uses every argument
+#[allow(unused_variables)]
+pub(crate) fn b64_decode_prefix_with(
+    level: SimdLevel,
+    encode: &[u8; 64],
+    decode: &[u8; 256],
+    input: &[u8],
+    out: &mut Vec<u8>,
+) -> usize {
+    match level {
+        SimdLevel::Scalar => 0,
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Sse41 => match b64_range_tail(encode) {
+            // SAFETY: the level says the cpu has the features
+            Some((c62, c63)) => unsafe { b64_decode_sse41(c62, c63, input, out) },
+            None => 0,
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => match b64_range_tail(encode) {
+            // SAFETY: the level says the cpu has the features
+            Some((c62, c63)) => unsafe { b64_decode_avx2(c62, c63, input, out) },
+            None => 0,
+        },
+        // SAFETY: the level says the cpu has the features
+        #[cfg(target_arch = "aarch64")]
+        SimdLevel::Neon => unsafe { b64_decode_neon(decode, input, out) },
+    }
+}
+
+/// hex encodes bytes from the start of input. returns the number of bytes used.
+#[inline]
+pub(crate) fn hex_encode_prefix(input: &[u8], upper: bool, out: &mut Vec<u8>) -> usize {
+    hex_encode_prefix_with(SimdLevel::detect(), input, upper, out)
+}
+
+// not every arch uses every argument
+#[allow(unused_variables)]
+pub(crate) fn hex_encode_prefix_with(
+    level: SimdLevel,
+    input: &[u8],
+    upper: bool,
+    out: &mut Vec<u8>,
+) -> usize {
+    let digits = if upper {
+        b"0123456789ABCDEF"
+    } else {
+        b"0123456789abcdef"
+    };
+    match level {
+        SimdLevel::Scalar => 0,
+        // SAFETY: the level says the cpu has the features
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Sse41 => unsafe { hex_encode_sse41(digits, input, out) },
+        // SAFETY: the level says the cpu has the features
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe { hex_encode_avx2(digits, input, out) },
+        // SAFETY: the level says the cpu has the features
+        #[cfg(target_arch = "aarch64")]
+        SimdLevel::Neon => unsafe { hex_encode_neon(digits, input, out) },
+    }
+}
+
+/// decodes pairs of hex digits (either case) from the start of input, stopping at the first block
+/// with anything else in it. returns the number of bytes used, always a multiple of 2.
+#[inline]
+pub(crate) fn hex_decode_prefix(input: &[u8], out: &mut Vec<u8>) -> usize {
+    hex_decode_prefix_with(SimdLevel::detect(), input, out)
+}
+
+// not every arch uses every argument
+#[allow(unused_variables)]
+pub(crate) fn hex_decode_prefix_with(level: SimdLevel, input: &[u8], out: &mut Vec<u8>) -> usize {
+    match level {
+        SimdLevel::Scalar => 0,
+        // SAFETY: the level says the cpu has the features
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Sse41 => unsafe { hex_decode_sse41(input, out) },
+        // SAFETY: the level says the cpu has the features
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe { hex_decode_avx2(input, out) },
+        // SAFETY: the level says the cpu has the features
+        #[cfg(target_arch = "aarch64")]
+        SimdLevel::Neon => unsafe { hex_decode_neon(input, out) },
+    }
+}
+
+// ---------------------------------------------------------------------------------------------
+// x86_64 SSE4.1 (with SSSE3)
+// ---------------------------------------------------------------------------------------------
+
+// splits the first 12 bytes of x into 16 six bit indices, one per byte
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3,sse4.1")]
+#[inline]
+fn b64_indices_128(x: __m128i) -> __m128i {
+    // each 32 bit lane gets bytes b a c b of its quantum a b c
+    let x = _mm_shuffle_epi8(
+        x,
+        _mm_setr_epi8(1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10, 9, 11, 10),
+    );
+    // move indices 0 and 2 into the low 6 bits of bytes 0 and 2
+    let t0 = _mm_and_si128(x, _mm_set1_epi32(0x0fc0fc00));
+    let t1 = _mm_mulhi_epu16(t0, _mm_set1_epi32(0x04000040));
+    // and indices 1 and 3 into bytes 1 and 3
+    let t2 = _mm_and_si128(x, _mm_set1_epi32(0x003f03f0));
+    let t3 = _mm_mullo_epi16(t2, _mm_set1_epi32(0x01000010));
+    _mm_or_si128(t1, t3)
+}
+
+// six bit indices to symbols, by adding the offset of the range each index is in
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3,sse4.1")]
+#[inline]
+fn b64_symbols_128(idx: __m128i, c62: u8, c63: u8) -> __m128i {
+    let gt = |n: i8| _mm_cmpgt_epi8(idx, _mm_set1_epi8(n));
+    let eq = |n: i8| _mm_cmpeq_epi8(idx, _mm_set1_epi8(n));
+    let mut off = _mm_set1_epi8(b'A' as i8);
+    off = _mm_blendv_epi8(off, _mm_set1_epi8((b'a' - 26) as i8), gt(25));
+    off = _mm_blendv_epi8(off, _mm_set1_epi8(b'0'.wrapping_sub(52) as i8), gt(51));
+    off = _mm_blendv_epi8(off, _mm_set1_epi8(c62.wrapping_sub(62) as i8), eq(62));
+    off = _mm_blendv_epi8(off, _mm_set1_epi8(c63.wrapping_sub(63) as i8), eq(63));
+    _mm_add_epi8(idx, off)
+}
+
+// symbols to six bit indices, or None if any byte is not a symbol
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3,sse4.1")]
+#[inline]
+fn b64_ords_128(x: __m128i, c62: u8, c63: u8) -> Option<__m128i> {
+    // bytes from 0x80 are negative, so they fall outside every range
+    let range = |lo: u8, hi: u8| {
+        _mm_and_si128(
+            _mm_cmpgt_epi8(x, _mm_set1_epi8(lo as i8 - 1)),
+            _mm_cmplt_epi8(x, _mm_set1_epi8(hi as i8 + 1)),
+        )
+    };
+    let upper = range(b'A', b'Z');
+    let lower = range(b'a', b'z');
+    let digit = range(b'0', b'9');
+    let is62 = _mm_cmpeq_epi8(x, _mm_set1_epi8(c62 as i8));
+    let is63 = _mm_cmpeq_epi8(x, _mm_set1_epi8(c63 as i8));
+    let valid = _mm_or_si128(
+        _mm_or_si128(upper, lower),
+        _mm_or_si128(digit, _mm_or_si128(is62, is63)),
+    );
+    if _mm_movemask_epi8(valid) != 0xFFFF {
+        return None;
+    }
+    let mut off = _mm_setzero_si128();
+    off = _mm_blendv_epi8(off, _mm_set1_epi8(b'A'.wrapping_neg() as i8), upper);
+    off = _mm_blendv_epi8(off, _mm_set1_epi8((b'a' - 26).wrapping_neg() as i8), lower);
+    off = _mm_blendv_epi8(off, _mm_set1_epi8(52u8.wrapping_sub(b'0') as i8), digit);
+    off = _mm_blendv_epi8(off, _mm_set1_epi8(62u8.wrapping_sub(c62) as i8), is62);
+    off = _mm_blendv_epi8(off, _mm_set1_epi8(63u8.wrapping_sub(c63) as i8), is63);
+    Some(_mm_add_epi8(x, off))
+}
+
+// 16 six bit indices to 12 bytes, in the low 12 bytes of the result
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3,sse4.1")]
+#[inline]
+fn b64_pack_128(ords: __m128i) -> __m128i {
+    // pairs to 12 bits, then pairs of those to 24 bits per 32 bit lane
+    let m = _mm_maddubs_epi16(ords, _mm_set1_epi32(0x01400140));
+    let m = _mm_madd_epi16(m, _mm_set1_epi32(0x00011000));
+    _mm_shuffle_epi8(
+        m,
+        _mm_setr_epi8(2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1),
+    )
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3,sse4.1")]
+fn b64_encode_sse41(c62: u8, c63: u8, input: &[u8], out: &mut Vec<u8>) -> usize {
+    let mut buf = [0u8; 16];
+    let mut i = 0;
+    // loads are 16 bytes wide, of which 12 are used
+    while i + 16 <= input.len() {
+        // SAFETY: i + 16 <= input.len()
+        let x = unsafe { _mm_loadu_si128(input.as_ptr().add(i).cast()) };
+        let s = b64_symbols_128(b64_indices_128(x), c62, c63);
+        // SAFETY: buf is 16 bytes
+        unsafe { _mm_storeu_si128(buf.as_mut_ptr().cast(), s) };
+        out.extend_from_slice(&buf);
+        i += 12;
+    }
+    i
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3,sse4.1")]
+fn b64_decode_sse41(c62: u8, c63: u8, input: &[u8], out: &mut Vec<u8>) -> usize {
+    let mut buf = [0u8; 16];
+    let mut i = 0;
+    while i + 16 <= input.len() {
+        // SAFETY: i + 16 <= input.len()
+        let x = unsafe { _mm_loadu_si128(input.as_ptr().add(i).cast()) };
+        let Some(ords) = b64_ords_128(x, c62, c63) else {
+            break;
+        };
+        // SAFETY: buf is 16 bytes
+        unsafe { _mm_storeu_si128(buf.as_mut_ptr().cast(), b64_pack_128(ords)) };
+        out.extend_from_slice(&buf[..12]);
+        i += 16;
+    }
+    i
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3,sse4.1")]
+fn hex_encode_sse41(digits: &[u8; 16], input: &[u8], out: &mut Vec<u8>) -> usize {
+    // SAFETY: digits is 16 bytes
+    let table = unsafe { _mm_loadu_si128(digits.as_ptr().cast()) };
+    let mask = _mm_set1_epi8(0x0F);
+    let mut buf = [0u8; 32];
+    let mut i = 0;
+    while i + 16 <= input.len() {
+        // SAFETY: i + 16 <= input.len()
+        let x = unsafe { _mm_loadu_si128(input.as_ptr().add(i).cast()) };
+        let hi = _mm_shuffle_epi8(table, _mm_and_si128(_mm_srli_epi16(x, 4), mask));
+        let lo = _mm_shuffle_epi8(table, _mm_and_si128(x, mask));
+        // SAFETY: buf is 32 bytes
+        unsafe {
+            _mm_storeu_si128(buf.as_mut_ptr().cast(), _mm_unpacklo_epi8(hi, lo));
+            _mm_storeu_si128(buf.as_mut_ptr().add(16).cast(), _mm_unpackhi_epi8(hi, lo));
+        }
+        out.extend_from_slice(&buf);
+        i += 16;
+    }
+    i
+}
+
+// hex digits to nibbles, or None if any byte is not a hex digit
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3,sse4.1")]
+#[inline]
+fn hex_nibbles_128(x: __m128i) -> Option<__m128i> {
+    let range = |x: __m128i, lo: u8, hi: u8| {
+        _mm_and_si128(
+            _mm_cmpgt_epi8(x, _mm_set1_epi8(lo as i8 - 1)),
+            _mm_cmplt_epi8(x, _mm_set1_epi8(hi as i8 + 1)),
+        )
+    };
+    let digit = range(x, b'0', b'9');
+    let lower = _mm_or_si128(x, _mm_set1_epi8(0x20));
+    let alpha = range(lower, b'a', b'f');
+    if _mm_movemask_epi8(_mm_or_si128(digit, alpha)) != 0xFFFF {
+        return None;
+    }
+    Some(_mm_blendv_epi8(
+        _mm_sub_epi8(lower, _mm_set1_epi8((b'a' - 10) as i8)),
+        _mm_sub_epi8(x, _mm_set1_epi8(b'0' as i8)),
+        digit,
+    ))
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3,sse4.1")]
+fn hex_decode_sse41(input: &[u8], out: &mut Vec<u8>) -> usize {
+    // high nibble * 16 + low nibble, for each pair
+    let weights = _mm_set1_epi16(0x0110);
+    let mut buf = [0u8; 16];
+    let mut i = 0;
+    while i + 32 <= input.len() {
+        // SAFETY: i + 32 <= input.len()
+        let (a, b) = unsafe {
+            (
+                _mm_loadu_si128(input.as_ptr().add(i).cast()),
+                _mm_loadu_si128(input.as_ptr().add(i + 16).cast()),
+            )
+        };
+        let (Some(a), Some(b)) = (hex_nibbles_128(a), hex_nibbles_128(b)) else {
+            break;
+        };
+        let r = _mm_packus_epi16(_mm_maddubs_epi16(a, weights), _mm_maddubs_epi16(b, weights));
+        // SAFETY: buf is 16 bytes
+        unsafe { _mm_storeu_si128(buf.as_mut_ptr().cast(), r) };
+        out.extend_from_slice(&buf);
+        i += 32;
+    }
+    i
+}
+
+// ---------------------------------------------------------------------------------------------
+// x86_64 AVX2. the same steps as SSE4.1 on two 128 bit lanes at once.
+// ---------------------------------------------------------------------------------------------
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+fn b64_encode_avx2(c62: u8, c63: u8, input: &[u8], out: &mut Vec<u8>) -> usize {
+    let mut buf = [0u8; 32];
+    let mut i = 0;
+    // each lane loads 16 bytes, of which 12 are used
+    while i + 28 <= input.len() {
+        // SAFETY: i + 12 + 16 <= input.len()
+        let x = unsafe {
+            _mm256_set_m128i(
+                _mm_loadu_si128(input.as_ptr().add(i + 12).cast()),
+                _mm_loadu_si128(input.as_ptr().add(i).cast()),
+            )
+        };
+        let x = _mm256_shuffle_epi8(
+            x,
+            _mm256_setr_epi8(
+                1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10, 9, 11, 10, 1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8,
+                7, 10, 9, 11, 10,
+            ),
+        );
+        let t0 = _mm256_and_si256(x, _mm256_set1_epi32(0x0fc0fc00));
+        let t1 = _mm256_mulhi_epu16(t0, _mm256_set1_epi32(0x04000040));
+        let t2 = _mm256_and_si256(x, _mm256_set1_epi32(0x003f03f0));
+        let t3 = _mm256_mullo_epi16(t2, _mm256_set1_epi32(0x01000010));
+        let idx = _mm256_or_si256(t1, t3);
+
+        let gt = |n: i8| _mm256_cmpgt_epi8(idx, _mm256_set1_epi8(n));
+        let eq = |n: i8| _mm256_cmpeq_epi8(idx, _mm256_set1_epi8(n));
+        let mut off = _mm256_set1_epi8(b'A' as i8);
+        off = _mm256_blendv_epi8(off, _mm256_set1_epi8((b'a' - 26) as i8), gt(25));
+        off = _mm256_blendv_epi8(off, _mm256_set1_epi8(b'0'.wrapping_sub(52) as i8), gt(51));
+        off = _mm256_blendv_epi8(off, _mm256_set1_epi8(c62.wrapping_sub(62) as i8), eq(62));
+        off = _mm256_blendv_epi8(off, _mm256_set1_epi8(c63.wrapping_sub(63) as i8), eq(63));
+
+        // SAFETY: buf is 32 bytes
+        unsafe { _mm256_storeu_si256(buf.as_mut_ptr().cast(), _mm256_add_epi8(idx, off)) };
+        out.extend_from_slice(&buf);
+        i += 24;
+    }
+    i
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+fn b64_decode_avx2(c62: u8, c63: u8, input: &[u8], out: &mut Vec<u8>) -> usize {
+    let mut buf = [0u8; 32];
+    let mut i = 0;
+    while i + 32 <= input.len() {
+        // SAFETY: i + 32 <= input.len()
+        let x = unsafe { _mm256_loadu_si256(input.as_ptr().add(i).cast()) };
+        let range = |lo: u8, hi: u8| {
+            _mm256_and_si256(
+                _mm256_cmpgt_epi8(x, _mm256_set1_epi8(lo as i8 - 1)),
+                _mm256_cmpgt_epi8(_mm256_set1_epi8(hi as i8 + 1), x),
+            )
+        };
+        let upper = range(b'A', b'Z');
+        let lower = range(b'a', b'z');
+        let digit = range(b'0', b'9');
+        let is62 = _mm256_cmpeq_epi8(x, _mm256_set1_epi8(c62 as i8));
+        let is63 = _mm256_cmpeq_epi8(x, _mm256_set1_epi8(c63 as i8));
+        let valid = _mm256_or_si256(
+            _mm256_or_si256(upper, lower),
+            _mm256_or_si256(digit, _mm256_or_si256(is62, is63)),
+        );
+        if _mm256_movemask_epi8(valid) != -1 {
+            break;
+        }
+        let mut off = _mm256_setzero_si256();
+        off = _mm256_blendv_epi8(off, _mm256_set1_epi8(b'A'.wrapping_neg() as i8), upper);
+        off = _mm256_blendv_epi8(
+            off,
+            _mm256_set1_epi8((b'a' - 26).wrapping_neg() as i8),
+            lower,
+        );
+        off = _mm256_blendv_epi8(off, _mm256_set1_epi8(52u8.wrapping_sub(b'0') as i8), digit);
+        off = _mm256_blendv_epi8(off, _mm256_set1_epi8(62u8.wrapping_sub(c62) as i8), is62);
+        off = _mm256_blendv_epi8(off, _mm256_set1_epi8(63u8.wrapping_sub(c63) as i8), is63);
+        let ords = _mm256_add_epi8(x, off);
+
+        let m = _mm256_maddubs_epi16(ords, _mm256_set1_epi32(0x01400140));
+        let m = _mm256_madd_epi16(m, _mm256_set1_epi32(0x00011000));
+        let m = _mm256_shuffle_epi8(
+            m,
+            _mm256_setr_epi8(
+                2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1, 2, 1, 0, 6, 5, 4, 10, 9, 8,
+                14, 13, 12, -1, -1, -1, -1,
+            ),
+        );
+        // SAFETY: buf is 32 bytes
+        unsafe { _mm256_storeu_si256(buf.as_mut_ptr().cast(), m) };
+        // 12 bytes from each lane
+        out.extend_from_slice(&buf[..12]);
+        out.extend_from_slice(&buf[16..28]);
+        i += 32;
+    }
+    i
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+fn hex_encode_avx2(digits: &[u8; 16], input: &[u8], out: &mut Vec<u8>) -> usize {
+    // SAFETY: digits is 16 bytes
+    let table = _mm256_broadcastsi128_si256(unsafe { _mm_loadu_si128(digits.as_ptr().cast()) });
+    let mask = _mm256_set1_epi8(0x0F);
+    let mut buf = [0u8; 64];
+    let mut i = 0;
+    while i + 32 <= input.len() {
+        // SAFETY: i + 32 <= input.len()
+        let x = unsafe { _mm256_loadu_si256(input.as_ptr().add(i).cast()) };
+        let hi = _mm256_shuffle_epi8(table, _mm256_and_si256(_mm256_srli_epi16(x, 4), mask));
+        let lo = _mm256_shuffle_epi8(table, _mm256_and_si256(x, mask));
+        // unpack works within lanes, so put the lane halves back in order
+        let a = _mm256_unpacklo_epi8(hi, lo);
+        let b = _mm256_unpackhi_epi8(hi, lo);
+        // SAFETY: buf is 64 bytes
+        unsafe {
+            _mm256_storeu_si256(
+                buf.as_mut_ptr().cast(),
+                _mm256_permute2x128_si256(a, b, 0x20),
+            );
+            _mm256_storeu_si256(
+                buf.as_mut_ptr().add(32).cast(),
+                _mm256_permute2x128_si256(a, b, 0x31),
+            );
+        }
+        out.extend_from_slice(&buf);
+        i += 32;
+    }
+    i
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+fn hex_nibbles_256(x: __m256i) -> Option<__m256i> {
+    let range = |x: __m256i, lo: u8, hi: u8| {
+        _mm256_and_si256(
+            _mm256_cmpgt_epi8(x, _mm256_set1_epi8(lo as i8 - 1)),
+            _mm256_cmpgt_epi8(_mm256_set1_epi8(hi as i8 + 1), x),
+        )
+    };
+    let digit = range(x, b'0', b'9');
+    let lower = _mm256_or_si256(x, _mm256_set1_epi8(0x20));
+    let alpha = range(lower, b'a', b'f');
+    if _mm256_movemask_epi8(_mm256_or_si256(digit, alpha)) != -1 {
+        return None;
+    }
+    Some(_mm256_blendv_epi8(
+        _mm256_sub_epi8(lower, _mm256_set1_epi8((b'a' - 10) as i8)),
+        _mm256_sub_epi8(x, _mm256_set1_epi8(b'0' as i8)),
+        digit,
+    ))
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+fn hex_decode_avx2(input: &[u8], out: &mut Vec<u8>) -> usize {
+    let weights = _mm256_set1_epi16(0x0110);
+    let mut buf = [0u8; 32];
+    let mut i = 0;
+    while i + 64 <= input.len() {
+        // SAFETY: i + 64 <= input.len()
+        let (a, b) = unsafe {
+            (
+                _mm256_loadu_si256(input.as_ptr().add(i).cast()),
+                _mm256_loadu_si256(input.as_ptr().add(i + 32).cast()),
+            )
+        };
+        let (Some(a), Some(b)) = (hex_nibbles_256(a), hex_nibbles_256(b)) else {
+            break;
+        };
+        let r = _mm256_packus_epi16(
+            _mm256_maddubs_epi16(a, weights),
+            _mm256_maddubs_epi16(b, weights),
+        );
+        // pack works within lanes, giving a0 b0 a1 b1
+        let r = _mm256_permute4x64_epi64(r, 0xD8);
+        // SAFETY: buf is 32 bytes
+        unsafe { _mm256_storeu_si256(buf.as_mut_ptr().cast(), r) };
+        out.extend_from_slice(&buf);
+        i += 64;
+    }
+    i
+}
+
+// ---------------------------------------------------------------------------------------------
+// aarch64 NEON. the structured loads and stores split quanta into one vector per position, and
+// the 64 byte table lookups handle any alphabet.
+// ---------------------------------------------------------------------------------------------
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+fn b64_encode_neon(encode: &[u8; 64], input: &[u8], out: &mut Vec<u8>) -> usize {
+    // SAFETY: encode is 64 bytes
+    let table = unsafe { vld1q_u8_x4(encode.as_ptr()) };
+    let mut buf = [0u8; 64];
+    let mut i = 0;
+    while i + 48 <= input.len() {
+        // SAFETY: i + 48 <= input.len()
+        let x = unsafe { vld3q_u8(input.as_ptr().add(i)) };
+        let i0 = vshrq_n_u8(x.0, 2);
+        let i1 = vorrq_u8(
+            vshlq_n_u8(vandq_u8(x.0, vdupq_n_u8(0x03)), 4),
+            vshrq_n_u8(x.1, 4),
+        );
+        let i2 = vorrq_u8(
+            vshlq_n_u8(vandq_u8(x.1, vdupq_n_u8(0x0F)), 2),
+            vshrq_n_u8(x.2, 6),
+        );
+        let i3 = vandq_u8(x.2, vdupq_n_u8(0x3F));
+        let s = uint8x16x4_t(
+            vqtbl4q_u8(table, i0),
+            vqtbl4q_u8(table, i1),
+            vqtbl4q_u8(table, i2),
+            vqtbl4q_u8(table, i3),
+        );
+        // SAFETY: buf is 64 bytes
+        unsafe { vst4q_u8(buf.as_mut_ptr(), s) };
+        out.extend_from_slice(&buf);
+        i += 48;
+    }
+    i
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+fn b64_decode_neon(decode: &[u8; 256], input: &[u8], out: &mut Vec<u8>) -> usize {
+    // the ASCII half of the decode table, in two 64 byte lookups
+    // SAFETY: decode is 256 bytes
+    let (lo, hi) = unsafe {
+        (
+            vld1q_u8_x4(decode.as_ptr()),
+            vld1q_u8_x4(decode.as_ptr().add(64)),
+        )
+    };
+    // ords are below 64, and bytes from 0x80 are not in the lookups at all
+    let ord = |c: uint8x16_t| {
+        let o = vqtbx4q_u8(vqtbl4q_u8(lo, c), hi, vsubq_u8(c, vdupq_n_u8(64)));
+        let bad = vorrq_u8(vcgeq_u8(o, vdupq_n_u8(64)), vcgeq_u8(c, vdupq_n_u8(0x80)));
+        (o, bad)
+    };
+    let mut buf = [0u8; 48];
+    let mut i = 0;
+    while i + 64 <= input.len() {
+        // SAFETY: i + 64 <= input.len()
+        let x = unsafe { vld4q_u8(input.as_ptr().add(i)) };
+        let (a, bad_a) = ord(x.0);
+        let (b, bad_b) = ord(x.1);
+        let (c, bad_c) = ord(x.2);
+        let (d, bad_d) = ord(x.3);
+        if vmaxvq_u8(vorrq_u8(vorrq_u8(bad_a, bad_b), vorrq_u8(bad_c, bad_d))) != 0 {
+            break;
+        }
+        let r = uint8x16x3_t(
+            vorrq_u8(vshlq_n_u8(a, 2), vshrq_n_u8(b, 4)),
+            vorrq_u8(vshlq_n_u8(b, 4), vshrq_n_u8(c, 2)),
+            vorrq_u8(vshlq_n_u8(c, 6), d),
+        );
+        // SAFETY: buf is 48 bytes
+        unsafe { vst3q_u8(buf.as_mut_ptr(), r) };
+        out.extend_from_slice(&buf);
+        i += 64;
+    }
+    i
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+fn hex_encode_neon(digits: &[u8; 16], input: &[u8], out: &mut Vec<u8>) -> usize {
+    // SAFETY: digits is 16 bytes
+    let table = unsafe { vld1q_u8(digits.as_ptr()) };
+    let mut buf = [0u8; 32];
+    let mut i = 0;
+    while i + 16 <= input.len() {
+        // SAFETY: i + 16 <= input.len()
+        let x = unsafe { vld1q_u8(input.as_ptr().add(i)) };
+        let r = uint8x16x2_t(
+            vqtbl1q_u8(table, vshrq_n_u8(x, 4)),
+            vqtbl1q_u8(table, vandq_u8(x, vdupq_n_u8(0x0F))),
+        );
+        // SAFETY: buf is 32 bytes
+        unsafe { vst2q_u8(buf.as_mut_ptr(), r) };
+        out.extend_from_slice(&buf);
+        i += 16;
+    }
+    i
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+fn hex_decode_neon(input: &[u8], out: &mut Vec<u8>) -> usize {
+    let nibble = |c: uint8x16_t| {
+        let d = vsubq_u8(c, vdupq_n_u8(b'0'));
+        let a = vsubq_u8(vorrq_u8(c, vdupq_n_u8(0x20)), vdupq_n_u8(b'a'));
+        let is_d = vcltq_u8(d, vdupq_n_u8(10));
+        let is_a = vcltq_u8(a, vdupq_n_u8(6));
+        let v = vbslq_u8(is_d, d, vaddq_u8(a, vdupq_n_u8(10)));
+        (v, vmvnq_u8(vorrq_u8(is_d, is_a)))
+    };
+    let mut buf = [0u8; 16];
+    let mut i = 0;
+    while i + 32 <= input.len() {
+        // SAFETY: i + 32 <= input.len()
+        let x = unsafe { vld2q_u8(input.as_ptr().add(i)) };
+        let (hi, bad_hi) = nibble(x.0);
+        let (lo, bad_lo) = nibble(x.1);
+        if vmaxvq_u8(vorrq_u8(bad_hi, bad_lo)) != 0 {
+            break;
+        }
+        // SAFETY: buf is 16 bytes
+        unsafe { vst1q_u8(buf.as_mut_ptr(), vorrq_u8(vshlq_n_u8(hi, 4), lo)) };
+        out.extend_from_slice(&buf);
+        i += 32;
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cj_binary::b64::{B64Alphabet, B64Config};
+
+    // simple repeatable noise, no need for a rand dependency
+    fn noise(len: usize, seed: u32) -> Vec<u8> {
+        let mut x = seed.wrapping_mul(2654435761).wrapping_add(1);
+        (0..len)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                (x >> 8) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect() {
+        assert!(SimdLevel::supported().contains(&SimdLevel::detect()));
+    }
+
+    #[test]
+    fn test_b64_encode_prefix() {
+        let url = B64Alphabet::URL_SAFE;
+        let bcrypt =
+            B64Alphabet::new("./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789")
+                .unwrap();
+        for level in SimdLevel::supported() {
+            for len in 0..300 {
+                let data = noise(len, len as u32);
+                for alphabet in [&B64Alphabet::STANDARD, &url, &bcrypt] {
+                    let mut out = Vec::new();
+                    let n = b64_encode_prefix_with(level, &alphabet.encode, &data, &mut out);
+                    assert_eq!(n % 3, 0);
+                    let expected = B64Config::new(*alphabet).with_padding(false);
+                    let expected = expected_encode(&expected, &data[..n]);
+                    assert_eq!(out, expected.as_bytes(), "{level:?} {len}");
+                }
+            }
+        }
+    }
+
+    // scalar only encoding, going through the iterator
+    fn expected_encode(cfg: &B64Config, data: &[u8]) -> String {
+        cfg.iter_encode(data).collect()
+    }
+
+    #[test]
+    fn test_b64_decode_prefix() {
+        let cfg = B64Config::STANDARD.with_padding(false);
+        for level in SimdLevel::supported() {
+            for len in 0..300 {
+                let data = noise(len, len as u32 + 7);
+                let enc = expected_encode(&cfg, &data);
+                let a = B64Alphabet::STANDARD;
+                let mut out = Vec::new();
+                let n =
+                    b64_decode_prefix_with(level, &a.encode, &a.decode, enc.as_bytes(), &mut out);
+                assert_eq!(n % 4, 0);
+                assert_eq!(out.as_slice(), &data[..n / 4 * 3], "{level:?} {len}");
+
+                // anything but a symbol stops the fast path before its block
+                for bad in [b'=', b'\n', b'-', 0xC3, 0x00] {
+                    for pos in (0..enc.len()).step_by(7) {
+                        let mut e = enc.as_bytes().to_vec();
+                        e[pos] = bad;
+                        let mut out = Vec::new();
+                        let n = b64_decode_prefix_with(level, &a.encode, &a.decode, &e, &mut out);
+                        assert!(n <= pos);
+                        assert_eq!(out.as_slice(), &data[..n / 4 * 3]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_encode_prefix() {
+        for level in SimdLevel::supported() {
+            for len in 0..200 {
+                let data = noise(len, len as u32 + 3);
+                for upper in [true, false] {
+                    let mut out = Vec::new();
+                    let n = hex_encode_prefix_with(level, &data, upper, &mut out);
+                    let expected = data[..n]
+                        .iter()
+                        .map(|b| {
+                            if upper {
+                                format!("{b:02X}")
+                            } else {
+                                format!("{b:02x}")
+                            }
+                        })
+                        .collect::<String>();
+                    assert_eq!(out, expected.as_bytes(), "{level:?} {len}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_decode_prefix() {
+        for level in SimdLevel::supported() {
+            for len in 0..200 {
+                let data = noise(len, len as u32 + 5);
+                let enc = data
+                    .iter()
+                    .enumerate()
+                    .map(|(i, b)| {
+                        if i % 2 == 0 {
+                            format!("{b:02X}")
+                        } else {
+                            format!("{b:02x}")
+                        }
+                    })
+                    .collect::<String>();
+                let mut out = Vec::new();
+                let n = hex_decode_prefix_with(level, enc.as_bytes(), &mut out);
+                assert_eq!(n % 2, 0);
+                assert_eq!(out.as_slice(), &data[..n / 2], "{level:?} {len}");
+
+                for bad in [b'g', b'G', b'/', b':', b'@', b'`', b' ', 0xC3] {
+                    for pos in (0..enc.len()).step_by(5) {
+                        let mut e = enc.as_bytes().to_vec();
+                        e[pos] = bad;
+                        let mut out = Vec::new();
+                        let n = hex_decode_prefix_with(level, &e, &mut out);
+                        assert!(n <= pos);
+                        assert_eq!(out.as_slice(), &data[..n / 2]);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,6 @@
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 
-
 /// A small convenience enum that abstracts over several Tokio channel
 /// transmitters (TX):
 /// - `OneShot` — a single-use `oneshot::Sender<T>` wrapped in an `Option` so it
@@ -363,6 +363,7 @@ impl CjIsAscii for char {
 }
 
 #[cfg(test)]
+#[allow(clippy::almost_complete_range)] // exclusive ranges are tested on purpose
 pub mod test {
     use super::*;
 
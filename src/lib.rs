@@ -2,7 +2,17 @@
 //!
 //! Current features relate to:
 //! * Base64 encoding/decoding
-//! * Hex encoding/decoding
+//! * Base32 encoding/decoding (standard and extended hex alphabets)
+//! * Base58 and Base58Check encoding/decoding
+//! * Ascii85 and Z85 encoding/decoding
+//! * Base62, Base36 and any other radix from 2 to 256, for integers and byte strings
+//! * PEM armor reading/writing
+//! * Hex encoding/decoding, with optional prefixes, separators and line wrapping, and `std::io` streaming
+//! * Hex formatting of every primitive, with optional minimal width, `0x`, sign and digit grouping
+//! * Hex dumps in the style of `xxd` and `hexdump -C`
+//! * Intel HEX and Motorola S-record firmware files, read into an address-sparse memory image
+//! * Constant-time hex and Base64 decoding, and slice equality, for secret material
+//! * `Encoding`, a trait shared by all of the above for code generic over the encoding used
 //! * Bit manipulation
 //! * In-set checking (values within a set of ranges)
 //! * Time utilities (feature: `timext`) — `OffsetDateTimeExt` with helpers like `to_primitive()`
@@ -141,7 +151,7 @@
 //! }
 //! ```
 
-/// b64, hex and bitbuf
+/// b64, b32, b58, b85, radix, hex, hexfile, pem and bitbuf
 pub mod cj_binary;
 
 /// in_set
@@ -149,9 +159,17 @@ pub mod cj_helpers;
 
 /// easiest way to import all functionality
 pub mod prelude {
+    pub use crate::cj_binary::b32::*;
+    pub use crate::cj_binary::b58::*;
     pub use crate::cj_binary::b64::*;
+    pub use crate::cj_binary::b85::*;
     pub use crate::cj_binary::bitbuf::*;
+    pub use crate::cj_binary::ct::*;
+    pub use crate::cj_binary::encoding::*;
     pub use crate::cj_binary::hex::*;
+    pub use crate::cj_binary::hexfile::*;
+    pub use crate::cj_binary::pem::*;
+    pub use crate::cj_binary::radix::*;
     pub use crate::cj_helpers::in_set::*;
 }
 